@@ -0,0 +1,465 @@
+//!Asynchronous WAV and raw PCM reading and writing over tokio, and a stream of blocks.
+
+use crate::format::*;
+use crate::sample::*;
+use futures_core::Stream;
+use std::io::{Error, ErrorKind, Result, SeekFrom};
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use tokio::io::{
+    AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt, AsyncWrite, AsyncWriteExt, ReadBuf,
+};
+
+//reads up to n bytes, fewer only at the end of input. The buffer grows only as bytes arrive, as n may come from untrusted input.
+async fn read_up_to<R: AsyncRead + Unpin>(r: &mut R, n: usize) -> Result<Vec<u8>> {
+    let mut v = Vec::new();
+    (&mut *r).take(n as u64).read_to_end(&mut v).await?;
+    Ok(v)
+}
+
+//reads frames of byte size, dropping a trailing partial frame.
+async fn read_frames<R: AsyncRead + Unpin>(
+    r: &mut R,
+    byte_size: usize,
+    frames: usize,
+) -> Result<Option<Vec<u8>>> {
+    let mut v = read_up_to(r, frames * byte_size).await?;
+    v.truncate(v.len() - v.len() % byte_size.max(1));
+    Ok((!v.is_empty()).then_some(v))
+}
+
+//returns bytes in little-endian byte order.
+fn to_le(o: &ByteBlock) -> Vec<u8> {
+    let width = o.byte_size() / (o.channel_size().max(1) as usize);
+    let mut v = o.to_vec();
+    if o.is_big_endian() && width > 1 {
+        for i in v.chunks_exact_mut(width) {
+            i.reverse();
+        }
+    }
+    v
+}
+
+///A reader of raw PCM frames.
+#[derive(Debug)]
+pub struct PcmReader<R> {
+    inner: R,
+    channel_size: u16,
+    byte_size: usize,
+    big_endian: bool,
+}
+
+impl<R: AsyncRead + Unpin> PcmReader<R> {
+    ///Constructs a new PcmReader of frames of byte size.
+    pub fn new(inner: R, channel_size: u16, byte_size: usize, big_endian: bool) -> Self {
+        Self {
+            inner,
+            channel_size,
+            byte_size,
+            big_endian,
+        }
+    }
+
+    ///Reads up to frames. Returns None at the end of input, a trailing partial frame is dropped.
+    pub async fn read(&mut self, frames: usize) -> Result<Option<ByteBlock>> {
+        let v = read_frames(&mut self.inner, self.byte_size, frames).await?;
+        Ok(v.map(|v| ByteBlock::new(self.channel_size, self.byte_size, self.big_endian, v)))
+    }
+
+    ///Returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+///A writer of raw PCM bytes.
+#[derive(Debug)]
+pub struct PcmWriter<W> {
+    inner: W,
+}
+
+impl<W: AsyncWrite + Unpin> PcmWriter<W> {
+    ///Constructs a new PcmWriter.
+    pub fn new(inner: W) -> Self {
+        Self { inner }
+    }
+
+    ///Writes bytes of byte block as they are.
+    pub async fn write(&mut self, o: &ByteBlock) -> Result<()> {
+        self.inner.write_all(o).await
+    }
+
+    ///Flushes and returns the inner writer.
+    pub async fn finish(mut self) -> Result<W> {
+        self.inner.flush().await?;
+        Ok(self.inner)
+    }
+}
+
+pub use crate::wav::WavSpec;
+
+fn invalid(msg: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, msg.to_string())
+}
+
+///A reader of WAV, which reads the data chunk as AsyncRead.
+#[derive(Debug)]
+pub struct WavReader<R> {
+    inner: R,
+    spec: WavSpec,
+    remaining: Option<u64>,
+}
+
+impl<R: AsyncRead + Unpin> WavReader<R> {
+    ///Constructs a new WavReader, reading headers up to the data chunk.
+    pub async fn new(mut inner: R) -> Result<Self> {
+        let mut h = [0u8; 12];
+        inner.read_exact(&mut h).await?;
+        if &h[..4] != b"RIFF" || &h[8..] != b"WAVE" {
+            return Err(invalid("not a RIFF WAVE"));
+        }
+        let mut spec = None;
+        loop {
+            let mut c = [0u8; 8];
+            inner.read_exact(&mut c).await?;
+            let size = u32::from_le_bytes([c[4], c[5], c[6], c[7]]);
+            match &c[..4] {
+                b"fmt " => {
+                    let n = size as u64 + (size & 1) as u64;
+                    if n > crate::wav::MAX_FMT {
+                        return Err(invalid("oversized fmt chunk"));
+                    }
+                    let f = read_up_to(&mut inner, n as usize).await?;
+                    spec = Some(crate::wav::parse_fmt(&f)?);
+                }
+                b"data" => {
+                    let spec = spec.ok_or_else(|| invalid("data before fmt chunk"))?;
+                    return Ok(Self {
+                        inner,
+                        spec,
+                        //0 and u32::MAX are written by streaming writers.
+                        remaining: (size != 0 && size != u32::MAX).then_some(size as u64),
+                    });
+                }
+                _ => {
+                    let n = size as u64 + (size & 1) as u64;
+                    let k =
+                        tokio::io::copy(&mut (&mut inner).take(n), &mut tokio::io::sink()).await?;
+                    if k < n {
+                        return Err(ErrorKind::UnexpectedEof.into());
+                    }
+                }
+            }
+        }
+    }
+
+    ///Returns format.
+    pub fn spec(&self) -> WavSpec {
+        self.spec
+    }
+
+    ///Reads up to frames in little-endian byte order. Returns None at the end of data.
+    pub async fn read(&mut self, frames: usize) -> Result<Option<ByteBlock>> {
+        let s = self.spec;
+        let v = read_frames(self, s.byte_size(), frames).await?;
+        Ok(v.map(|v| ByteBlock::new(s.channel_size, s.byte_size(), false, v)))
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for WavReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<Result<()>> {
+        let n = match self.remaining {
+            Some(0) => return Poll::Ready(Ok(())),
+            Some(r) => buf.remaining().min(r as usize),
+            None => buf.remaining(),
+        };
+        let mut b = ReadBuf::new(buf.initialize_unfilled_to(n));
+        ready!(Pin::new(&mut self.inner).poll_read(cx, &mut b))?;
+        let k = b.filled().len();
+        buf.advance(k);
+        if let Some(r) = self.remaining.as_mut() {
+            *r -= k as u64;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+///A writer of WAV.
+#[derive(Debug)]
+pub struct WavWriter<W> {
+    inner: W,
+    spec: WavSpec,
+    written: u64,
+}
+
+impl<W: AsyncWrite + Unpin> WavWriter<W> {
+    ///Constructs a new WavWriter, writing headers of unknown length. Fails with Error::Unsupported if the byte rate or frame size does not fit the fmt chunk.
+    pub async fn new(mut inner: W, spec: WavSpec) -> Result<Self> {
+        let unsupported = |msg| Error::from(crate::error::Error::Unsupported(msg));
+        let block_align =
+            u16::try_from(spec.byte_size()).map_err(|_| unsupported("WAV frame size"))?;
+        let byte_rate = spec
+            .sample_rate
+            .checked_mul(block_align as u32)
+            .ok_or_else(|| unsupported("WAV byte rate"))?;
+        let mut h = Vec::with_capacity(44);
+        h.extend_from_slice(b"RIFF");
+        h.extend_from_slice(&u32::MAX.to_le_bytes());
+        h.extend_from_slice(b"WAVEfmt ");
+        h.extend_from_slice(&16u32.to_le_bytes());
+        h.extend_from_slice(&(if spec.float { 3u16 } else { 1 }).to_le_bytes());
+        h.extend_from_slice(&spec.channel_size.to_le_bytes());
+        h.extend_from_slice(&spec.sample_rate.to_le_bytes());
+        h.extend_from_slice(&byte_rate.to_le_bytes());
+        h.extend_from_slice(&block_align.to_le_bytes());
+        h.extend_from_slice(&spec.bits.to_le_bytes());
+        h.extend_from_slice(b"data");
+        h.extend_from_slice(&u32::MAX.to_le_bytes());
+        inner.write_all(&h).await?;
+        Ok(Self {
+            inner,
+            spec,
+            written: 0,
+        })
+    }
+
+    ///Writes frames of byte block, swapping big-endian data to little-endian.
+    pub async fn write(&mut self, o: &ByteBlock) -> Result<()> {
+        if o.channel_size() != self.spec.channel_size || o.byte_size() != self.spec.byte_size() {
+            return Err(Error::new(ErrorKind::InvalidInput, "format mismatch"));
+        }
+        let v = to_le(o);
+        self.inner.write_all(&v).await?;
+        self.written += v.len() as u64;
+        Ok(())
+    }
+
+    ///Flushes and returns the inner writer, leaving lengths unknown for streaming.
+    pub async fn finish(mut self) -> Result<W> {
+        self.inner.flush().await?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: AsyncWrite + AsyncSeek + Unpin> WavWriter<W> {
+    ///Pads data to even length, writes the lengths into headers, flushes and returns the inner writer. Fails with Error::Unsupported if the RIFF chunk would exceed u32::MAX bytes.
+    pub async fn finish_seekable(mut self) -> Result<W> {
+        let data = u32::try_from(self.written)
+            .map_err(|_| Error::new(ErrorKind::InvalidInput, "data too long for WAV"))?;
+        //the data chunk, its padding and the headers must fit the u32 size of the RIFF chunk.
+        let riff = (data & 1)
+            .checked_add(36)
+            .and_then(|o| o.checked_add(data))
+            .ok_or(crate::error::Error::Unsupported("file too large"))?;
+        if data & 1 == 1 {
+            self.inner.write_all(&[0]).await?;
+        }
+        self.inner.seek(SeekFrom::Start(4)).await?;
+        self.inner.write_all(&riff.to_le_bytes()).await?;
+        self.inner.seek(SeekFrom::Start(40)).await?;
+        self.inner.write_all(&data.to_le_bytes()).await?;
+        self.inner.seek(SeekFrom::End(0)).await?;
+        self.inner.flush().await?;
+        Ok(self.inner)
+    }
+}
+
+///A stream of blocks of frames decoded from bytes of AsyncRead by a closure, such as `|b| i16::from_le_bytes([b[0], b[1]])`.
+///
+///A trailing partial frame at the end of input is dropped.
+pub struct BlockStream<R, T, F> {
+    inner: R,
+    f: F,
+    byte_size: usize,
+    buf: Vec<u8>,
+    filled: usize,
+    done: bool,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<R, T, F> std::fmt::Debug for BlockStream<R, T, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("BlockStream");
+        f.field("byte_size", &self.byte_size)
+            .field("frames", &(self.buf.len() / self.byte_size))
+            .field("done", &self.done)
+            .finish()
+    }
+}
+
+impl<R, T, F> BlockStream<R, T, F>
+where
+    R: AsyncRead + Unpin,
+    T: Sample,
+    F: FnMut(&[u8]) -> T + Unpin,
+{
+    ///Constructs a new BlockStream of up to frames per block, each frame of byte size.
+    pub fn new(inner: R, byte_size: usize, frames: usize, f: F) -> Self {
+        let byte_size = byte_size.max(1);
+        Self {
+            inner,
+            f,
+            byte_size,
+            buf: vec![0; byte_size * frames.max(1)],
+            filled: 0,
+            done: false,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<R, T, F> Stream for BlockStream<R, T, F>
+where
+    R: AsyncRead + Unpin,
+    T: Sample,
+    F: FnMut(&[u8]) -> T + Unpin,
+{
+    type Item = Result<Block<T>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        while !this.done && this.filled < this.buf.len() {
+            let mut b = ReadBuf::new(&mut this.buf[this.filled..]);
+            if let Err(e) = ready!(Pin::new(&mut this.inner).poll_read(cx, &mut b)) {
+                return Poll::Ready(Some(Err(e)));
+            }
+            match b.filled().len() {
+                0 => this.done = true,
+                n => this.filled += n,
+            }
+        }
+        let n = this.filled - this.filled % this.byte_size;
+        this.filled = 0;
+        if n == 0 {
+            return Poll::Ready(None);
+        }
+        let v: Vec<T> = this.buf[..n]
+            .chunks_exact(this.byte_size)
+            .map(&mut this.f)
+            .collect();
+        Poll::Ready(Some(Ok(Block::from(v))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::future::Future;
+    use std::io::Cursor;
+
+    //polls a future of in-memory io, which never waits, to completion.
+    fn block_on<F: Future>(f: F) -> F::Output {
+        let mut f = std::pin::pin!(f);
+        let mut cx = Context::from_waker(std::task::Waker::noop());
+        loop {
+            if let Poll::Ready(o) = f.as_mut().poll(&mut cx) {
+                return o;
+            }
+        }
+    }
+
+    fn stereo() -> WavSpec {
+        WavSpec {
+            sample_rate: 48000,
+            channel_size: 2,
+            bits: 16,
+            float: false,
+        }
+    }
+
+    fn frames() -> Block<[i16; 2]> {
+        Block::from(vec![[0, -1], [i16::MIN, i16::MAX], [1234, -4321]])
+    }
+
+    fn write(spec: WavSpec, o: &ByteBlock) -> Vec<u8> {
+        block_on(async {
+            let mut w = WavWriter::new(Cursor::new(Vec::new()), spec).await.unwrap();
+            w.write(o).await.unwrap();
+            w.finish_seekable().await.unwrap().into_inner()
+        })
+    }
+
+    #[test]
+    fn wav_round_trip() {
+        let o = ByteBlock::from_block(false, &frames());
+        let v = write(stereo(), &o);
+        assert_eq!(v.len(), 44 + 12);
+        assert_eq!(v[4..8], 48u32.to_le_bytes());
+        assert_eq!(v[40..44], 12u32.to_le_bytes());
+        assert_eq!(
+            crate::wav::read_header(&mut &v[..]).unwrap(),
+            (stereo(), Some(12))
+        );
+        let back = block_on(async {
+            let mut r = WavReader::new(&v[..]).await.unwrap();
+            assert_eq!(r.spec(), stereo());
+            let o = r.read(16).await.unwrap().unwrap();
+            assert!(r.read(16).await.unwrap().is_none());
+            o
+        });
+        assert_eq!(back[..], o[..]);
+    }
+
+    #[test]
+    fn wav_writer_swaps_big_endian() {
+        let le = ByteBlock::from_block(false, &frames());
+        let be = ByteBlock::from_block(true, &frames());
+        assert_eq!(write(stereo(), &be), write(stereo(), &le));
+    }
+
+    #[test]
+    fn wav_pads_odd_data() {
+        let spec = WavSpec {
+            sample_rate: 8000,
+            channel_size: 1,
+            bits: 8,
+            float: false,
+        };
+        let v = write(spec, &ByteBlock::new(1, 1, false, vec![1, 128, 255]));
+        assert_eq!(v.len(), 44 + 4);
+        assert_eq!(v[4..8], 40u32.to_le_bytes());
+        assert_eq!(v[40..44], 3u32.to_le_bytes());
+        let back = block_on(async {
+            let mut r = WavReader::new(&v[..]).await.unwrap();
+            r.read(16).await.unwrap().unwrap()
+        });
+        assert_eq!(back[..], [1, 128, 255]);
+    }
+
+    #[test]
+    fn wav_read_whole() {
+        let v = write(stereo(), &ByteBlock::from_block(false, &frames()));
+        let path = std::env::temp_dir().join(format!("bssf-test-{}.wav", std::process::id()));
+        std::fs::write(&path, &v).unwrap();
+        let o = crate::wav::read::<[f32; 2]>(&path);
+        let p = crate::wav::read_parallel::<[f32; 2]>(&path, 2);
+        std::fs::remove_file(&path).unwrap();
+        let (o, p) = (o.unwrap(), p.unwrap());
+        assert_eq!(o.sample_rate().get(), 48000);
+        let expected: Vec<[f32; 2]> = frames()
+            .iter()
+            .map(|f| f.map(|s| s.to_unit() as f32))
+            .collect();
+        assert_eq!(o[..], expected[..]);
+        assert_eq!(p[..], expected[..]);
+    }
+
+    #[test]
+    fn wav_read_rejects_rate_0() {
+        let spec = WavSpec {
+            sample_rate: 0,
+            ..stereo()
+        };
+        let v = write(spec, &ByteBlock::from_block(false, &frames()));
+        let path = std::env::temp_dir().join(format!("bssf-test-0-{}.wav", std::process::id()));
+        std::fs::write(&path, &v).unwrap();
+        let o = crate::wav::read::<[f32; 2]>(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(o, Err(crate::error::Error::InvalidSampleRate(0))));
+    }
+}
@@ -0,0 +1,64 @@
+use crate::fft::*;
+use crate::format::*;
+use crate::sample::*;
+
+fn mono<T: Frame>(o: &Block<T>) -> impl Iterator<Item = f64> + '_ {
+    let c = T::CHANNEL_SIZE as usize;
+    o.iter()
+        .map(move |i| (0..c).map(|n| i.channel(n) as f64).sum::<f64>() / c as f64)
+}
+
+///Returns cross correlation of the channel mix of a and b, `r[l] = sum(a[n + l] * b[n])` for lag l from `1 - b.len()` to `a.len() - 1` at index `l + b.len() - 1`.
+pub fn cross_correlate<T: Frame>(a: &Block<T>, b: &Block<T>) -> Vec<f32> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let len = a.len() + b.len() - 1;
+    let n = len.next_power_of_two();
+    let mut x = vec![Complex::default(); n];
+    let mut y = vec![Complex::default(); n];
+    for (i, v) in mono(a).enumerate() {
+        x[i].re = v;
+    }
+    for (i, v) in mono(b).enumerate() {
+        y[i].re = v;
+    }
+    fft(&mut x, false);
+    fft(&mut y, false);
+    for (p, q) in x.iter_mut().zip(y.iter()) {
+        *p = *p * q.conj();
+    }
+    fft(&mut x, true);
+    (0..len)
+        .map(|k| {
+            let l = k as isize - (b.len() as isize - 1);
+            let i = if l < 0 { n as isize + l } else { l } as usize;
+            (x[i].re / n as f64) as f32
+        })
+        .collect()
+}
+
+///Returns the lag in frames which best aligns b to a, positive if a is later than b.
+pub fn find_lag<T: Frame>(a: &Block<T>, b: &Block<T>) -> isize {
+    let r = cross_correlate(a, b);
+    let k = r
+        .iter()
+        .enumerate()
+        .max_by(|p, q| p.1.total_cmp(q.1))
+        .map_or(b.len().saturating_sub(1), |o| o.0);
+    k as isize - (b.len() as isize - 1)
+}
+
+///Returns b shifted by the lag to align with reference, padded with silence or trimmed to the length of reference.
+pub fn align<T: Frame>(reference: &Block<T>, b: &Block<T>) -> Block<T> {
+    let lag = find_lag(reference, b);
+    let mut v = Block::new(reference.len());
+    if lag >= 0 {
+        v.extend(std::iter::repeat_n(T::default(), lag as usize));
+        v.extend(b.iter().copied());
+    } else {
+        v.extend(b.iter().skip(lag.unsigned_abs()).copied());
+    }
+    v.resize(reference.len(), T::default());
+    v
+}
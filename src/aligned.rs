@@ -0,0 +1,187 @@
+use crate::format::*;
+use crate::sample::*;
+use std::alloc::{alloc, dealloc, handle_alloc_error, realloc, Layout};
+use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+
+///Alignment of a cache line and of AVX-512 vectors.
+pub const CACHE_ALIGN: usize = 64;
+
+///Block of samples whose buffer start is aligned to a power of two, such as 32 or 64 bytes for SIMD.
+pub struct AlignedBlock<T> {
+    ptr: NonNull<T>,
+    len: usize,
+    cap: usize,
+    align: usize,
+}
+
+unsafe impl<T: Send> Send for AlignedBlock<T> {}
+
+unsafe impl<T: Sync> Sync for AlignedBlock<T> {}
+
+impl<T> std::fmt::Debug for AlignedBlock<T>
+where
+    T: Sample + std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AlignedBlock")
+            .field("align", &self.align)
+            .field("data", &self.deref())
+            .finish()
+    }
+}
+
+impl<T> AlignedBlock<T>
+where
+    T: Sample,
+{
+    ///Constructs a new, empty AlignedBlock with the specified capacity and alignment. Panics if align is not a power of two or T is zero sized.
+    pub fn new(n: usize, align: usize) -> Self {
+        assert!(align.is_power_of_two(), "alignment is not a power of two");
+        assert!(size_of::<T>() > 0, "zero sized sample");
+        let mut o = Self {
+            ptr: NonNull::dangling(),
+            len: 0,
+            cap: 0,
+            align: align.max(align_of::<T>()),
+        };
+        o.reserve(n);
+        o
+    }
+
+    ///Constructs a new AlignedBlock copying samples.
+    pub fn from_slice(o: &[T], align: usize) -> Self
+    where
+        T: Clone,
+    {
+        let mut v = Self::new(o.len(), align);
+        for i in o {
+            v.push(i.clone());
+        }
+        v
+    }
+
+    ///Returns alignment of the buffer start in bytes.
+    pub fn alignment(&self) -> usize {
+        self.align
+    }
+
+    ///Returns capacity in samples.
+    pub fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    fn layout(&self, cap: usize) -> Layout {
+        Layout::array::<T>(cap)
+            .and_then(|o| o.align_to(self.align))
+            .expect("capacity overflow")
+    }
+
+    ///Reserves capacity for at least additional more samples.
+    pub fn reserve(&mut self, additional: usize) {
+        let need = self.len.checked_add(additional).expect("capacity overflow");
+        if need <= self.cap {
+            return;
+        }
+        let cap = need.max(self.cap.saturating_mul(2)).max(4);
+        let layout = self.layout(cap);
+        let ptr = unsafe {
+            if self.cap == 0 {
+                alloc(layout)
+            } else {
+                realloc(
+                    self.ptr.as_ptr() as *mut u8,
+                    self.layout(self.cap),
+                    layout.size(),
+                )
+            }
+        };
+        self.ptr = NonNull::new(ptr as *mut T).unwrap_or_else(|| handle_alloc_error(layout));
+        self.cap = cap;
+    }
+
+    ///Appends a sample.
+    pub fn push(&mut self, o: T) {
+        if self.len == self.cap {
+            self.reserve(1);
+        }
+        unsafe { self.ptr.as_ptr().add(self.len).write(o) };
+        self.len += 1;
+    }
+
+    ///Removes all samples, keeping the buffer.
+    pub fn clear(&mut self) {
+        let n = self.len;
+        self.len = 0;
+        unsafe {
+            std::ptr::drop_in_place(std::ptr::slice_from_raw_parts_mut(self.ptr.as_ptr(), n))
+        };
+    }
+
+    ///Copies samples into a Block.
+    pub fn to_block(&self) -> Block<T>
+    where
+        T: Clone,
+    {
+        Block::from(self.to_vec())
+    }
+}
+
+impl<T> Deref for AlignedBlock<T>
+where
+    T: Sample,
+{
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T> DerefMut for AlignedBlock<T>
+where
+    T: Sample,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T> Drop for AlignedBlock<T> {
+    fn drop(&mut self) {
+        unsafe {
+            std::ptr::drop_in_place(std::ptr::slice_from_raw_parts_mut(
+                self.ptr.as_ptr(),
+                self.len,
+            ));
+            if self.cap > 0 {
+                let layout =
+                    Layout::from_size_align_unchecked(size_of::<T>() * self.cap, self.align);
+                dealloc(self.ptr.as_ptr() as *mut u8, layout);
+            }
+        }
+    }
+}
+
+impl<T> Clone for AlignedBlock<T>
+where
+    T: Sample + Clone,
+{
+    fn clone(&self) -> Self {
+        Self::from_slice(self, self.align)
+    }
+}
+
+block_impl! {
+    impl<T> Block<T>
+    where
+        T: Sample,
+    {
+        ///Returns alignment of the buffer start in bytes, at least the alignment of T.
+        pub fn alignment(&self) -> usize {
+            1 << (self.as_ptr() as usize)
+                .trailing_zeros()
+                .min(usize::BITS - 1)
+        }
+    }
+}
@@ -0,0 +1,452 @@
+use crate::db::*;
+use crate::format::*;
+use crate::sample::*;
+
+const NOISE_WINDOW: usize = 1024;
+
+///Domain of histogram.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HistogramScale {
+    ///Normalized value from -1 to 1.
+    Linear,
+    ///Magnitude in dBFS from the floor to 0, lower values are counted in the first bin.
+    Db(Db),
+}
+
+///Histogram of a channel.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Histogram {
+    ///Bin edges, one more than counts.
+    pub edges: Vec<f32>,
+    pub counts: Vec<usize>,
+}
+
+impl Histogram {
+    ///Returns indices of empty bins between the first and last used bins, such as missing codes.
+    pub fn missing(&self) -> Vec<usize> {
+        let first = self.counts.iter().position(|o| *o > 0);
+        let last = self.counts.iter().rposition(|o| *o > 0);
+        match (first, last) {
+            (Some(a), Some(b)) => (a..=b).filter(|k| self.counts[*k] == 0).collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+///Summary statistics of a channel, values are normalized to full scale 1.0.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Stats {
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+    pub rms: f32,
+    pub count: usize,
+}
+
+block_impl! {
+    impl<T> Block<T>
+    where
+        T: Channels,
+    {
+        ///Returns peak of each channel, normalized to full scale 1.0.
+        pub fn peak(&self) -> Vec<f32> {
+            let c = T::CHANNEL_SIZE as usize;
+            let mut v = vec![0.0f64; c];
+            for i in self.iter() {
+                for (n, p) in v.iter_mut().enumerate() {
+                    *p = p.max(i.get_channel(n).to_unit().abs());
+                }
+            }
+            v.into_iter().map(|o| o as f32).collect()
+        }
+
+        ///Returns peak of each channel in dBFS.
+        pub fn peak_dbfs(&self) -> Vec<Db> {
+            self.peak().into_iter().map(Db::from_gain).collect()
+        }
+
+        ///Returns RMS of each channel, normalized to full scale 1.0.
+        pub fn rms(&self) -> Vec<f32> {
+            let c = T::CHANNEL_SIZE as usize;
+            let mut v = vec![0.0f64; c];
+            for i in self.iter() {
+                for (n, s) in v.iter_mut().enumerate() {
+                    let x = i.get_channel(n).to_unit();
+                    *s += x * x;
+                }
+            }
+            let len = self.len().max(1) as f64;
+            v.into_iter().map(|o| (o / len).sqrt() as f32).collect()
+        }
+
+        ///Returns RMS of each channel in dBFS, where a full scale square wave is 0 dBFS.
+        pub fn rms_dbfs(&self) -> Vec<Db> {
+            self.rms().into_iter().map(Db::from_gain).collect()
+        }
+
+        ///Returns zero crossing rate of each channel, the proportion of adjacent frames changing sign.
+        pub fn zero_crossing_rate(&self) -> Vec<f32> {
+            let c = T::CHANNEL_SIZE as usize;
+            let mut v = vec![0usize; c];
+            for w in self.windows(2) {
+                for (n, z) in v.iter_mut().enumerate() {
+                    if (w[0].get_channel(n).to_unit() >= 0.0) != (w[1].get_channel(n).to_unit() >= 0.0)
+                    {
+                        *z += 1;
+                    }
+                }
+            }
+            let len = self.len().saturating_sub(1).max(1) as f32;
+            v.into_iter().map(|o| o as f32 / len).collect()
+        }
+
+        ///Returns summary statistics of each channel in one pass.
+        pub fn stats(&self) -> Vec<Stats> {
+            let c = T::CHANNEL_SIZE as usize;
+            let mut v = vec![(f64::INFINITY, f64::NEG_INFINITY, 0.0f64, 0.0f64); c];
+            for i in self.iter() {
+                for (n, s) in v.iter_mut().enumerate() {
+                    let x = i.get_channel(n).to_unit();
+                    s.0 = s.0.min(x);
+                    s.1 = s.1.max(x);
+                    s.2 += x;
+                    s.3 += x * x;
+                }
+            }
+            let count = self.len();
+            let len = count.max(1) as f64;
+            v.into_iter()
+                .map(|(min, max, sum, sq)| Stats {
+                    min: if count > 0 { min as f32 } else { 0.0 },
+                    max: if count > 0 { max as f32 } else { 0.0 },
+                    mean: (sum / len) as f32,
+                    rms: (sq / len).sqrt() as f32,
+                    count,
+                })
+                .collect()
+        }
+
+        ///Returns histogram of each channel. Linear bins span -1 to 1, so 2^bits bins of integer block are one code each.
+        pub fn histogram(&self, bins: usize, scale: HistogramScale) -> Vec<Histogram> {
+            let bins = bins.max(1);
+            let (lo, hi) = match scale {
+                HistogramScale::Linear => (-1.0, 1.0),
+                HistogramScale::Db(floor) => (floor.0.min(0.0), 0.0),
+            };
+            let edges: Vec<f32> = (0..=bins)
+                .map(|k| lo + (hi - lo) * k as f32 / bins as f32)
+                .collect();
+            let c = T::CHANNEL_SIZE as usize;
+            let mut v = vec![vec![0usize; bins]; c];
+            let width = (hi - lo) as f64 / bins as f64;
+            for i in self.iter() {
+                for (n, h) in v.iter_mut().enumerate() {
+                    let x = i.get_channel(n).to_unit();
+                    let x = match scale {
+                        HistogramScale::Linear => x,
+                        HistogramScale::Db(_) => Db::from_gain(x.abs() as f32).0 as f64,
+                    };
+                    let k = ((x - lo as f64) / width)
+                        .floor()
+                        .clamp(0.0, (bins - 1) as f64);
+                    h[k as usize] += 1;
+                }
+            }
+            v.into_iter()
+                .map(|counts| Histogram {
+                    edges: edges.clone(),
+                    counts,
+                })
+                .collect()
+        }
+
+        ///Returns DC offset of each channel, the mean normalized to full scale 1.0.
+        pub fn measure_dc_offset(&self) -> Vec<f32> {
+            self.dc().into_iter().map(|o| o as f32).collect()
+        }
+
+        fn dc(&self) -> Vec<f64> {
+            let c = T::CHANNEL_SIZE as usize;
+            let mut v = vec![0.0f64; c];
+            for i in self.iter() {
+                for (n, s) in v.iter_mut().enumerate() {
+                    *s += i.get_channel(n).to_unit();
+                }
+            }
+            let len = self.len().max(1) as f64;
+            v.into_iter().map(|o| o / len).collect()
+        }
+
+        ///Returns noise floor of each channel in dBFS, the percentile (0 to 100) of RMS of 1024 frame windows with DC removed.
+        pub fn noise_floor_dbfs(&self, percentile: f32) -> Vec<Db> {
+            let dc = self.dc();
+            let mut v = Vec::with_capacity(dc.len());
+            for (n, d) in dc.iter().enumerate() {
+                let mut w: Vec<f64> = self
+                    .chunks(NOISE_WINDOW)
+                    .map(|o| {
+                        let s: f64 = o
+                            .iter()
+                            .map(|i| (i.get_channel(n).to_unit() - d).powi(2))
+                            .sum();
+                        (s / o.len() as f64).sqrt()
+                    })
+                    .collect();
+                if w.is_empty() {
+                    v.push(Db(f32::NEG_INFINITY));
+                    continue;
+                }
+                w.sort_by(|a, b| a.total_cmp(b));
+                let k = (percentile.clamp(0.0, 100.0) / 100.0 * (w.len() - 1) as f32).round() as usize;
+                v.push(Db::from_gain(w[k] as f32));
+            }
+            v
+        }
+
+        fn mix(&self, i: usize) -> f64 {
+            let o = &self[i];
+            (0..T::CHANNEL_SIZE as usize)
+                .map(|n| o.get_channel(n).to_unit())
+                .sum()
+        }
+
+        ///Returns the frame nearest to frame where the sum of all channels crosses zero, which is the one of the smaller magnitude of the pair.
+        pub fn nearest_zero_crossing(&self, frame: usize) -> Option<usize> {
+            let mut r: Option<usize> = None;
+            for i in 0..self.len().saturating_sub(1) {
+                let (a, b) = (self.mix(i), self.mix(i + 1));
+                if (a >= 0.0) == (b >= 0.0) {
+                    continue;
+                }
+                let o = if a.abs() <= b.abs() { i } else { i + 1 };
+                if r.is_none_or(|r| o.abs_diff(frame) < r.abs_diff(frame)) {
+                    r = Some(o);
+                }
+            }
+            r
+        }
+    }
+}
+
+const OVERSAMPLE: usize = 4;
+const PHASE_TAPS: usize = 13;
+
+///A 4 times oversampling true peak detector of polyphase interpolation.
+#[derive(Clone, Debug)]
+pub struct TruePeak {
+    phases: [[f32; PHASE_TAPS]; OVERSAMPLE],
+    history: Vec<[f32; PHASE_TAPS]>,
+    pos: usize,
+}
+
+impl Default for TruePeak {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TruePeak {
+    ///Latency in frames.
+    pub const LATENCY: usize = PHASE_TAPS / 2;
+
+    ///Constructs a new TruePeak.
+    pub fn new() -> Self {
+        let len = OVERSAMPLE * (PHASE_TAPS - 1) + 1;
+        let center = (len / 2) as f64;
+        let mut phases = [[0.0; PHASE_TAPS]; OVERSAMPLE];
+        for k in 0..len {
+            let t = (k as f64 - center) / OVERSAMPLE as f64;
+            let sinc = if t == 0.0 {
+                1.0
+            } else {
+                (std::f64::consts::PI * t).sin() / (std::f64::consts::PI * t)
+            };
+            let a = 2.0 * std::f64::consts::PI * k as f64 / (len - 1) as f64;
+            let w = 0.42 - 0.5 * a.cos() + 0.08 * (2.0 * a).cos();
+            phases[k % OVERSAMPLE][k / OVERSAMPLE] = (sinc * w) as f32;
+        }
+        for p in phases.iter_mut() {
+            let s: f32 = p.iter().sum();
+            for c in p.iter_mut() {
+                *c /= s;
+            }
+        }
+        Self {
+            phases,
+            history: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    //sizes the history for channels ahead of the first frame.
+    pub(crate) fn with_channels(mut self, c: usize) -> Self {
+        self.history = vec![[0.0; PHASE_TAPS]; c];
+        self
+    }
+
+    ///Clears the internal state.
+    pub fn reset(&mut self) {
+        for h in self.history.iter_mut() {
+            *h = [0.0; PHASE_TAPS];
+        }
+        self.pos = 0;
+    }
+
+    fn channel_peak(&mut self, n: usize) -> f32 {
+        let h = &self.history[n];
+        let mut m = 0.0f32;
+        for p in self.phases.iter() {
+            let mut s = 0.0;
+            for (j, c) in p.iter().enumerate() {
+                s += c * h[(self.pos + PHASE_TAPS - j) % PHASE_TAPS];
+            }
+            m = m.max(s.abs());
+        }
+        m
+    }
+
+    //pushes a frame into the history of each channel.
+    fn push<T: Frame>(&mut self, x: &T) {
+        let c = T::CHANNEL_SIZE as usize;
+        if self.history.len() != c {
+            self.history = vec![[0.0; PHASE_TAPS]; c];
+            self.pos = 0;
+        }
+        self.pos = (self.pos + 1) % PHASE_TAPS;
+        for n in 0..c {
+            self.history[n][self.pos] = x.channel(n);
+        }
+    }
+
+    ///Pushes a frame, returns the maximum absolute interpolated value of all channels, delayed by latency.
+    pub fn tick<T: Frame>(&mut self, x: &T) -> f32 {
+        self.push(x);
+        (0..T::CHANNEL_SIZE as usize).fold(0.0, |m, n| m.max(self.channel_peak(n)))
+    }
+
+    //pushes a frame and raises the peak of each channel.
+    fn accumulate<T: Frame>(&mut self, x: &T, v: &mut [f32]) {
+        self.push(x);
+        for (n, p) in v.iter_mut().enumerate() {
+            *p = p.max(self.channel_peak(n));
+        }
+    }
+
+    ///Pushes block, returns the maximum absolute interpolated value of each channel.
+    pub fn process<T: Frame>(&mut self, o: &Block<T>) -> Vec<f32> {
+        let mut v = vec![0.0f32; T::CHANNEL_SIZE as usize];
+        for i in o.iter() {
+            self.accumulate(i, &mut v);
+        }
+        v
+    }
+
+    ///Pushes latency frames of silence, returns the maximum absolute interpolated value of each channel over the frames still in latency.
+    pub fn flush<T: Frame>(&mut self) -> Vec<f32> {
+        let mut v = vec![0.0f32; T::CHANNEL_SIZE as usize];
+        for _ in 0..Self::LATENCY {
+            self.accumulate(&T::default(), &mut v);
+        }
+        v
+    }
+}
+
+///Returns true peak of each channel in dBTP.
+pub fn true_peak<T: Frame>(o: &Block<T>) -> Vec<Db> {
+    let mut d = TruePeak::new();
+    d.process(o)
+        .into_iter()
+        .zip(d.flush::<T>())
+        .map(|(a, b)| Db::from_gain(a.max(b)))
+        .collect()
+}
+
+///Clipping report of a channel.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Clipping {
+    ///Number of clipped samples.
+    pub count: usize,
+    ///Length of the longest run of consecutive clipped samples.
+    pub longest_run: usize,
+    ///Frame positions of clipped samples.
+    pub frames: Vec<usize>,
+}
+
+fn clipping<T: Channels>(o: &Block<T>, threshold: f64) -> Vec<Clipping> {
+    let c = T::CHANNEL_SIZE as usize;
+    let mut v = vec![Clipping::default(); c];
+    let mut runs = vec![0usize; c];
+    for (i, f) in o.iter().enumerate() {
+        for (n, r) in v.iter_mut().enumerate() {
+            if f.get_channel(n).to_unit().abs() >= threshold {
+                r.count += 1;
+                r.frames.push(i);
+                runs[n] += 1;
+                r.longest_run = r.longest_run.max(runs[n]);
+            } else {
+                runs[n] = 0;
+            }
+        }
+    }
+    v
+}
+
+///Returns clipping report of each channel, where samples reaching full scale are clipped.
+pub fn detect_clipping<T: Channels>(o: &Block<T>) -> Vec<Clipping> {
+    clipping(o, T::Scalar::MAX_UNIT)
+}
+
+///Returns clipping report of each channel, where samples reaching threshold (normalized to full scale 1.0) are clipped.
+pub fn detect_clipping_above<T: Channels>(o: &Block<T>, threshold: f32) -> Vec<Clipping> {
+    clipping(o, threshold as f64)
+}
+
+fn correlate<T: Scalar>(o: &[[T; 2]]) -> f32 {
+    let (mut lr, mut ll, mut rr) = (0.0, 0.0, 0.0);
+    for i in o {
+        let (l, r) = (i[0].to_unit(), i[1].to_unit());
+        lr += l * r;
+        ll += l * l;
+        rr += r * r;
+    }
+    if ll > 0.0 && rr > 0.0 {
+        (lr / (ll * rr).sqrt()) as f32
+    } else {
+        0.0
+    }
+}
+
+block_impl! {
+    impl<T> Block<[T; 2]>
+    where
+        T: Scalar,
+    {
+        ///Returns correlation coefficient of the two channels from -1 (out of phase) to 1 (mono). It's 0 if a channel is silent.
+        pub fn correlation(&self) -> f32 {
+            correlate(self)
+        }
+
+        ///Returns correlation coefficient of each window of frames, the last window may be shorter.
+        pub fn correlation_windows(&self, window: usize) -> Vec<f32> {
+            self.chunks(window.max(1)).map(correlate).collect()
+        }
+
+        ///Returns frame ranges of consecutive windows whose correlation coefficient is below threshold, which would cancel in mono.
+        pub fn out_of_phase(&self, window: usize, threshold: f32) -> Vec<std::ops::Range<usize>> {
+            let window = window.max(1);
+            let mut v: Vec<std::ops::Range<usize>> = Vec::new();
+            for (i, c) in self.correlation_windows(window).into_iter().enumerate() {
+                if c >= threshold {
+                    continue;
+                }
+                let s = i * window;
+                let e = (s + window).min(self.len());
+                match v.last_mut() {
+                    Some(r) if r.end == s => r.end = e,
+                    _ => v.push(s..e),
+                }
+            }
+            v
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use crate::cancel::*;
+use crate::error::*;
+use crate::format::*;
+use crate::sample::*;
+use crate::transcode::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+///Results of a batch conversion, in the order of items.
+#[derive(Debug)]
+pub struct BatchReport {
+    pub results: Vec<Result<ByteBlock, Error>>,
+}
+
+impl BatchReport {
+    ///Returns number of items converted.
+    pub fn succeeded(&self) -> usize {
+        self.results.iter().filter(|o| o.is_ok()).count()
+    }
+
+    ///Returns number of items failed.
+    pub fn failed(&self) -> usize {
+        self.results.len() - self.succeeded()
+    }
+
+    ///Returns index and error of each failed item.
+    pub fn errors(&self) -> impl Iterator<Item = (usize, &Error)> {
+        self.results
+            .iter()
+            .enumerate()
+            .filter_map(|(i, o)| o.as_ref().err().map(|e| (i, e)))
+    }
+}
+
+///A batch converter of many wholes to the same target, optionally in parallel. With the gpu feature, each whole is resampled by a dispatch of its own.
+#[derive(Clone, Debug)]
+pub struct BatchConvert {
+    spec: TargetSpec,
+    threads: usize,
+    cancel: CancelToken,
+}
+
+impl BatchConvert {
+    ///Constructs a new BatchConvert to spec on one thread.
+    pub fn new(spec: TargetSpec) -> Self {
+        Self {
+            spec,
+            threads: 1,
+            cancel: CancelToken::new(),
+        }
+    }
+
+    ///Sets threads converting items at once, 0 for the available parallelism. 1 by default.
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads;
+    }
+
+    ///Sets a token to cancel the batch. Items not finished when it is cancelled fail with Error::Cancelled.
+    pub fn set_cancel(&mut self, cancel: CancelToken) {
+        self.cancel = cancel;
+    }
+
+    //returns options of an item, cancelled with the batch.
+    fn options(&self) -> RunOptions<'static> {
+        let mut o = RunOptions::new();
+        o.set_cancel(self.cancel.clone());
+        o
+    }
+
+    //runs f on each item, calling progress with items done and total after each.
+    fn run_with<I: Sync>(
+        &self,
+        items: &[I],
+        f: impl Fn(&I) -> Result<ByteBlock, Error> + Sync,
+        progress: impl FnMut(usize, usize) + Send,
+    ) -> BatchReport {
+        let n = items.len();
+        let threads = match self.threads {
+            0 => std::thread::available_parallelism().map_or(1, |o| o.get()),
+            o => o,
+        };
+        let threads = threads.min(n).max(1);
+        let progress = Mutex::new((0, progress));
+        let next = AtomicUsize::new(0);
+        let results: Vec<Mutex<Option<Result<ByteBlock, Error>>>> =
+            (0..n).map(|_| Mutex::new(None)).collect();
+        let work = || loop {
+            let i = next.fetch_add(1, Ordering::Relaxed);
+            if i >= n {
+                return;
+            }
+            let r = self
+                .cancel
+                .check()
+                .map_err(Error::from)
+                .and_then(|_| f(&items[i]));
+            *results[i].lock().unwrap_or_else(|o| o.into_inner()) = Some(r);
+            let mut g = progress.lock().unwrap_or_else(|o| o.into_inner());
+            g.0 += 1;
+            let done = g.0;
+            (g.1)(done, n);
+        };
+        if threads == 1 {
+            work();
+        } else {
+            std::thread::scope(|s| {
+                for _ in 0..threads {
+                    s.spawn(work);
+                }
+            });
+        }
+        BatchReport {
+            results: results
+                .into_iter()
+                .map(|o| {
+                    o.into_inner()
+                        .unwrap_or_else(|o| o.into_inner())
+                        .unwrap_or(Err(Error::Cancelled))
+                })
+                .collect(),
+        }
+    }
+
+    ///Converts each whole, calling progress with items done and total after each.
+    pub fn run<T: Frame + Sync>(
+        &self,
+        items: &[Whole<T>],
+        progress: impl FnMut(usize, usize) + Send,
+    ) -> BatchReport {
+        self.run_with(
+            items,
+            |o| o.convert_to_with(self.spec, &mut self.options()),
+            progress,
+        )
+    }
+
+    ///Loads each item, such as a path, and converts it, calling progress with items done and total after each. Failures to load are reported per item.
+    pub fn run_loaded<I: Sync, T: Frame>(
+        &self,
+        items: &[I],
+        load: impl Fn(&I) -> Result<Whole<T>, Error> + Sync,
+        progress: impl FnMut(usize, usize) + Send,
+    ) -> BatchReport {
+        self.run_with(
+            items,
+            |o| load(o)?.convert_to_with(self.spec, &mut self.options()),
+            progress,
+        )
+    }
+}
+
+///Converts each whole to spec on all available threads.
+pub fn convert_batch<T: Frame + Sync>(items: &[Whole<T>], spec: TargetSpec) -> BatchReport {
+    let mut o = BatchConvert::new(spec);
+    o.set_threads(0);
+    o.run(items, |_, _| {})
+}
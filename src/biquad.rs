@@ -0,0 +1,162 @@
+//!Biquad filters designed by the RBJ audio EQ cookbook.
+
+use crate::db::*;
+use crate::format::*;
+use crate::sample::*;
+use std::f64::consts::PI;
+
+///Type of biquad filter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FilterType {
+    LowPass,
+    HighPass,
+    BandPass,
+    Notch,
+    Peaking,
+    LowShelf,
+    HighShelf,
+}
+
+///Normalized coefficients of biquad filter.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Coefficients {
+    pub b0: f64,
+    pub b1: f64,
+    pub b2: f64,
+    pub a1: f64,
+    pub a2: f64,
+}
+
+impl Coefficients {
+    ///Designs coefficients. Gain is only used by peaking and shelf filters.
+    pub fn new(filter_type: FilterType, sample_rate: u32, freq: f32, q: f32, gain: Db) -> Self {
+        let w = 2.0 * PI * freq as f64 / sample_rate as f64;
+        let (sin, cos) = w.sin_cos();
+        let alpha = sin / (2.0 * q as f64);
+        let a = 10f64.powf(gain.0 as f64 / 40.0);
+        let s = 2.0 * a.sqrt() * alpha;
+        let (b0, b1, b2, a0, a1, a2) = match filter_type {
+            FilterType::LowPass => (
+                (1.0 - cos) / 2.0,
+                1.0 - cos,
+                (1.0 - cos) / 2.0,
+                1.0 + alpha,
+                -2.0 * cos,
+                1.0 - alpha,
+            ),
+            FilterType::HighPass => (
+                (1.0 + cos) / 2.0,
+                -(1.0 + cos),
+                (1.0 + cos) / 2.0,
+                1.0 + alpha,
+                -2.0 * cos,
+                1.0 - alpha,
+            ),
+            FilterType::BandPass => (alpha, 0.0, -alpha, 1.0 + alpha, -2.0 * cos, 1.0 - alpha),
+            FilterType::Notch => (1.0, -2.0 * cos, 1.0, 1.0 + alpha, -2.0 * cos, 1.0 - alpha),
+            FilterType::Peaking => (
+                1.0 + alpha * a,
+                -2.0 * cos,
+                1.0 - alpha * a,
+                1.0 + alpha / a,
+                -2.0 * cos,
+                1.0 - alpha / a,
+            ),
+            FilterType::LowShelf => (
+                a * ((a + 1.0) - (a - 1.0) * cos + s),
+                2.0 * a * ((a - 1.0) - (a + 1.0) * cos),
+                a * ((a + 1.0) - (a - 1.0) * cos - s),
+                (a + 1.0) + (a - 1.0) * cos + s,
+                -2.0 * ((a - 1.0) + (a + 1.0) * cos),
+                (a + 1.0) + (a - 1.0) * cos - s,
+            ),
+            FilterType::HighShelf => (
+                a * ((a + 1.0) + (a - 1.0) * cos + s),
+                -2.0 * a * ((a - 1.0) + (a + 1.0) * cos),
+                a * ((a + 1.0) + (a - 1.0) * cos - s),
+                (a + 1.0) - (a - 1.0) * cos + s,
+                2.0 * ((a - 1.0) - (a + 1.0) * cos),
+                (a + 1.0) - (a - 1.0) * cos - s,
+            ),
+        };
+        Self {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b2 / a0,
+            a1: a1 / a0,
+            a2: a2 / a0,
+        }
+    }
+
+    ///Returns linear magnitude response at freq.
+    pub fn magnitude(&self, sample_rate: u32, freq: f32) -> f32 {
+        let w = 2.0 * PI * freq as f64 / sample_rate as f64;
+        let (s1, c1) = w.sin_cos();
+        let (s2, c2) = (2.0 * w).sin_cos();
+        let nr = self.b0 + self.b1 * c1 + self.b2 * c2;
+        let ni = -self.b1 * s1 - self.b2 * s2;
+        let dr = 1.0 + self.a1 * c1 + self.a2 * c2;
+        let di = -self.a1 * s1 - self.a2 * s2;
+        ((nr * nr + ni * ni) / (dr * dr + di * di)).sqrt() as f32
+    }
+}
+
+///A stateful biquad filter processing every channel of packed block.
+#[derive(Clone, Debug)]
+pub struct Biquad {
+    coef: Coefficients,
+    state: Vec<[f64; 2]>,
+}
+
+impl Biquad {
+    ///Constructs a new Biquad from coefficients.
+    pub fn new(coef: Coefficients) -> Self {
+        Self {
+            coef,
+            state: Vec::new(),
+        }
+    }
+
+    ///Constructs a new Biquad of the design.
+    pub fn design(filter_type: FilterType, sample_rate: u32, freq: f32, q: f32, gain: Db) -> Self {
+        Self::new(Coefficients::new(filter_type, sample_rate, freq, q, gain))
+    }
+
+    ///Returns coefficients.
+    pub fn coefficients(&self) -> &Coefficients {
+        &self.coef
+    }
+
+    ///Replaces coefficients, keeping the internal state.
+    pub fn set_coefficients(&mut self, coef: Coefficients) {
+        self.coef = coef;
+    }
+
+    ///Clears the internal state.
+    pub fn reset(&mut self) {
+        self.state.clear();
+    }
+
+    ///Processes one sample of channel n.
+    pub fn tick(&mut self, n: usize, x: f32) -> f32 {
+        if self.state.len() <= n {
+            self.state.resize(n + 1, [0.0; 2]);
+        }
+        let c = &self.coef;
+        let s = &mut self.state[n];
+        let x = x as f64;
+        let y = c.b0 * x + s[0];
+        s[0] = c.b1 * x - c.a1 * y + s[1];
+        s[1] = c.b2 * x - c.a2 * y;
+        y as f32
+    }
+
+    ///Processes block in place.
+    pub fn process<T: Frame>(&mut self, o: &mut Block<T>) {
+        for i in o.iter_mut() {
+            for n in 0..T::CHANNEL_SIZE as usize {
+                i.set_channel(n, self.tick(n, i.channel(n)));
+            }
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+///A token shared by clones to cancel long-running operations, which check it between blocks.
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    ///Constructs a new CancelToken, not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///Requests cancellation of operations observing self or its clones.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    ///Returns true if cancellation is requested.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    ///Returns Err(Cancelled) if cancellation is requested.
+    pub fn check(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+///Error of an operation stopped by its CancelToken.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl std::fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "operation cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+impl From<Cancelled> for std::io::Error {
+    fn from(o: Cancelled) -> Self {
+        std::io::Error::other(o)
+    }
+}
+
+///Cancellation and progress of a long-running operation, observed between blocks.
+#[derive(Default)]
+pub struct RunOptions<'a> {
+    cancel: CancelToken,
+    progress: Option<Box<dyn FnMut(usize, usize) + Send + 'a>>,
+}
+
+impl std::fmt::Debug for RunOptions<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("RunOptions");
+        f.field("cancel", &self.cancel)
+            .field("progress", &self.progress.is_some())
+            .finish()
+    }
+}
+
+impl<'a> RunOptions<'a> {
+    ///Constructs a new RunOptions, never cancelled and reporting no progress.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///Sets a token to cancel the operation, which then fails with Error::Cancelled. Never cancelled by default.
+    pub fn set_cancel(&mut self, cancel: CancelToken) {
+        self.cancel = cancel;
+    }
+
+    ///Sets a callback of units done and total, called after each block. None by default.
+    pub fn set_progress(&mut self, progress: impl FnMut(usize, usize) + Send + 'a) {
+        self.progress = Some(Box::new(progress));
+    }
+
+    pub(crate) fn cancel(&self) -> &CancelToken {
+        &self.cancel
+    }
+
+    //returns Err(Cancelled) if cancellation is requested.
+    pub(crate) fn check(&self) -> Result<(), Cancelled> {
+        self.cancel.check()
+    }
+
+    pub(crate) fn report(&mut self, done: usize, total: usize) {
+        if let Some(o) = &mut self.progress {
+            o(done, total);
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use crate::format::*;
+use crate::sample::*;
+use std::ops::{Index, IndexMut};
+
+///Default frames of a segment of ChunkedBlock.
+pub const SEGMENT_FRAMES: usize = 1 << 16;
+
+///A block stored as segments of fixed frames, so appending never reallocates or copies existing frames.
+pub struct ChunkedBlock<T> {
+    segments: Vec<Vec<T>>,
+    segment: usize,
+    len: usize,
+}
+
+impl<T> std::fmt::Debug for ChunkedBlock<T>
+where
+    T: Sample,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("ChunkedBlock");
+        f.field("channel_size", &self.channel_size())
+            .field("byte_size", &self.byte_size())
+            .field("segment", &self.segment)
+            .field("data_size", &self.len)
+            .finish()
+    }
+}
+
+impl<T> Default for ChunkedBlock<T>
+where
+    T: Sample,
+{
+    fn default() -> Self {
+        Self::new(SEGMENT_FRAMES)
+    }
+}
+
+impl<T> ChunkedBlock<T>
+where
+    T: Sample,
+{
+    ///Constructs a new, empty ChunkedBlock of segments of frames.
+    pub fn new(segment: usize) -> Self {
+        Self {
+            segments: Vec::new(),
+            segment: segment.max(1),
+            len: 0,
+        }
+    }
+
+    ///Returns channel size.
+    pub fn channel_size(&self) -> u16 {
+        T::CHANNEL_SIZE
+    }
+
+    ///Returns byte size.
+    pub fn byte_size(&self) -> usize {
+        T::BYTE_SIZE
+    }
+
+    ///Returns bit depth.
+    pub fn bit_depth(&self) -> usize {
+        8 * (T::BYTE_SIZE / T::CHANNEL_SIZE as usize)
+    }
+
+    ///Returns number of frames.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    ///Returns true if there is no frame.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    //returns the last segment with room, allocating a new one when full.
+    fn tail(&mut self) -> &mut Vec<T> {
+        if self.segments.last().is_none_or(|o| o.len() == self.segment) {
+            self.segments.push(Vec::with_capacity(self.segment));
+        }
+        self.segments.last_mut().unwrap()
+    }
+
+    ///Appends a frame.
+    pub fn push(&mut self, o: T) {
+        self.tail().push(o);
+        self.len += 1;
+    }
+
+    ///Appends frames of slice.
+    pub fn extend_from_slice(&mut self, mut o: &[T])
+    where
+        T: Clone,
+    {
+        while !o.is_empty() {
+            let segment = self.segment;
+            let tail = self.tail();
+            let n = (segment - tail.len()).min(o.len());
+            tail.extend_from_slice(&o[..n]);
+            self.len += n;
+            o = &o[n..];
+        }
+    }
+
+    ///Moves frames of block into self.
+    pub fn append(&mut self, mut o: Block<T>) {
+        for i in o.drain(..) {
+            self.push(i);
+        }
+    }
+
+    ///Removes all frames and segments.
+    pub fn clear(&mut self) {
+        self.segments.clear();
+        self.len = 0;
+    }
+
+    ///Returns a reference to the frame at index.
+    pub fn get(&self, i: usize) -> Option<&T> {
+        self.segments.get(i / self.segment)?.get(i % self.segment)
+    }
+
+    ///Returns a mutable reference to the frame at index.
+    pub fn get_mut(&mut self, i: usize) -> Option<&mut T> {
+        self.segments
+            .get_mut(i / self.segment)?
+            .get_mut(i % self.segment)
+    }
+
+    ///Returns an iterator of segments as slices.
+    pub fn segments(&self) -> impl Iterator<Item = &[T]> {
+        self.segments.iter().map(|o| o.as_slice())
+    }
+
+    ///Returns an iterator of frames.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.segments.iter().flatten()
+    }
+
+    ///Returns a mutable iterator of frames.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.segments.iter_mut().flatten()
+    }
+
+    ///Copies frames into a contiguous Block.
+    pub fn to_block(&self) -> Block<T>
+    where
+        T: Clone,
+    {
+        let mut v = Block::new(self.len);
+        for o in &self.segments {
+            v.extend_from_slice(o);
+        }
+        v
+    }
+
+    ///Converts self into a contiguous Block.
+    pub fn into_block(self) -> Block<T> {
+        let mut v = Block::new(self.len);
+        for o in self.segments {
+            v.extend(o);
+        }
+        v
+    }
+}
+
+impl<T> Index<usize> for ChunkedBlock<T>
+where
+    T: Sample,
+{
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        self.get(i).expect("index out of bounds")
+    }
+}
+
+impl<T> IndexMut<usize> for ChunkedBlock<T>
+where
+    T: Sample,
+{
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        self.get_mut(i).expect("index out of bounds")
+    }
+}
+
+impl<T> From<Block<T>> for ChunkedBlock<T>
+where
+    T: Sample,
+{
+    fn from(o: Block<T>) -> Self {
+        let mut v = Self::default();
+        v.append(o);
+        v
+    }
+}
@@ -0,0 +1,204 @@
+use crate::format::*;
+use crate::sample::*;
+
+///Tolerance of approximate equality.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Tolerance {
+    ///Maximum absolute difference, normalized to full scale 1.0.
+    Absolute(f64),
+    ///Maximum difference relative to the larger magnitude of the two samples.
+    Relative(f64),
+    ///Maximum distance in units in the last place. A unit of an integer sample is 1.
+    Ulps(u64),
+}
+
+///Failure of approximate equality, with the maximum error of each channel in the unit of the tolerance.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApproxMismatch {
+    ///Frames of both blocks.
+    pub len: (usize, usize),
+    ///First frame exceeding the tolerance, None if only the lengths differ.
+    pub frame: Option<usize>,
+    ///Maximum error of each channel over the common frames.
+    pub max_error: Vec<f64>,
+}
+
+impl std::fmt::Display for ApproxMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.len.0 != self.len.1 {
+            write!(f, "length {} differs from {}", self.len.0, self.len.1)?;
+        }
+        if let Some(n) = self.frame {
+            if self.len.0 != self.len.1 {
+                f.write_str(", ")?;
+            }
+            write!(f, "frame {n} exceeds tolerance")?;
+        }
+        write!(f, ", max error of each channel {:?}", self.max_error)
+    }
+}
+
+impl std::error::Error for ApproxMismatch {}
+
+//returns distance in units in the last place, infinite if either is NaN.
+fn ulps<S: Scalar>(a: S, b: S) -> f64 {
+    let nan = |o: S| o.partial_cmp(&o).is_none();
+    if nan(a) || nan(b) {
+        return f64::INFINITY;
+    }
+    (a.ulp_index() - b.ulp_index()).unsigned_abs() as f64
+}
+
+impl Tolerance {
+    //returns error of a and b in the unit of self.
+    fn error<S: Scalar>(&self, a: S, b: S) -> f64 {
+        match self {
+            Self::Absolute(_) => (a.to_unit() - b.to_unit()).abs(),
+            Self::Relative(_) => {
+                let (a, b) = (a.to_unit(), b.to_unit());
+                let m = a.abs().max(b.abs());
+                if m == 0.0 {
+                    0.0
+                } else {
+                    (a - b).abs() / m
+                }
+            }
+            Self::Ulps(_) => ulps(a, b),
+        }
+    }
+
+    fn limit(&self) -> f64 {
+        match *self {
+            Self::Absolute(o) | Self::Relative(o) => o,
+            Self::Ulps(o) => o as f64,
+        }
+    }
+}
+
+block_impl! {
+    impl<T> Block<T>
+    where
+        T: Channels,
+    {
+        ///Returns Ok if other has the same frames and every sample is within tolerance, or the mismatch with the maximum error of each channel. NaN never equals.
+        pub fn approx_eq(&self, other: &[T], tolerance: Tolerance) -> Result<(), ApproxMismatch> {
+            let limit = tolerance.limit();
+            let mut max_error = vec![0.0f64; T::CHANNEL_SIZE as usize];
+            let mut frame = None;
+            for (i, (a, b)) in self.iter().zip(other).enumerate() {
+                for (n, m) in max_error.iter_mut().enumerate() {
+                    let e = tolerance.error(a.get_channel(n), b.get_channel(n));
+                    let e = if e.is_nan() { f64::INFINITY } else { e };
+                    if e > limit && frame.is_none() {
+                        frame = Some(i);
+                    }
+                    *m = m.max(e);
+                }
+            }
+            if frame.is_none() && self.len() == other.len() {
+                return Ok(());
+            }
+            Err(ApproxMismatch {
+                len: (self.len(), other.len()),
+                frame,
+                max_error,
+            })
+        }
+    }
+}
+
+///Differences of a channel.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ChannelDifference {
+    ///First differing frame.
+    pub first: Option<usize>,
+    ///Count of differing samples.
+    pub count: usize,
+    ///Maximum absolute difference, normalized to full scale 1.0.
+    pub max: f64,
+    ///Count of differing samples that are zeros of opposite sign.
+    pub signed_zero: usize,
+    ///Count of differing samples that are both NaN of different payloads.
+    pub nan_payload: usize,
+}
+
+///Report of bit transparency between two blocks over their common frames.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Comparison {
+    ///Frames of both blocks.
+    pub len: (usize, usize),
+    ///First frame where any channel differs.
+    pub first: Option<usize>,
+    ///Count of differing samples of all channels.
+    pub count: usize,
+    ///Maximum absolute difference of all channels, normalized to full scale 1.0.
+    pub max: f64,
+    ///Differences of each channel.
+    pub channels: Vec<ChannelDifference>,
+}
+
+impl Comparison {
+    ///Returns true if both blocks have the same frames and every sample is identical.
+    pub fn is_identical(&self) -> bool {
+        self.count == 0 && self.len.0 == self.len.1
+    }
+}
+
+impl std::fmt::Display for Comparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_identical() {
+            return write!(f, "identical, {} frames", self.len.0);
+        }
+        if self.len.0 != self.len.1 {
+            write!(f, "length {} differs from {}, ", self.len.0, self.len.1)?;
+        }
+        write!(f, "{} samples differ", self.count)?;
+        if let Some(n) = self.first {
+            write!(f, " from frame {n}, max difference {:e}", self.max)?;
+        }
+        let zero: usize = self.channels.iter().map(|o| o.signed_zero).sum();
+        if zero > 0 {
+            write!(f, ", {zero} in sign of zero")?;
+        }
+        let nan: usize = self.channels.iter().map(|o| o.nan_payload).sum();
+        if nan > 0 {
+            write!(f, ", {nan} in NaN payload")?;
+        }
+        Ok(())
+    }
+}
+
+///Compares bit patterns of samples of a and b, as a check that a conversion is lossless, so +0.0 differs from -0.0 and NaN from NaN of another payload. Frames beyond the shorter block are not compared.
+pub fn compare<T: Channels>(a: &[T], b: &[T]) -> Comparison {
+    let mut channels = vec![ChannelDifference::default(); T::CHANNEL_SIZE as usize];
+    let mut first = None;
+    for (i, (x, y)) in a.iter().zip(b).enumerate() {
+        for (n, c) in channels.iter_mut().enumerate() {
+            let (x, y) = (x.get_channel(n), y.get_channel(n));
+            if x.to_bits() == y.to_bits() {
+                continue;
+            }
+            c.first.get_or_insert(i);
+            first.get_or_insert(i);
+            c.count += 1;
+            let nan = |o: T::Scalar| o.partial_cmp(&o).is_none();
+            if nan(x) && nan(y) {
+                c.nan_payload += 1;
+                continue;
+            }
+            if x == y {
+                c.signed_zero += 1;
+                continue;
+            }
+            let d = (x.to_unit() - y.to_unit()).abs();
+            c.max = c.max.max(if d.is_nan() { f64::INFINITY } else { d });
+        }
+    }
+    Comparison {
+        len: (a.len(), b.len()),
+        first,
+        count: channels.iter().map(|o| o.count).sum(),
+        max: channels.iter().fold(0.0, |m, o| m.max(o.max)),
+        channels,
+    }
+}
@@ -0,0 +1,459 @@
+use crate::error::*;
+use crate::format::*;
+use crate::sample::*;
+use std::marker::PhantomData;
+
+#[cfg(target_arch = "x86_64")]
+mod sse2 {
+    use std::arch::x86_64::*;
+
+    //rounds half away from zero as f64::round for values within i32, NaN to 0.
+    #[inline(always)]
+    unsafe fn round(y: __m128) -> __m128i {
+        let t = _mm_cvttps_epi32(y);
+        let frac = _mm_sub_ps(y, _mm_cvtepi32_ps(t));
+        let up = _mm_castps_si128(_mm_cmpge_ps(frac, _mm_set1_ps(0.5)));
+        let down = _mm_castps_si128(_mm_cmple_ps(frac, _mm_set1_ps(-0.5)));
+        let t = _mm_add_epi32(_mm_sub_epi32(t, up), down);
+        _mm_and_si128(t, _mm_castps_si128(_mm_cmpord_ps(y, y)))
+    }
+
+    pub(super) unsafe fn i16_to_f32(src: &[i16], dst: &mut [f32]) -> usize {
+        let k = _mm_set1_ps(1.0 / 32768.0);
+        let mut i = 0;
+        while i + 8 <= src.len() {
+            let x = _mm_loadu_si128(src.as_ptr().add(i) as *const __m128i);
+            let lo = _mm_srai_epi32(_mm_unpacklo_epi16(x, x), 16);
+            let hi = _mm_srai_epi32(_mm_unpackhi_epi16(x, x), 16);
+            let p = dst.as_mut_ptr().add(i);
+            _mm_storeu_ps(p, _mm_mul_ps(_mm_cvtepi32_ps(lo), k));
+            _mm_storeu_ps(p.add(4), _mm_mul_ps(_mm_cvtepi32_ps(hi), k));
+            i += 8;
+        }
+        i
+    }
+
+    pub(super) unsafe fn f32_to_i16(src: &[f32], dst: &mut [i16]) -> usize {
+        let k = _mm_set1_ps(32768.0);
+        let (lo, hi) = (_mm_set1_ps(-32768.0), _mm_set1_ps(32767.0));
+        let mut i = 0;
+        while i + 8 <= src.len() {
+            let p = src.as_ptr().add(i);
+            let a = _mm_mul_ps(_mm_loadu_ps(p), k);
+            let b = _mm_mul_ps(_mm_loadu_ps(p.add(4)), k);
+            //NaN passes min and max as the second operand.
+            let a = round(_mm_min_ps(hi, _mm_max_ps(lo, a)));
+            let b = round(_mm_min_ps(hi, _mm_max_ps(lo, b)));
+            _mm_storeu_si128(
+                dst.as_mut_ptr().add(i) as *mut __m128i,
+                _mm_packs_epi32(a, b),
+            );
+            i += 8;
+        }
+        i
+    }
+
+    pub(super) unsafe fn i32_to_f32(src: &[i32], dst: &mut [f32]) -> usize {
+        let k = _mm_set1_ps(1.0 / 2147483648.0);
+        let mut i = 0;
+        while i + 4 <= src.len() {
+            let x = _mm_loadu_si128(src.as_ptr().add(i) as *const __m128i);
+            _mm_storeu_ps(dst.as_mut_ptr().add(i), _mm_mul_ps(_mm_cvtepi32_ps(x), k));
+            i += 4;
+        }
+        i
+    }
+
+    pub(super) unsafe fn f32_to_i32(src: &[f32], dst: &mut [i32]) -> usize {
+        let k = _mm_set1_ps(2147483648.0);
+        let max = _mm_set1_epi32(i32::MAX);
+        let mut i = 0;
+        while i + 4 <= src.len() {
+            let y = _mm_max_ps(
+                _mm_set1_ps(-2147483648.0),
+                _mm_mul_ps(_mm_loadu_ps(src.as_ptr().add(i)), k),
+            );
+            //values from 2^31 saturate.
+            let over = _mm_castps_si128(_mm_cmpge_ps(y, k));
+            let t = _mm_or_si128(_mm_andnot_si128(over, round(y)), _mm_and_si128(over, max));
+            _mm_storeu_si128(dst.as_mut_ptr().add(i) as *mut __m128i, t);
+            i += 4;
+        }
+        i
+    }
+}
+
+//fails unless destination is as long as source.
+pub(crate) fn check_len(src: usize, dst: usize) -> Result<(), Error> {
+    if src == dst {
+        Ok(())
+    } else {
+        Err(Error::LengthMismatch {
+            expected: src,
+            found: dst,
+        })
+    }
+}
+
+macro_rules! kernel {
+    ($(#[$m:meta])* $name:ident, $s:ty, $d:ty, $f:expr) => {
+        $(#[$m])*
+        pub fn $name(src: &[$s], dst: &mut [$d]) -> Result<(), Error> {
+            check_len(src.len(), dst.len())?;
+            fn serial(src: &[$s], dst: &mut [$d]) {
+                #[cfg(target_arch = "x86_64")]
+                let i = unsafe { sse2::$name(src, dst) };
+                #[cfg(not(target_arch = "x86_64"))]
+                let i = 0;
+                for (d, s) in dst[i..].iter_mut().zip(&src[i..]) {
+                    *d = $f(*s);
+                }
+            }
+            #[cfg(feature = "rayon")]
+            if src.len() >= crate::par::PAR_LEN {
+                use rayon::prelude::*;
+                src.par_chunks(crate::par::PAR_LEN)
+                    .zip(dst.par_chunks_mut(crate::par::PAR_LEN))
+                    .for_each(|(s, d)| serial(s, d));
+                return Ok(());
+            }
+            serial(src, dst);
+            Ok(())
+        }
+    };
+}
+
+kernel!(
+    ///Converts i16 samples to f32 normalized to full scale 1.0 in the Asymmetric convention, vectorized where available. Fails if lengths differ. Use `convert_scaled` for other conventions.
+    i16_to_f32,
+    i16,
+    f32,
+    |s: i16| s.to_unit() as f32
+);
+
+kernel!(
+    ///Converts f32 samples normalized to full scale 1.0 to i16 in the Asymmetric convention, rounding and clamping, vectorized where available. Fails if lengths differ. Use `convert_scaled` for other conventions.
+    f32_to_i16,
+    f32,
+    i16,
+    |s: f32| i16::from_unit(s as f64)
+);
+
+kernel!(
+    ///Converts i32 samples to f32 normalized to full scale 1.0 in the Asymmetric convention, vectorized where available. Fails if lengths differ. Use `convert_scaled` for other conventions.
+    i32_to_f32,
+    i32,
+    f32,
+    |s: i32| s.to_unit() as f32
+);
+
+kernel!(
+    ///Converts f32 samples normalized to full scale 1.0 to i32 in the Asymmetric convention, rounding and clamping, vectorized where available. Fails if lengths differ. Use `convert_scaled` for other conventions.
+    f32_to_i32,
+    f32,
+    i32,
+    |s: f32| i32::from_unit(s as f64)
+);
+
+///Mapping between integer samples and float samples of full scale 1.0. Float samples are not affected.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ScalingConvention {
+    ///Divides and multiplies by 2^(bits-1), so -1.0 is the minimum and 1.0 clips to the maximum. As FFmpeg and dasp.
+    #[default]
+    Asymmetric,
+    ///Divides and multiplies by 2^(bits-1) - 1, so ±1.0 are the maximum and the negated maximum, and the minimum is never written.
+    Symmetric,
+    ///Scales negative values by 2^(bits-1) and positive values by 2^(bits-1) - 1, so ±1.0 are the minimum and maximum. As common Web Audio encoders.
+    SignSplit,
+}
+
+impl ScalingConvention {
+    //returns the integer value of v of full scale, neither rounded nor clamped.
+    fn encode(self, v: f64, full: f64) -> f64 {
+        match self {
+            Self::Asymmetric => v * full,
+            Self::Symmetric => v * (full - 1.0),
+            Self::SignSplit if v < 0.0 => v * full,
+            Self::SignSplit => v * (full - 1.0),
+        }
+    }
+
+    //returns float value of integer value i of full scale.
+    fn decode(self, i: f64, full: f64) -> f64 {
+        match self {
+            Self::Asymmetric => i / full,
+            Self::Symmetric => i / (full - 1.0),
+            Self::SignSplit if i < 0.0 => i / full,
+            Self::SignSplit => i / (full - 1.0),
+        }
+    }
+
+    //returns the lowest integer value written of full scale.
+    fn min(self, full: f64) -> f64 {
+        match self {
+            Self::Symmetric => 1.0 - full,
+            _ => -full,
+        }
+    }
+
+    ///Returns v normalized to full scale 1.0.
+    pub fn to_unit<S: Scalar>(self, v: S) -> f64 {
+        if S::FULL_SCALE == 1.0 {
+            return v.to_f64();
+        }
+        self.decode(v.to_f64() - S::ZERO, S::FULL_SCALE)
+    }
+
+    ///Converts v normalized to full scale 1.0 to S, rounding and clamping for integer.
+    pub fn from_unit<S: Scalar>(self, v: f64) -> S {
+        if S::FULL_SCALE == 1.0 {
+            return S::from_f64(v);
+        }
+        let full = S::FULL_SCALE;
+        let i = self
+            .encode(v, full)
+            .round()
+            .clamp(self.min(full), full - 1.0);
+        S::from_f64(i + S::ZERO)
+    }
+
+    ///Returns v of integer of bits re-expressed in the Asymmetric convention, where steps are 2^(1-bits), clamped to the range written.
+    pub fn to_asymmetric(self, v: f64, bits: u32) -> f64 {
+        let full = (1u64 << (bits.clamp(1, 64) - 1)) as f64;
+        self.encode(v, full).clamp(self.min(full), full - 1.0) / full
+    }
+
+    ///Returns v of integer of bits normalized in the Asymmetric convention, re-expressed in self.
+    pub fn from_asymmetric(self, v: f64, bits: u32) -> f64 {
+        let full = (1u64 << (bits.clamp(1, 64) - 1)) as f64;
+        self.decode(v * full, full)
+    }
+
+    //returns the lowest value written of integer of bits in the Asymmetric convention.
+    pub(crate) fn min_asymmetric(self, bits: u32) -> f64 {
+        let full = (1u64 << (bits.clamp(1, 64) - 1)) as f64;
+        self.min(full) / full
+    }
+}
+
+///Converts samples from S to D through values normalized to full scale 1.0 in convention, rounding and clamping for integer. Fails if lengths differ.
+pub fn convert_scaled<S: Scalar, D: Scalar>(
+    src: &[S],
+    dst: &mut [D],
+    convention: ScalingConvention,
+) -> Result<(), Error> {
+    check_len(src.len(), dst.len())?;
+    for (d, s) in dst.iter_mut().zip(src) {
+        *d = convention.from_unit(convention.to_unit(*s));
+    }
+    Ok(())
+}
+
+///An iterator of samples of a block, interleaved, converted to U on the fly.
+pub struct SamplesAs<'a, T, U> {
+    data: &'a [T],
+    index: usize,
+    convention: ScalingConvention,
+    marker: PhantomData<U>,
+}
+
+impl<T, U> std::fmt::Debug for SamplesAs<'_, T, U>
+where
+    T: Channels,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("SamplesAs");
+        f.field("index", &self.index)
+            .field("convention", &self.convention)
+            .field("data_size", &self.data.len())
+            .finish()
+    }
+}
+
+impl<T, U> Iterator for SamplesAs<'_, T, U>
+where
+    T: Channels,
+    U: Scalar,
+{
+    type Item = U;
+
+    fn next(&mut self) -> Option<U> {
+        let c = T::CHANNEL_SIZE as usize;
+        let f = self.data.get(self.index / c)?;
+        let s = f.get_channel(self.index % c);
+        self.index += 1;
+        Some(self.convention.from_unit(self.convention.to_unit(s)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.data.len() * T::CHANNEL_SIZE as usize - self.index;
+        (n, Some(n))
+    }
+}
+
+impl<T, U> ExactSizeIterator for SamplesAs<'_, T, U>
+where
+    T: Channels,
+    U: Scalar,
+{
+}
+
+block_impl! {
+    impl<T> Block<T>
+    where
+        T: Channels,
+    {
+        ///Returns an iterator of samples, interleaved, converted to U without copying the block.
+        pub fn iter_as<U: Scalar>(&self) -> SamplesAs<'_, T, U> {
+            self.iter_as_with(ScalingConvention::Asymmetric)
+        }
+
+        ///Returns an iterator like `iter_as`, scaling integers in convention.
+        pub fn iter_as_with<U: Scalar>(&self, convention: ScalingConvention) -> SamplesAs<'_, T, U> {
+            SamplesAs {
+                data: self,
+                index: 0,
+                convention,
+                marker: PhantomData,
+            }
+        }
+    }
+}
+
+block_impl! {
+    impl<S, const N: usize> Block<[S; N]>
+    where
+        S: Scalar,
+        [S; N]: Channels<Scalar = S>,
+    {
+        ///Returns an iterator of frames converted to U without copying the block.
+        pub fn frames_as<U: Scalar>(&self) -> impl ExactSizeIterator<Item = [U; N]> + '_ {
+            self.frames_as_with(ScalingConvention::Asymmetric)
+        }
+
+        ///Returns an iterator like `frames_as`, scaling integers in convention.
+        pub fn frames_as_with<U: Scalar>(
+            &self,
+            convention: ScalingConvention,
+        ) -> impl ExactSizeIterator<Item = [U; N]> + '_ {
+            self.iter()
+                .map(move |f| std::array::from_fn(|n| convention.from_unit(convention.to_unit(f[n]))))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //pseudo-random values, deterministic across runs.
+    fn noise(n: usize) -> impl Iterator<Item = u32> {
+        let mut x = 0x9E37_79B9u32;
+        (0..n).map(move |_| {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            x
+        })
+    }
+
+    fn floats() -> Vec<f32> {
+        let step = 1.0 / 32768.0;
+        let fine = 1.0 / 2147483648.0;
+        let mut v = vec![
+            0.0,
+            -0.0,
+            1.0,
+            -1.0,
+            1.0 - f32::EPSILON,
+            -1.0 + f32::EPSILON,
+            2.0,
+            -2.0,
+            0.5 * step,
+            -0.5 * step,
+            1.5 * step,
+            -1.5 * step,
+            0.5 * fine,
+            -0.5 * fine,
+            2.5 * fine,
+            -2.5 * fine,
+            f32::NAN,
+            -f32::NAN,
+            f32::INFINITY,
+            f32::NEG_INFINITY,
+            f32::MAX,
+            f32::MIN,
+            f32::MIN_POSITIVE / 2.0,
+        ];
+        v.extend(noise(61).map(|x| (x as i32) as f32 / 1.5e9));
+        v
+    }
+
+    //runs f on every length and start offset of src, comparing bits with the scalar g.
+    fn check<S: Copy, D: Copy + Default>(
+        src: &[S],
+        f: fn(&[S], &mut [D]) -> Result<(), Error>,
+        g: impl Fn(S) -> D,
+        bits: impl Fn(D) -> u64,
+    ) {
+        for start in 0..4 {
+            for end in start..=src.len() {
+                let s = &src[start..end];
+                let mut d = vec![D::default(); s.len() + 1];
+                f(s, &mut d[1..]).unwrap();
+                for (n, (a, b)) in d[1..].iter().zip(s.iter()).enumerate() {
+                    assert_eq!(bits(*a), bits(g(*b)), "sample {} of {}..{}", n, start, end);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn i16_to_f32_matches_scalar() {
+        let mut src = vec![i16::MIN, i16::MAX, 0, -1, 1, i16::MIN + 1];
+        src.extend(noise(41).map(|x| x as i16));
+        check(
+            &src,
+            i16_to_f32,
+            |s| s.to_unit() as f32,
+            |d| d.to_bits() as u64,
+        );
+    }
+
+    #[test]
+    fn f32_to_i16_matches_scalar() {
+        check(
+            &floats(),
+            f32_to_i16,
+            |s| i16::from_unit(s as f64),
+            |d| d as u64,
+        );
+    }
+
+    #[test]
+    fn i32_to_f32_matches_scalar() {
+        let mut src = vec![i32::MIN, i32::MAX, 0, -1, 1, i32::MIN + 1, 0xFF_FFFF];
+        src.extend(noise(41).map(|x| x as i32));
+        check(
+            &src,
+            i32_to_f32,
+            |s| s.to_unit() as f32,
+            |d| d.to_bits() as u64,
+        );
+    }
+
+    #[test]
+    fn f32_to_i32_matches_scalar() {
+        check(
+            &floats(),
+            f32_to_i32,
+            |s| i32::from_unit(s as f64),
+            |d| d as u64,
+        );
+    }
+
+    #[test]
+    fn length_mismatch() {
+        assert!(i16_to_f32(&[0; 3], &mut [0.0; 2]).is_err());
+    }
+}
@@ -0,0 +1,141 @@
+use crate::biquad::*;
+use crate::db::*;
+use crate::format::*;
+use crate::sample::*;
+use std::f32::consts::FRAC_1_SQRT_2;
+
+///A 4th order Linkwitz-Riley filter pair splitting at a frequency.
+#[derive(Clone, Debug)]
+pub struct LinkwitzRiley {
+    low: [Biquad; 2],
+    high: [Biquad; 2],
+}
+
+impl LinkwitzRiley {
+    ///Constructs a new LinkwitzRiley.
+    pub fn new(sample_rate: u32, freq: f32) -> Self {
+        let lp = Biquad::design(
+            FilterType::LowPass,
+            sample_rate,
+            freq,
+            FRAC_1_SQRT_2,
+            Db::UNITY,
+        );
+        let hp = Biquad::design(
+            FilterType::HighPass,
+            sample_rate,
+            freq,
+            FRAC_1_SQRT_2,
+            Db::UNITY,
+        );
+        Self {
+            low: [lp.clone(), lp],
+            high: [hp.clone(), hp],
+        }
+    }
+
+    ///Clears the internal state.
+    pub fn reset(&mut self) {
+        for f in self.low.iter_mut().chain(self.high.iter_mut()) {
+            f.reset();
+        }
+    }
+
+    ///Splits block into low and high bands.
+    pub fn split<T: Frame>(&mut self, o: &Block<T>) -> (Block<T>, Block<T>) {
+        let mut low = Block::from(o.to_vec());
+        let mut high = Block::from(o.to_vec());
+        for f in self.low.iter_mut() {
+            f.process(&mut low);
+        }
+        for f in self.high.iter_mut() {
+            f.process(&mut high);
+        }
+        (low, high)
+    }
+
+    ///Processes block in place by the sum of both bands, which is an allpass.
+    pub fn allpass<T: Frame>(&mut self, o: &mut Block<T>) {
+        let (low, high) = self.split(o);
+        *o = Crossover::combine(&[low, high]);
+    }
+}
+
+///A multiband splitter of cascaded Linkwitz-Riley filters.
+#[derive(Clone, Debug)]
+pub struct Crossover {
+    freqs: Vec<f32>,
+    splits: Vec<LinkwitzRiley>,
+    compensation: Vec<Vec<LinkwitzRiley>>,
+}
+
+impl Crossover {
+    ///Constructs a new Crossover of ascending frequencies, which makes `freqs.len() + 1` bands.
+    pub fn new(sample_rate: u32, freqs: Vec<f32>) -> Self {
+        let splits = freqs
+            .iter()
+            .map(|f| LinkwitzRiley::new(sample_rate, *f))
+            .collect();
+        let compensation = (0..freqs.len())
+            .map(|i| {
+                freqs[i + 1..]
+                    .iter()
+                    .map(|f| LinkwitzRiley::new(sample_rate, *f))
+                    .collect()
+            })
+            .collect();
+        Self {
+            freqs,
+            splits,
+            compensation,
+        }
+    }
+
+    ///Returns crossover frequencies.
+    pub fn freqs(&self) -> &[f32] {
+        &self.freqs
+    }
+
+    ///Clears the internal state.
+    pub fn reset(&mut self) {
+        for f in self
+            .splits
+            .iter_mut()
+            .chain(self.compensation.iter_mut().flatten())
+        {
+            f.reset();
+        }
+    }
+
+    ///Splits block into bands from low to high. Lower bands are allpass compensated so the sum is flat.
+    pub fn split<T: Frame>(&mut self, o: &Block<T>) -> Vec<Block<T>> {
+        let mut v = Vec::with_capacity(self.freqs.len() + 1);
+        let mut rest = Block::from(o.to_vec());
+        for (s, c) in self.splits.iter_mut().zip(self.compensation.iter_mut()) {
+            let (mut low, high) = s.split(&rest);
+            for f in c.iter_mut() {
+                f.allpass(&mut low);
+            }
+            v.push(low);
+            rest = high;
+        }
+        v.push(rest);
+        v
+    }
+
+    ///Sums bands into a block of the shortest length.
+    pub fn combine<T: Frame>(bands: &[Block<T>]) -> Block<T> {
+        let n = bands.iter().map(|o| o.len()).min().unwrap_or(0);
+        let mut v = Block::new(n);
+        for i in 0..n {
+            let mut f = T::default();
+            for b in bands {
+                for c in 0..T::CHANNEL_SIZE as usize {
+                    f.set_channel(c, f.channel(c) + b[i].channel(c));
+                }
+            }
+            v.push(f);
+        }
+        v
+    }
+}
@@ -0,0 +1,136 @@
+use crate::format::*;
+use crate::sample::*;
+use std::ops::{Deref, Range};
+
+///A borrowed view of contiguous frames read by a Cursor.
+pub struct BlockView<'a, T> {
+    frame: usize,
+    data: &'a [T],
+}
+
+impl<T> std::fmt::Debug for BlockView<'_, T>
+where
+    T: Sample,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("BlockView");
+        f.field("frame", &self.frame)
+            .field("data_size", &self.data.len())
+            .finish()
+    }
+}
+
+impl<'a, T> BlockView<'a, T> {
+    ///Returns the position of the first frame in the whole.
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    ///Returns the frames.
+    pub fn as_slice(&self) -> &'a [T] {
+        self.data
+    }
+}
+
+impl<T> Deref for BlockView<'_, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.data
+    }
+}
+
+///A playback position over a whole, with an optional loop region.
+pub struct Cursor<'a, T> {
+    whole: &'a Whole<T>,
+    position: usize,
+    looping: Option<Range<usize>>,
+}
+
+impl<T> std::fmt::Debug for Cursor<'_, T>
+where
+    T: Sample,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("Cursor");
+        f.field("position", &self.position)
+            .field("looping", &self.looping)
+            .field("data_size", &self.whole.len())
+            .finish()
+    }
+}
+
+impl<'a, T> Cursor<'a, T>
+where
+    T: Sample,
+{
+    ///Constructs a new Cursor at the start of whole.
+    pub fn new(whole: &'a Whole<T>) -> Self {
+        Self {
+            whole,
+            position: 0,
+            looping: None,
+        }
+    }
+
+    ///Returns position in frames.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    ///Returns position in seconds.
+    pub fn time(&self) -> f64 {
+        self.position as f64 / self.whole.sample_rate().get() as f64
+    }
+
+    ///Returns true if there is no frame left to read.
+    pub fn is_end(&self) -> bool {
+        self.position >= self.whole.len()
+    }
+
+    ///Moves to frame, clamped to the end.
+    pub fn seek_to_frame(&mut self, frame: usize) {
+        self.position = frame.min(self.whole.len());
+    }
+
+    ///Moves to the frame nearest to seconds, clamped.
+    pub fn seek_to_time(&mut self, seconds: f64) {
+        let frame = (seconds.max(0.0) * self.whole.sample_rate().get() as f64).round();
+        self.seek_to_frame(frame as usize);
+    }
+
+    ///Returns the loop region.
+    pub fn loop_range(&self) -> Option<Range<usize>> {
+        self.looping.clone()
+    }
+
+    ///Sets the loop region, clamped, or None to play through. Reading from before its end wraps to its start.
+    pub fn set_loop(&mut self, range: Option<Range<usize>>) {
+        self.looping = range.and_then(|o| {
+            let end = o.end.min(self.whole.len());
+            let start = o.start.min(end);
+            (start < end).then_some(start..end)
+        });
+    }
+
+    ///Reads up to frames, fewer at the loop end or the end of the whole, and advances. Returns an empty view at the end.
+    pub fn read(&mut self, frames: usize) -> BlockView<'a, T> {
+        let limit = match &self.looping {
+            Some(o) if self.position < o.end => o.end,
+            _ => self.whole.len(),
+        };
+        let start = self.position;
+        let end = start + frames.min(limit - start);
+        self.position = end;
+        if let Some(o) = &self.looping {
+            if end == o.end && end > start {
+                self.position = o.start;
+            }
+        }
+        let whole: &'a Whole<T> = self.whole;
+        BlockView {
+            frame: start,
+            data: &whole[start..end],
+        }
+    }
+}
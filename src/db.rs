@@ -0,0 +1,176 @@
+use crate::format::*;
+use crate::sample::*;
+use crate::shared::*;
+use std::ops::{Add, AddAssign, Neg, Range, Sub, SubAssign};
+
+///A level in decibels, relative to a linear gain of 1.0 or, as dBFS, to full scale.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct Db(pub f32);
+
+impl Db {
+    ///Unity gain.
+    pub const UNITY: Db = Db(0.0);
+
+    ///Converts linear gain to Db. Gain of 0 or below gives a very low finite level.
+    pub fn from_gain(gain: f32) -> Self {
+        Self(20.0 * gain.max(f32::MIN_POSITIVE).log10())
+    }
+
+    ///Converts Self to linear gain.
+    pub fn to_gain(self) -> f32 {
+        10f32.powf(self.0 / 20.0)
+    }
+
+    ///Converts power ratio to Db.
+    pub fn from_power(power: f32) -> Self {
+        Self(10.0 * power.max(f32::MIN_POSITIVE).log10())
+    }
+
+    ///Converts Self to power ratio.
+    pub fn to_power(self) -> f32 {
+        10f32.powf(self.0 / 10.0)
+    }
+
+    ///Returns level of value in dBFS, relative to the full scale of its type.
+    pub fn dbfs<S: Scalar>(value: S) -> Self {
+        Self::from_gain(value.to_unit().abs() as f32)
+    }
+
+    ///Returns the positive value of the type at level in dBFS, saturating at its maximum.
+    pub fn to_scalar<S: Scalar>(self) -> S {
+        S::from_unit((self.to_gain() as f64).min(S::MAX_UNIT))
+    }
+}
+
+impl Add for Db {
+    type Output = Self;
+
+    fn add(self, o: Self) -> Self {
+        Self(self.0 + o.0)
+    }
+}
+
+impl Sub for Db {
+    type Output = Self;
+
+    fn sub(self, o: Self) -> Self {
+        Self(self.0 - o.0)
+    }
+}
+
+impl AddAssign for Db {
+    fn add_assign(&mut self, o: Self) {
+        self.0 += o.0;
+    }
+}
+
+impl SubAssign for Db {
+    fn sub_assign(&mut self, o: Self) {
+        self.0 -= o.0;
+    }
+}
+
+impl Neg for Db {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(-self.0)
+    }
+}
+
+impl std::fmt::Display for Db {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match f.precision() {
+            Some(p) => write!(f, "{:.*} dB", p, self.0),
+            None => write!(f, "{} dB", self.0),
+        }
+    }
+}
+
+block_impl! {
+    impl<T> Block<T>
+    where
+        T: Frame,
+    {
+        ///Multiplies all frames by gain.
+        pub fn apply_gain(&mut self, gain: Db) {
+            let g = gain.to_gain();
+            for i in self.iter_mut() {
+                i.scale(g);
+            }
+        }
+
+        ///Scales frames so that the peak of all channels reaches target, and returns the gain applied. Silence is left unchanged at unity gain.
+        pub fn normalize_peak(&mut self, target: Db) -> Db {
+            let peak = self.iter().fold(0.0, |m, o| o.peak().max(m));
+            if peak == 0.0 {
+                return Db::UNITY;
+            }
+            let gain = target - Db::from_gain(peak);
+            self.apply_gain(gain);
+            gain
+        }
+    }
+}
+
+impl<T> CowWhole<T>
+where
+    T: Frame,
+{
+    ///Multiplies frames in range, clamped, by gain in Db, copying only those frames.
+    pub fn gain_db(&mut self, range: Range<usize>, gain: Db) {
+        self.gain(range, gain.to_gain());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() <= 1e-4 * b.abs().max(1.0)
+    }
+
+    #[test]
+    fn gain_round_trip() {
+        for gain in [1e-6, 0.001, 0.25, 0.5, 1.0, 2.0, 10.0, 1000.0] {
+            assert!(close(Db::from_gain(gain).to_gain(), gain), "{gain}");
+            assert!(close(Db::from_power(gain).to_power(), gain), "{gain}");
+        }
+        for db in [-120.0, -60.0, -6.0, 0.0, 3.0, 24.0] {
+            assert!(close(Db::from_gain(Db(db).to_gain()).0, db), "{db}");
+            assert!(close(Db::from_power(Db(db).to_power()).0, db), "{db}");
+        }
+    }
+
+    #[test]
+    fn known_levels() {
+        assert_eq!(Db::from_gain(1.0), Db::UNITY);
+        assert!(close(Db::from_gain(0.5).0, -6.0206));
+        assert!(close(Db::from_power(0.5).0, -3.0103));
+        assert!(close(Db(-20.0).to_gain(), 0.1));
+        assert_eq!(Db(-6.0) - Db(6.0) + -Db(-12.0), Db::UNITY);
+        let silence = Db::from_gain(0.0);
+        assert!(silence.0.is_finite() && silence.0 < -700.0);
+        assert_eq!(Db::from_gain(-1.0), silence);
+    }
+
+    #[test]
+    fn dbfs() {
+        assert_eq!(Db::dbfs(i16::MIN), Db::UNITY);
+        assert_eq!(Db::dbfs(-1.0f32), Db::UNITY);
+        assert!(close(Db::dbfs(0.5f32).0, -6.0206));
+        assert!(close(Db::dbfs(16384i16).0, -6.0206));
+        assert_eq!(Db::UNITY.to_scalar::<i16>(), i16::MAX);
+        assert_eq!(Db(12.0).to_scalar::<i16>(), i16::MAX);
+        assert_eq!(Db(12.0).to_scalar::<u8>(), u8::MAX);
+        let half = Db::from_gain(0.5).to_scalar::<i16>();
+        assert!((16383..=16384).contains(&half), "{half}");
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Db(-6.0206).to_string(), "-6.0206 dB");
+        assert_eq!(format!("{:.1}", Db(-6.0206)), "-6.0 dB");
+    }
+}
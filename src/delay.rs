@@ -0,0 +1,79 @@
+use crate::format::*;
+use crate::sample::*;
+use crate::util::*;
+
+///A feedback delay keeping its ring buffer across successive blocks.
+#[derive(Clone, Debug)]
+pub struct Delay {
+    delay: usize,
+    feedback: f32,
+    mix: f32,
+    buffer: Vec<Vec<f32>>,
+    pos: usize,
+}
+
+impl Delay {
+    ///Constructs a new Delay of at least one frame. Mix is the wet proportion from 0 to 1.
+    pub fn new(delay: usize, feedback: f32, mix: f32) -> Self {
+        Self {
+            delay: delay.max(1),
+            feedback,
+            mix: mix.clamp(0.0, 1.0),
+            buffer: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    ///Constructs a new Delay of milliseconds.
+    pub fn from_ms(sample_rate: u32, ms: f32, feedback: f32, mix: f32) -> Self {
+        Self::new(ms_to_frames(ms, sample_rate), feedback, mix)
+    }
+
+    ///Returns delay in frames.
+    pub fn delay(&self) -> usize {
+        self.delay
+    }
+
+    ///Changes delay in frames, which clears the internal state.
+    pub fn set_delay(&mut self, delay: usize) {
+        self.delay = delay.max(1);
+        self.buffer.clear();
+        self.pos = 0;
+    }
+
+    ///Sets feedback.
+    pub fn set_feedback(&mut self, feedback: f32) {
+        self.feedback = feedback;
+    }
+
+    ///Sets wet proportion from 0 to 1.
+    pub fn set_mix(&mut self, mix: f32) {
+        self.mix = mix.clamp(0.0, 1.0);
+    }
+
+    ///Clears the internal state.
+    pub fn reset(&mut self) {
+        for b in self.buffer.iter_mut() {
+            b.fill(0.0);
+        }
+        self.pos = 0;
+    }
+
+    ///Processes block in place.
+    pub fn process<T: Frame>(&mut self, o: &mut Block<T>) {
+        let c = T::CHANNEL_SIZE as usize;
+        if self.buffer.len() != c {
+            self.buffer = vec![vec![0.0; self.delay]; c];
+            self.pos = 0;
+        }
+        for i in o.iter_mut() {
+            for (n, b) in self.buffer.iter_mut().enumerate() {
+                let x = i.channel(n);
+                let d = b[self.pos];
+                b[self.pos] = x + d * self.feedback;
+                i.set_channel(n, x * (1.0 - self.mix) + d * self.mix);
+            }
+            self.pos = (self.pos + 1) % self.delay;
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use crate::db::*;
+use crate::format::*;
+use crate::sample::*;
+use crate::stft::*;
+use crate::window::*;
+use std::ops::Range;
+
+const FFT_SIZE: usize = 2048;
+const HOP: usize = 512;
+
+///A spectral noise reduction of a learned noise profile.
+#[derive(Clone, Debug)]
+pub struct NoiseReduction {
+    profile: Vec<Vec<f32>>,
+    strength: f32,
+    floor: f32,
+}
+
+fn power(o: &Stft) -> Vec<Vec<f32>> {
+    o.frames
+        .iter()
+        .map(|f| {
+            f.iter()
+                .map(|c| (c.re * c.re + c.im * c.im) as f32)
+                .collect()
+        })
+        .collect()
+}
+
+fn average(frames: &[&Vec<f32>]) -> Vec<f32> {
+    let mut v = vec![0.0f32; FFT_SIZE / 2 + 1];
+    for f in frames {
+        for (a, b) in v.iter_mut().zip(f.iter()) {
+            *a += b;
+        }
+    }
+    let n = frames.len().max(1) as f32;
+    v.iter_mut().for_each(|o| *o /= n);
+    v
+}
+
+impl NoiseReduction {
+    fn with_profile(profile: Vec<Vec<f32>>) -> Self {
+        Self {
+            profile,
+            strength: 2.0,
+            floor: Db(-30.0).to_gain(),
+        }
+    }
+
+    ///Learns noise profile of each channel from a frame range of noise only.
+    pub fn learn<T: Frame>(o: &Whole<T>, range: Range<usize>) -> Self {
+        let profile = (0..T::CHANNEL_SIZE as usize)
+            .map(|n| {
+                let s = Stft::from_channel(o, n, FFT_SIZE, HOP, Window::Hann);
+                let p = power(&s);
+                let frames: Vec<&Vec<f32>> = p
+                    .iter()
+                    .enumerate()
+                    .filter(|(t, _)| range.contains(&(t * HOP)))
+                    .map(|o| o.1)
+                    .collect();
+                average(&frames)
+            })
+            .collect();
+        Self::with_profile(profile)
+    }
+
+    ///Learns noise profile of each channel from the quietest proportion (0 to 1) of analysis frames.
+    pub fn learn_quietest<T: Frame>(o: &Whole<T>, proportion: f32) -> Self {
+        let profile = (0..T::CHANNEL_SIZE as usize)
+            .map(|n| {
+                let s = Stft::from_channel(o, n, FFT_SIZE, HOP, Window::Hann);
+                let p = power(&s);
+                let mut frames: Vec<&Vec<f32>> = p.iter().collect();
+                frames.sort_by(|a, b| a.iter().sum::<f32>().total_cmp(&b.iter().sum::<f32>()));
+                let k = ((frames.len() as f32 * proportion.clamp(0.0, 1.0)).ceil() as usize).max(1);
+                frames.truncate(k);
+                average(&frames)
+            })
+            .collect();
+        Self::with_profile(profile)
+    }
+
+    ///Sets over-subtraction factor of the noise profile, 2 by default.
+    pub fn set_strength(&mut self, strength: f32) {
+        self.strength = strength.max(0.0);
+    }
+
+    ///Sets minimum gain of each bin, -30 dB by default.
+    pub fn set_floor(&mut self, floor: Db) {
+        self.floor = floor.to_gain();
+    }
+
+    ///Returns noise power profile of each channel, `FFT_SIZE / 2 + 1` bins of 2048 FFT size.
+    pub fn profile(&self) -> &[Vec<f32>] {
+        &self.profile
+    }
+
+    ///Returns the cleaned whole.
+    pub fn process<T: Frame>(&self, o: &Whole<T>) -> Whole<T> {
+        let mut v = vec![T::default(); o.len()];
+        for (n, noise) in self
+            .profile
+            .iter()
+            .enumerate()
+            .take(T::CHANNEL_SIZE as usize)
+        {
+            let mut s = Stft::from_channel(o, n, FFT_SIZE, HOP, Window::Hann);
+            for f in s.frames.iter_mut() {
+                for (c, p) in f.iter_mut().zip(noise.iter()) {
+                    let x = (c.re * c.re + c.im * c.im) as f32;
+                    let g = if x > 0.0 {
+                        (1.0 - self.strength * p / x)
+                            .max(0.0)
+                            .sqrt()
+                            .max(self.floor)
+                    } else {
+                        self.floor
+                    };
+                    c.re *= g as f64;
+                    c.im *= g as f64;
+                }
+            }
+            for (i, y) in v.iter_mut().zip(s.inverse_slice()) {
+                i.set_channel(n, y);
+            }
+        }
+        Whole::from_block(o.sample_rate(), Block::from(v))
+    }
+}
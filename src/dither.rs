@@ -0,0 +1,207 @@
+use crate::convert::*;
+use crate::db::*;
+use crate::error::*;
+use crate::format::*;
+use crate::sample::*;
+use crate::util::*;
+
+///Dither added before quantization.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Dither {
+    ///Round to the nearest step.
+    #[default]
+    None,
+    ///Triangular PDF noise of ±1 LSB of the target, independent for each channel.
+    Tpdf { seed: u64 },
+    ///TPDF noise with the quantization error fed back through a shaping filter, moving noise to where hearing is less sensitive.
+    Shaped { shape: NoiseShape, seed: u64 },
+}
+
+///Error feedback filter of noise-shaped dither.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NoiseShape {
+    ///First order highpass, rising 6 dB per octave, for any sample rate.
+    Simple,
+    ///5 tap E-weighted filter of Lipshitz et al. for 44.1 kHz, also usable at 48 kHz.
+    Lipshitz,
+    ///3 tap F-weighted filter of Wannamaker for 44.1 and 48 kHz.
+    Wannamaker3,
+    ///9 tap F-weighted filter of Wannamaker for 44.1 and 48 kHz, the strongest shaping.
+    Wannamaker9,
+}
+
+//taps of the most recent error first.
+const SHAPE_SIZE: usize = 9;
+
+impl NoiseShape {
+    fn coefficients(self) -> &'static [f64] {
+        match self {
+            Self::Simple => &[1.0],
+            Self::Lipshitz => &[2.033, -2.165, 1.959, -1.590, 0.6149],
+            Self::Wannamaker3 => &[1.623, -0.982, 0.109],
+            Self::Wannamaker9 => &[
+                2.412, -3.370, 3.937, -4.174, 3.353, -2.205, 1.281, -0.569, 0.0847,
+            ],
+        }
+    }
+}
+
+///A quantizer of samples normalized to full scale 1.0 to bits, applying dither per channel.
+#[derive(Clone, Debug)]
+pub struct Ditherer {
+    dither: Dither,
+    lsb: f64,
+    scaling: ScalingConvention,
+    rng: Vec<Rng>,
+    //recent errors of each channel, the most recent first.
+    error: Vec<[f64; SHAPE_SIZE]>,
+}
+
+impl Ditherer {
+    ///Constructs a new Ditherer to bits, clamped from 1 to 32, for channels.
+    pub fn new(dither: Dither, bits: u32, channel_size: u16) -> Self {
+        let seed = match dither {
+            Dither::None => 0,
+            Dither::Tpdf { seed } | Dither::Shaped { seed, .. } => seed,
+        };
+        let channel_size = channel_size.max(1) as usize;
+        Self {
+            dither,
+            lsb: 1.0 / (1u64 << (bits.clamp(1, 32) - 1)) as f64,
+            scaling: ScalingConvention::Asymmetric,
+            rng: (0..channel_size as u64)
+                .map(|n| Rng::new(seed.wrapping_add(n.wrapping_mul(0xD1B54A32D192ED03))))
+                .collect(),
+            error: vec![[0.0; SHAPE_SIZE]; channel_size],
+        }
+    }
+
+    ///Returns dither.
+    pub fn dither(&self) -> Dither {
+        self.dither
+    }
+
+    ///Returns the step of the target normalized to full scale 1.0.
+    pub fn lsb(&self) -> f64 {
+        self.lsb
+    }
+
+    ///Sets scaling of the integer conversions, ScalingConvention::Asymmetric by default.
+    pub fn set_scaling(&mut self, scaling: ScalingConvention) {
+        self.scaling = scaling;
+    }
+
+    ///Clears the error history of noise shaping.
+    pub fn reset(&mut self) {
+        for o in &mut self.error {
+            *o = [0.0; SHAPE_SIZE];
+        }
+    }
+
+    ///Returns v of channel with dither added and rounded to a step of the target, not clamped.
+    pub fn quantize(&mut self, channel: usize, v: f64) -> f64 {
+        let mut tpdf = || {
+            let r = &mut self.rng[channel];
+            (r.uniform() - r.uniform()) * self.lsb
+        };
+        match self.dither {
+            Dither::None => (v / self.lsb).round() * self.lsb,
+            Dither::Tpdf { .. } => ((v + tpdf()) / self.lsb).round() * self.lsb,
+            Dither::Shaped { shape, .. } => {
+                let d = tpdf();
+                let e = &mut self.error[channel];
+                let h = shape.coefficients();
+                let x = v - h.iter().zip(e.iter()).map(|(h, e)| h * e).sum::<f64>();
+                let q = ((x + d) / self.lsb).round() * self.lsb;
+                e.copy_within(..SHAPE_SIZE - 1, 1);
+                e[0] = q - x;
+                q
+            }
+        }
+    }
+
+    //quantizes interleaved samples in scaling, channels taken in turn.
+    fn run<S: Scalar, D: Scalar>(&mut self, src: &[S], dst: &mut [D]) -> Result<(), Error> {
+        check_len(src.len(), dst.len())?;
+        let c = self.rng.len().max(1);
+        let bits = (8 * size_of::<D>()) as u32;
+        let min = self.scaling.min_asymmetric(bits);
+        for (i, (d, s)) in dst.iter_mut().zip(src).enumerate() {
+            //steps of the target are those of the Asymmetric convention.
+            let v = self.scaling.to_asymmetric(self.scaling.to_unit(*s), bits);
+            *d = D::from_unit(self.quantize(i % c, v).max(min));
+        }
+        Ok(())
+    }
+
+    ///Converts interleaved f32 samples normalized to full scale 1.0 to i16 with dither, clamping. Fails if lengths differ.
+    pub fn f32_to_i16(&mut self, src: &[f32], dst: &mut [i16]) -> Result<(), Error> {
+        self.run(src, dst)
+    }
+
+    ///Converts interleaved f32 samples normalized to full scale 1.0 to i32 with dither, clamping. Fails if lengths differ.
+    pub fn f32_to_i32(&mut self, src: &[f32], dst: &mut [i32]) -> Result<(), Error> {
+        self.run(src, dst)
+    }
+
+    ///Converts interleaved i32 samples, such as 24 bit in the upper bytes, to i16 with dither, clamping. Fails if lengths differ.
+    pub fn i32_to_i16(&mut self, src: &[i32], dst: &mut [i16]) -> Result<(), Error> {
+        self.run(src, dst)
+    }
+}
+
+///Report of quantize_to_bits.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QuantizeReport {
+    ///Bits of the target.
+    pub bits: u32,
+    ///Dither applied.
+    pub dither: Dither,
+    ///Samples clamped to the range of the target.
+    pub clipped: usize,
+    ///Largest amount a sample exceeded the range of the target by, normalized to full scale 1.0.
+    pub max_overshoot: f32,
+}
+
+impl QuantizeReport {
+    ///Returns the gain that would bring the largest overshoot within range, unity if nothing clipped.
+    pub fn headroom_needed(&self) -> Db {
+        if self.max_overshoot > 0.0 {
+            -Db::from_gain(1.0 + self.max_overshoot)
+        } else {
+            Db::UNITY
+        }
+    }
+}
+
+block_impl! {
+    impl<T> Block<T>
+    where
+        T: Frame,
+    {
+        ///Returns frames quantized to bits, clamped from 1 to 32, with dither, and a report of clipping. Samples are clamped to the range of a signed integer of bits, from -1 to 1 - 1 LSB.
+        pub fn quantize_to_bits(&self, bits: u32, dither: Dither) -> (Block<T>, QuantizeReport) {
+            let mut d = Ditherer::new(dither, bits, T::CHANNEL_SIZE);
+            let (lo, hi) = (-1.0, 1.0 - d.lsb());
+            let mut r = QuantizeReport {
+                bits: bits.clamp(1, 32),
+                dither,
+                clipped: 0,
+                max_overshoot: 0.0,
+            };
+            let mut v = Block::from(self.to_vec());
+            for f in v.iter_mut() {
+                for n in 0..T::CHANNEL_SIZE as usize {
+                    let q = d.quantize(n, f.channel(n) as f64);
+                    let over = (q - hi).max(lo - q);
+                    if over > 0.0 {
+                        r.clipped += 1;
+                        r.max_overshoot = r.max_overshoot.max(over as f32);
+                    }
+                    f.set_channel(n, q.clamp(lo, hi) as f32);
+                }
+            }
+            (v, r)
+        }
+    }
+}
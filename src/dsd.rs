@@ -0,0 +1,196 @@
+use crate::error::*;
+use crate::format::*;
+use crate::pack::*;
+use crate::sample::*;
+use crate::units::*;
+use std::f64::consts::PI;
+
+///Bit rate of DSD64, 64 times 44.1 kHz.
+pub const DSD64: u32 = 2_822_400;
+
+///Byte of DSD silence, an idle pattern of equal ones and zeros.
+pub const DSD_SILENCE: u8 = 0x69;
+
+//markers of DoP frames, alternating.
+const DOP_MARKERS: [u8; 2] = [0x05, 0xFA];
+
+///1-bit DSD samples of channels. Each byte holds 8 samples of a channel, the earliest in the most significant bit, and bytes of channels are interleaved as in DSDIFF.
+#[derive(Clone, PartialEq, Eq)]
+pub struct DsdBlock {
+    sample_rate: u32,
+    channel_size: u16,
+    data: Vec<u8>,
+}
+
+impl std::fmt::Debug for DsdBlock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("DsdBlock");
+        f.field("sample_rate", &self.sample_rate)
+            .field("channel_size", &self.channel_size)
+            .field("data_size", &self.data.len())
+            .finish()
+    }
+}
+
+impl DsdBlock {
+    ///Constructs a new DsdBlock of bit rate and channels from interleaved bytes, failing if they are not whole bytes of every channel.
+    pub fn new(sample_rate: u32, channel_size: u16, data: Vec<u8>) -> Result<Self, Error> {
+        if channel_size == 0 || !data.len().is_multiple_of(channel_size as usize) {
+            return Err(Error::LengthNotMultiple {
+                len: data.len(),
+                multiple: channel_size as usize,
+            });
+        }
+        Ok(Self {
+            sample_rate,
+            channel_size,
+            data,
+        })
+    }
+
+    ///Returns bit rate of a channel.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    ///Returns channel size.
+    pub fn channel_size(&self) -> u16 {
+        self.channel_size
+    }
+
+    ///Returns samples of a channel.
+    pub fn len(&self) -> usize {
+        self.data.len() / self.channel_size as usize * 8
+    }
+
+    ///Returns true if there is no sample.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    ///Returns interleaved bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    ///Returns sample i of channel n, true for +1 and false for -1.
+    pub fn bit(&self, n: usize, i: usize) -> bool {
+        self.data[i / 8 * self.channel_size as usize + n] & (0x80 >> (i % 8)) != 0
+    }
+
+    //returns byte i of channel n, or None out of range.
+    fn byte(&self, n: usize, i: isize) -> Option<u8> {
+        let c = self.channel_size as usize;
+        usize::try_from(i)
+            .ok()
+            .and_then(|i| self.data.get(i * c + n))
+            .copied()
+    }
+
+    ///Packs samples into DSD over PCM (DoP v1.1) frames of 24 bit packed little-endian, at a sixteenth of the bit rate. The last frame is padded with DSD silence.
+    pub fn to_dop(&self) -> ByteBlock {
+        let c = self.channel_size as usize;
+        let frames = (self.data.len() / c).div_ceil(2);
+        let mut v = Block::new(frames * c);
+        for i in 0..frames {
+            let marker = DOP_MARKERS[i % 2] as u32;
+            for n in 0..c {
+                let b = |k: isize| self.byte(n, k).unwrap_or(DSD_SILENCE) as u32;
+                let s = marker << 16 | b(2 * i as isize) << 8 | b(2 * i as isize + 1);
+                v.push((s << 8) as i32);
+            }
+        }
+        ByteBlock::from_packed_bits(self.channel_size, 24, Justification::Left, false, &v)
+            .expect("24 bit frames fill whole bytes")
+    }
+
+    ///Unpacks DoP frames of 24 bit samples at pcm rate into DSD of 16 times the rate, failing if a marker is missing or does not alternate.
+    pub fn from_dop(pcm_rate: u32, o: &ByteBlock) -> Result<Self, Error> {
+        let c = o.channel_size() as usize;
+        if c == 0 || o.byte_size() != 3 * c {
+            return Err(Error::LayoutMismatch {
+                expected: (o.channel_size(), 3 * c),
+                found: (o.channel_size(), o.byte_size()),
+            });
+        }
+        let v = o.unpack_bits(Justification::Left)?;
+        let mut data = Vec::with_capacity(v.len() * 2);
+        let mut last = None;
+        for f in v.chunks_exact(c) {
+            let marker = (f[0] as u32 >> 24) as u8;
+            if !DOP_MARKERS.contains(&marker) || last == Some(marker) {
+                return Err(Error::InvalidData("missing DoP marker"));
+            }
+            last = Some(marker);
+            if f.iter().any(|s| (*s as u32 >> 24) as u8 != marker) {
+                return Err(Error::InvalidData("missing DoP marker"));
+            }
+            //the earlier byte of each channel, then the later.
+            data.extend(f.iter().map(|s| (*s as u32 >> 16) as u8));
+            data.extend(f.iter().map(|s| (*s as u32 >> 8) as u8));
+        }
+        Self::new(pcm_rate.saturating_mul(16), o.channel_size(), data)
+    }
+
+    ///Converts samples to PCM of a decimation-th of the bit rate, a multiple of 8 such as 64 for 44.1 kHz from DSD64, through a windowed sinc lowpass. Samples of ±1 map to full scale. Fails if channels of T differ or decimation is not a multiple of 8.
+    pub fn to_pcm<T: Frame>(&self, decimation: u32) -> Result<Whole<T>, Error> {
+        if T::CHANNEL_SIZE != self.channel_size {
+            return Err(Error::LayoutMismatch {
+                expected: (T::CHANNEL_SIZE, T::BYTE_SIZE),
+                found: (self.channel_size, 4 * self.channel_size as usize),
+            });
+        }
+        if decimation == 0 || !decimation.is_multiple_of(8) {
+            return Err(Error::Unsupported("decimation not a multiple of 8"));
+        }
+        let d = decimation as usize;
+        //taps span 16 output periods, in whole groups of 8 on each side.
+        let taps = 16 * d;
+        let cutoff = 0.45 / d as f64;
+        let h: Vec<f64> = (0..taps)
+            .map(|k| {
+                let t = k as f64 - (taps as f64 - 1.0) / 2.0;
+                let s = if t == 0.0 {
+                    2.0 * cutoff
+                } else {
+                    (2.0 * PI * cutoff * t).sin() / (PI * t)
+                };
+                let a = 2.0 * PI * (k as f64 + 0.5) / taps as f64;
+                s * (0.42 - 0.5 * a.cos() + 0.08 * (2.0 * a).cos())
+            })
+            .collect();
+        let sum: f64 = h.iter().sum();
+        //sum of taps of a group for each byte, the earliest bit first.
+        let table: Vec<[f32; 256]> = h
+            .chunks(8)
+            .map(|g| {
+                std::array::from_fn(|b| {
+                    g.iter()
+                        .enumerate()
+                        .map(|(k, h)| if b & (0x80 >> k) != 0 { *h } else { -h })
+                        .sum::<f64>() as f32
+                        / sum as f32
+                })
+            })
+            .collect();
+        let n = self.len().div_ceil(d);
+        let mut v = Block::from(vec![T::default(); n]);
+        let half = (taps / 2 / 8) as isize;
+        for (j, f) in v.iter_mut().enumerate() {
+            let start = (j * d / 8) as isize - half;
+            for c in 0..self.channel_size as usize {
+                let mut acc = 0.0;
+                for (g, t) in table.iter().enumerate() {
+                    if let Some(b) = self.byte(c, start + g as isize) {
+                        acc += t[b as usize];
+                    }
+                }
+                f.set_channel(c, acc);
+            }
+        }
+        Ok(Whole::from_block(
+            SampleRate::new(self.sample_rate / decimation)?,
+            v,
+        ))
+    }
+}
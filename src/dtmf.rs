@@ -0,0 +1,130 @@
+use crate::db::*;
+use crate::format::*;
+use crate::sample::*;
+use crate::util::*;
+
+const ROWS: [f32; 4] = [697.0, 770.0, 852.0, 941.0];
+const COLS: [f32; 4] = [1209.0, 1336.0, 1477.0, 1633.0];
+const KEYS: [[char; 4]; 4] = [
+    ['1', '2', '3', 'A'],
+    ['4', '5', '6', 'B'],
+    ['7', '8', '9', 'C'],
+    ['*', '0', '#', 'D'],
+];
+
+///Returns row and column frequencies in Hz of DTMF digit.
+pub fn dtmf_freqs(digit: char) -> Option<(f32, f32)> {
+    let digit = digit.to_ascii_uppercase();
+    KEYS.iter().enumerate().find_map(|(r, o)| {
+        o.iter()
+            .position(|c| *c == digit)
+            .map(|c| (ROWS[r], COLS[c]))
+    })
+}
+
+///Returns the squared magnitude of frequency in Hz over samples by the Goertzel algorithm.
+pub fn goertzel(x: &[f32], sample_rate: u32, freq: f32) -> f32 {
+    let w = std::f64::consts::TAU * freq as f64 / sample_rate as f64;
+    let c = 2.0 * w.cos();
+    let (mut s1, mut s2) = (0.0f64, 0.0f64);
+    for i in x {
+        let s = *i as f64 + c * s1 - s2;
+        s2 = s1;
+        s1 = s;
+    }
+    (s1 * s1 + s2 * s2 - c * s1 * s2) as f32
+}
+
+///A DTMF digit detector of Goertzel filters over fixed windows, mixing all channels.
+#[derive(Clone, Debug)]
+pub struct DtmfDetector {
+    sample_rate: u32,
+    window: usize,
+    min_level: f32,
+    twist: Db,
+}
+
+impl DtmfDetector {
+    ///Constructs a new DtmfDetector of 25 ms windows.
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            window: ms_to_frames(25.0, sample_rate).max(1),
+            min_level: Db(-50.0).to_gain(),
+            twist: Db(8.0),
+        }
+    }
+
+    ///Sets minimum RMS level in dBFS of a window to be considered, -50 dB by default.
+    pub fn set_min_level(&mut self, level: Db) {
+        self.min_level = level.to_gain();
+    }
+
+    ///Sets maximum level difference between row and column tones, 8 dB by default.
+    pub fn set_twist(&mut self, twist: Db) {
+        self.twist = Db(twist.0.abs());
+    }
+
+    ///Returns window size in frames.
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    fn digit(&self, x: &[f32]) -> Option<char> {
+        let n = x.len() as f32;
+        let energy = x.iter().map(|o| o * o).sum::<f32>();
+        if energy <= 0.0 || (energy / n).sqrt() < self.min_level {
+            return None;
+        }
+        //proportion of energy of each tone, 0.5 for each of a pure pair.
+        let rel = |f: f32| 2.0 * goertzel(x, self.sample_rate, f) / (n * energy);
+        let best = |o: &[f32; 4]| {
+            let v: Vec<f32> = o.iter().map(|f| rel(*f)).collect();
+            let (i, p) = v
+                .iter()
+                .enumerate()
+                .fold((0, 0.0f32), |a, (i, p)| if *p > a.1 { (i, *p) } else { a });
+            let second = v
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .fold(0.0f32, |a, (_, p)| a.max(*p));
+            (i, p, p > 4.0 * second)
+        };
+        let (r, pr, ur) = best(&ROWS);
+        let (c, pc, uc) = best(&COLS);
+        let twist = self.twist.to_gain();
+        let twist = twist * twist;
+        if ur && uc && pr + pc > 0.6 && pr < pc * twist && pc < pr * twist {
+            Some(KEYS[r][c])
+        } else {
+            None
+        }
+    }
+
+    ///Returns frames and digits of detected key presses, a digit must persist for two windows and is reported once per press.
+    pub fn detect<T: Frame>(&self, o: &Block<T>) -> Vec<(usize, char)> {
+        let c = T::CHANNEL_SIZE as usize;
+        let x: Vec<f32> = o
+            .iter()
+            .map(|i| (0..c).map(|n| i.channel(n)).sum::<f32>() / c as f32)
+            .collect();
+        let mut v = Vec::new();
+        let mut last = None;
+        let mut reported = None;
+        for (i, w) in x.chunks_exact(self.window).enumerate() {
+            let d = self.digit(w);
+            match d {
+                Some(k) if d == last && d != reported => {
+                    v.push(((i - 1) * self.window, k));
+                    reported = d;
+                }
+                //released after two windows without digit.
+                None if last.is_none() => reported = None,
+                _ => {}
+            }
+            last = d;
+        }
+        v
+    }
+}
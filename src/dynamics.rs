@@ -0,0 +1,389 @@
+use crate::analysis::*;
+use crate::db::*;
+use crate::format::*;
+use crate::sample::*;
+use crate::units::*;
+use crate::util::*;
+use std::collections::VecDeque;
+
+///A look-ahead brick-wall limiter.
+pub struct Limiter<T> {
+    ceiling: f32,
+    attack: f32,
+    release: f32,
+    lookahead: usize,
+    detector: Option<TruePeak>,
+    delay: VecDeque<T>,
+    window: VecDeque<(usize, f32)>,
+    index: usize,
+    gain: f32,
+    trace: Vec<Db>,
+}
+
+impl<T> std::fmt::Debug for Limiter<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("Limiter");
+        f.field("ceiling", &self.ceiling)
+            .field("lookahead", &self.lookahead)
+            .field("gain", &self.gain)
+            .finish()
+    }
+}
+
+impl<T> Limiter<T>
+where
+    T: Frame,
+{
+    ///Constructs a new Limiter. Ceiling is in dBFS, attack (look-ahead) and release are in milliseconds.
+    pub fn new(sample_rate: SampleRate, ceiling: Db, attack: f32, release: f32) -> Self {
+        let sample_rate = sample_rate.get();
+        let lookahead = ms_to_frames(attack, sample_rate);
+        Self {
+            ceiling: ceiling.to_gain(),
+            //reaches the target gain within the look-ahead.
+            attack: time_coef(attack * 0.25, sample_rate),
+            release: time_coef(release, sample_rate),
+            lookahead,
+            detector: None,
+            delay: VecDeque::with_capacity(lookahead + 1),
+            window: VecDeque::with_capacity(lookahead + 1),
+            index: 0,
+            gain: 1.0,
+            trace: Vec::new(),
+        }
+    }
+
+    ///Enables or disables true peak detection, which adds the latency of TruePeak. It allocates, so call it before processing.
+    pub fn set_true_peak(&mut self, on: bool) {
+        self.detector = on.then(|| TruePeak::new().with_channels(T::CHANNEL_SIZE as usize));
+        self.reset();
+        //the delay holds latency frames and the one just pushed.
+        self.delay.reserve(self.latency() + 1);
+    }
+
+    ///Returns latency in frames.
+    pub fn latency(&self) -> usize {
+        if self.detector.is_some() {
+            self.lookahead + TruePeak::LATENCY
+        } else {
+            self.lookahead
+        }
+    }
+
+    ///Clears the internal state.
+    pub fn reset(&mut self) {
+        if let Some(d) = self.detector.as_mut() {
+            d.reset();
+        }
+        self.delay.clear();
+        self.window.clear();
+        self.index = 0;
+        self.gain = 1.0;
+        self.trace.clear();
+    }
+
+    ///Returns gain reduction of each frame of the last processed block. It is replaced by each block, so it never outgrows the largest block.
+    pub fn gain_reduction(&self) -> &[Db] {
+        &self.trace
+    }
+
+    fn tick(&mut self, x: T) -> T {
+        let peak = match self.detector.as_mut() {
+            Some(d) => d.tick(&x),
+            None => x.peak(),
+        };
+        let required = if peak > self.ceiling {
+            self.ceiling / peak
+        } else {
+            1.0
+        };
+        while let Some(o) = self.window.back() {
+            if o.1 < required {
+                break;
+            }
+            self.window.pop_back();
+        }
+        self.window.push_back((self.index, required));
+        while let Some(o) = self.window.front() {
+            if o.0 + self.lookahead >= self.index {
+                break;
+            }
+            self.window.pop_front();
+        }
+        self.index += 1;
+        let target = self.window.front().map_or(1.0, |o| o.1);
+        let c = if target < self.gain {
+            self.attack
+        } else {
+            self.release
+        };
+        self.gain = target + (self.gain - target) * c;
+
+        self.delay.push_back(x);
+        let mut y = if self.delay.len() > self.latency() {
+            self.delay.pop_front().unwrap_or_default()
+        } else {
+            T::default()
+        };
+        y.scale(self.gain);
+        for n in 0..T::CHANNEL_SIZE as usize {
+            y.set_channel(n, y.channel(n).clamp(-self.ceiling, self.ceiling));
+        }
+        self.trace.push(-Db::from_gain(self.gain));
+        y
+    }
+
+    ///Processes block in place, the output is delayed by latency.
+    pub fn process(&mut self, o: &mut Block<T>) {
+        self.trace.clear();
+        self.trace.reserve(o.len());
+        for i in o.iter_mut() {
+            *i = self.tick(*i);
+        }
+    }
+
+    ///Resets and processes the whole in place, compensating latency.
+    pub fn process_whole(&mut self, o: &mut Whole<T>) {
+        self.reset();
+        let n = self.latency();
+        o.extend(std::iter::repeat_n(T::default(), n));
+        self.process(o);
+        o.drain(..n);
+        self.trace.drain(..n);
+    }
+}
+
+///A feed-forward compressor with linked detection of all channels.
+#[derive(Debug)]
+pub struct Compressor {
+    threshold: f32,
+    ratio: f32,
+    knee: f32,
+    attack: f32,
+    release: f32,
+    makeup: f32,
+    env: f32,
+}
+
+impl Compressor {
+    ///Constructs a new Compressor. Threshold is in dBFS, attack and release are in milliseconds.
+    pub fn new(
+        sample_rate: SampleRate,
+        threshold: Db,
+        ratio: f32,
+        knee: Db,
+        attack: f32,
+        release: f32,
+        makeup: Db,
+    ) -> Self {
+        let sample_rate = sample_rate.get();
+        Self {
+            threshold: threshold.0,
+            ratio: ratio.max(1.0),
+            knee: knee.0.max(0.0),
+            attack: time_coef(attack, sample_rate),
+            release: time_coef(release, sample_rate),
+            makeup: makeup.0,
+            env: 0.0,
+        }
+    }
+
+    ///Clears the internal state.
+    pub fn reset(&mut self) {
+        self.env = 0.0;
+    }
+
+    ///Returns current gain reduction.
+    pub fn gain_reduction(&self) -> Db {
+        Db(self.env)
+    }
+
+    fn curve(&self, x: f32) -> f32 {
+        let d = x - self.threshold;
+        let slope = 1.0 / self.ratio - 1.0;
+        if 2.0 * d < -self.knee {
+            0.0
+        } else if self.knee > 0.0 && 2.0 * d.abs() <= self.knee {
+            let o = d + self.knee / 2.0;
+            -slope * o * o / (2.0 * self.knee)
+        } else {
+            -slope * d
+        }
+    }
+
+    fn gain(&mut self, peak: f32) -> f32 {
+        let gr = self.curve(Db::from_gain(peak).0);
+        let c = if gr > self.env {
+            self.attack
+        } else {
+            self.release
+        };
+        self.env = gr + (self.env - gr) * c;
+        Db(self.makeup - self.env).to_gain()
+    }
+
+    ///Processes block in place.
+    pub fn process<T: Frame>(&mut self, o: &mut Block<T>) {
+        for i in o.iter_mut() {
+            let g = self.gain(i.peak());
+            i.scale(g);
+        }
+    }
+
+    ///Processes block in place, detecting level from sidechain. Missing sidechain frames are silence.
+    pub fn process_sidechain<T: Frame, U: Frame>(
+        &mut self,
+        o: &mut Block<T>,
+        sidechain: &Block<U>,
+    ) {
+        for (n, i) in o.iter_mut().enumerate() {
+            let g = self.gain(sidechain.get(n).map_or(0.0, |s| s.peak()));
+            i.scale(g);
+        }
+    }
+}
+
+///A noise gate, or an expander if range is finite.
+#[derive(Debug)]
+pub struct Gate {
+    open: f32,
+    close: f32,
+    floor: f32,
+    attack: f32,
+    hold: usize,
+    release: f32,
+    is_open: bool,
+    count: usize,
+    gain: f32,
+}
+
+impl Gate {
+    ///Constructs a new Gate. Threshold is in dBFS, range is the attenuation when closed, attack, hold and release are in milliseconds.
+    pub fn new(
+        sample_rate: SampleRate,
+        threshold: Db,
+        hysteresis: Db,
+        attack: f32,
+        hold: f32,
+        release: f32,
+        range: Db,
+    ) -> Self {
+        let sample_rate = sample_rate.get();
+        let floor = Db(-range.0.abs()).to_gain();
+        Self {
+            open: threshold.to_gain(),
+            close: Db(threshold.0 - hysteresis.0.abs()).to_gain(),
+            floor,
+            attack: time_coef(attack, sample_rate),
+            hold: ms_to_frames(hold, sample_rate),
+            release: time_coef(release, sample_rate),
+            is_open: false,
+            count: 0,
+            gain: floor,
+        }
+    }
+
+    ///Clears the internal state.
+    pub fn reset(&mut self) {
+        self.is_open = false;
+        self.count = 0;
+        self.gain = self.floor;
+    }
+
+    ///Returns true if the gate is open.
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    fn tick(&mut self, peak: f32) -> f32 {
+        if peak >= self.open {
+            self.is_open = true;
+            self.count = self.hold;
+        } else if self.is_open && peak < self.close {
+            if self.count > 0 {
+                self.count -= 1;
+            } else {
+                self.is_open = false;
+            }
+        }
+        if self.is_open {
+            self.gain = 1.0 + (self.gain - 1.0) * self.attack;
+        } else {
+            self.gain = self.floor + (self.gain - self.floor) * self.release;
+        }
+        self.gain
+    }
+
+    ///Processes block in place.
+    pub fn process<T: Frame>(&mut self, o: &mut Block<T>) {
+        for i in o.iter_mut() {
+            let g = self.tick(i.peak());
+            i.scale(g);
+        }
+    }
+
+    ///Resets and processes the whole in place.
+    pub fn process_whole<T: Frame>(&mut self, o: &mut Whole<T>) {
+        self.reset();
+        self.process(o);
+    }
+}
+
+///Shape of soft clipping.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClipShape {
+    Tanh,
+    Cubic,
+}
+
+impl ClipShape {
+    fn saturate(self, u: f32) -> f32 {
+        match self {
+            Self::Tanh => u.tanh(),
+            Self::Cubic => {
+                if u >= 1.5 {
+                    1.0
+                } else {
+                    u - u * u * u * 4.0 / 27.0
+                }
+            }
+        }
+    }
+}
+
+block_impl! {
+    impl<T> Block<T>
+    where
+        T: Frame,
+    {
+        fn clip_with(&mut self, threshold: f32, f: impl Fn(f32) -> f32) -> usize {
+            let mut n = 0;
+            for i in self.iter_mut() {
+                for c in 0..T::CHANNEL_SIZE as usize {
+                    let v = i.channel(c);
+                    if v.abs() > threshold {
+                        i.set_channel(c, f(v.abs()).copysign(v));
+                        n += 1;
+                    }
+                }
+            }
+            n
+        }
+
+        ///Clips samples in place to ±threshold. Returns the number of samples affected.
+        pub fn clip_hard(&mut self, threshold: f32) -> usize {
+            let t = threshold.abs();
+            self.clip_with(t, |_| t)
+        }
+
+        ///Saturates samples above ±threshold smoothly towards full scale (1.0). Returns the number of samples affected.
+        pub fn clip_soft(&mut self, threshold: f32, shape: ClipShape) -> usize {
+            let t = threshold.abs();
+            if t >= 1.0 {
+                return self.clip_hard(t);
+            }
+            let r = 1.0 - t;
+            self.clip_with(t, |v| t + r * shape.saturate((v - t) / r))
+        }
+    }
+}
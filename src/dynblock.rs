@@ -0,0 +1,264 @@
+use crate::convert::*;
+use crate::error::*;
+use crate::format::*;
+use crate::sample::*;
+use crate::transcode::*;
+
+///Interleaved samples of a DynBlock, one variant for each SampleFormat.
+#[derive(Debug)]
+pub enum DynData {
+    ///Unsigned 8 bit samples, silence at 128.
+    U8(Block<u8>),
+    ///Signed 16 bit samples.
+    I16(Block<i16>),
+    ///Signed 24 bit samples in the upper three bytes of i32, the low byte 0.
+    I24(Block<i32>),
+    ///Signed 32 bit samples.
+    I32(Block<i32>),
+    ///32 bit float samples of full scale 1.0.
+    F32(Block<f32>),
+    ///64 bit float samples of full scale 1.0.
+    F64(Block<f64>),
+}
+
+impl Clone for DynData {
+    fn clone(&self) -> Self {
+        match self {
+            Self::U8(o) => Self::U8(Block::from(o.to_vec())),
+            Self::I16(o) => Self::I16(Block::from(o.to_vec())),
+            Self::I24(o) => Self::I24(Block::from(o.to_vec())),
+            Self::I32(o) => Self::I32(Block::from(o.to_vec())),
+            Self::F32(o) => Self::F32(Block::from(o.to_vec())),
+            Self::F64(o) => Self::F64(Block::from(o.to_vec())),
+        }
+    }
+}
+
+impl DynData {
+    ///Returns sample format.
+    pub fn format(&self) -> SampleFormat {
+        match self {
+            Self::U8(_) => SampleFormat::U8,
+            Self::I16(_) => SampleFormat::I16,
+            Self::I24(_) => SampleFormat::I24,
+            Self::I32(_) => SampleFormat::I32,
+            Self::F32(_) => SampleFormat::F32,
+            Self::F64(_) => SampleFormat::F64,
+        }
+    }
+
+    ///Returns samples of all channels.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::U8(o) => o.len(),
+            Self::I16(o) => o.len(),
+            Self::I24(o) | Self::I32(o) => o.len(),
+            Self::F32(o) => o.len(),
+            Self::F64(o) => o.len(),
+        }
+    }
+
+    ///Returns true if there is no sample.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+///A block of interleaved samples whose format and channels are known only at runtime, such as from a file header. It always holds whole frames.
+#[derive(Clone)]
+pub struct DynBlock {
+    channel_size: u16,
+    data: DynData,
+}
+
+impl std::fmt::Debug for DynBlock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("DynBlock");
+        f.field("format", &self.format())
+            .field("channel_size", &self.channel_size)
+            .field("data_size", &self.data.len())
+            .finish()
+    }
+}
+
+//decodes samples of width bytes.
+fn decode<S: Sample>(data: &[u8], width: usize, f: impl Fn(&[u8]) -> S) -> Block<S> {
+    Block::from(data.chunks_exact(width).map(f).collect::<Vec<_>>())
+}
+
+fn encode<S: Sample>(o: &Block<S>, big_endian: bool) -> Vec<u8> {
+    let mut v = Vec::new();
+    o.extend_into(big_endian, &mut v);
+    v
+}
+
+//returns samples of integer of bits normalized in convention.
+fn to_f32<S: Scalar>(o: &[S], bits: u32, convention: ScalingConvention) -> Block<f32> {
+    Block::from(
+        o.iter()
+            .map(|s| convention.from_asymmetric(s.to_unit(), bits) as f32)
+            .collect::<Vec<_>>(),
+    )
+}
+
+fn from_f32<S: Scalar>(o: &[f32]) -> Block<S> {
+    Block::from(
+        o.iter()
+            .map(|s| S::from_unit(*s as f64))
+            .collect::<Vec<_>>(),
+    )
+}
+
+impl DynBlock {
+    ///Constructs a new DynBlock of channel size, failing if samples are not whole frames or I24 samples have a low byte.
+    pub fn new(channel_size: u16, data: DynData) -> Result<Self, Error> {
+        if channel_size == 0 || !data.len().is_multiple_of(channel_size as usize) {
+            return Err(Error::LengthNotMultiple {
+                len: data.len(),
+                multiple: channel_size as usize,
+            });
+        }
+        if let DynData::I24(o) = &data {
+            if o.iter().any(|s| s & 0xFF != 0) {
+                return Err(Error::InvalidData("I24 sample with a low byte"));
+            }
+        }
+        Ok(Self { channel_size, data })
+    }
+
+    ///Returns sample format.
+    pub fn format(&self) -> SampleFormat {
+        self.data.format()
+    }
+
+    ///Returns channel size.
+    pub fn channel_size(&self) -> u16 {
+        self.channel_size
+    }
+
+    ///Returns bit depth, 24 for I24.
+    pub fn bit_depth(&self) -> usize {
+        8 * self.format().bytes()
+    }
+
+    ///Returns interleaved samples.
+    pub fn data(&self) -> &DynData {
+        &self.data
+    }
+
+    ///Returns the inner interleaved samples.
+    pub fn into_data(self) -> DynData {
+        self.data
+    }
+
+    ///Returns number of frames.
+    pub fn len(&self) -> usize {
+        self.data.len() / self.channel_size as usize
+    }
+
+    ///Returns true if there is no frame.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    ///Returns interleaved samples normalized to full scale 1.0 in the Asymmetric convention.
+    pub fn to_f32(&self) -> Block<f32> {
+        self.to_f32_with(ScalingConvention::Asymmetric)
+    }
+
+    ///Returns interleaved samples normalized to full scale 1.0, scaling integers in convention.
+    pub fn to_f32_with(&self, convention: ScalingConvention) -> Block<f32> {
+        let bits = self.format().bits().unwrap_or(0);
+        match &self.data {
+            DynData::U8(o) => to_f32(o, bits, convention),
+            DynData::I16(o) => to_f32(o, bits, convention),
+            DynData::I24(o) | DynData::I32(o) => to_f32(o, bits, convention),
+            DynData::F32(o) => Block::from(o.to_vec()),
+            DynData::F64(o) => Block::from(o.iter().map(|s| *s as f32).collect::<Vec<_>>()),
+        }
+    }
+
+    ///Constructs a DynBlock of format from interleaved samples normalized to full scale 1.0, rounding and saturating integers. Fails if samples are not a multiple of channel size.
+    pub fn from_f32(format: SampleFormat, channel_size: u16, o: &[f32]) -> Result<Self, Error> {
+        let data = match format {
+            SampleFormat::U8 => DynData::U8(from_f32(o)),
+            SampleFormat::I16 => DynData::I16(from_f32(o)),
+            //rounds the 32 bit value to the upper three bytes, saturating at the maximum.
+            SampleFormat::I24 => DynData::I24(Block::from(
+                o.iter()
+                    .map(|s| i32::from_unit(*s as f64).saturating_add(0x80) & !0xFF)
+                    .collect::<Vec<_>>(),
+            )),
+            SampleFormat::I32 => DynData::I32(from_f32(o)),
+            SampleFormat::F32 => DynData::F32(Block::from(o.to_vec())),
+            SampleFormat::F64 => DynData::F64(from_f32(o)),
+        };
+        Self::new(channel_size, data)
+    }
+
+    ///Decodes ByteBlock of samples in format, in its byte order. Fails if its byte size is not that of format and channels, or data is not whole frames.
+    pub fn from_byte_block(format: SampleFormat, o: &ByteBlock) -> Result<Self, Error> {
+        let channel_size = o.channel_size();
+        let width = format.bytes();
+        let byte_size = width * channel_size as usize;
+        if channel_size == 0 || o.byte_size() != byte_size {
+            return Err(Error::LayoutMismatch {
+                expected: (channel_size, byte_size),
+                found: (channel_size, o.byte_size()),
+            });
+        }
+        if !o.len().is_multiple_of(byte_size) {
+            return Err(Error::LengthNotMultiple {
+                len: o.len(),
+                multiple: byte_size,
+            });
+        }
+        let be = o.is_big_endian();
+        //copies a sample into an array, reversed if big-endian.
+        fn le<const W: usize>(b: &[u8], be: bool) -> [u8; W] {
+            let mut a = [0; W];
+            a.copy_from_slice(b);
+            if be {
+                a.reverse();
+            }
+            a
+        }
+        let data = match format {
+            SampleFormat::U8 => DynData::U8(decode(o, width, |b| b[0])),
+            SampleFormat::I16 => DynData::I16(decode(o, width, |b| i16::from_le_bytes(le(b, be)))),
+            SampleFormat::I24 => DynData::I24(decode(o, width, |b| {
+                let [x, y, z] = le(b, be);
+                i32::from_le_bytes([0, x, y, z])
+            })),
+            SampleFormat::I32 => DynData::I32(decode(o, width, |b| i32::from_le_bytes(le(b, be)))),
+            SampleFormat::F32 => DynData::F32(decode(o, width, |b| f32::from_le_bytes(le(b, be)))),
+            SampleFormat::F64 => DynData::F64(decode(o, width, |b| f64::from_le_bytes(le(b, be)))),
+        };
+        Ok(Self { channel_size, data })
+    }
+
+    ///Encodes samples to ByteBlock in big-endian or little-endian byte order, I24 packed in 3 bytes.
+    pub fn to_byte_block(&self, big_endian: bool) -> ByteBlock {
+        let c = self.channel_size;
+        let byte_size = self.format().bytes() * c as usize;
+        let data = match &self.data {
+            DynData::U8(o) => o.to_vec(),
+            DynData::I16(o) => encode(o, big_endian),
+            DynData::I24(o) => {
+                let mut v = Vec::with_capacity(o.len() * 3);
+                for s in o.iter() {
+                    if big_endian {
+                        v.extend_from_slice(&s.to_be_bytes()[..3]);
+                    } else {
+                        v.extend_from_slice(&s.to_le_bytes()[1..]);
+                    }
+                }
+                v
+            }
+            DynData::I32(o) => encode(o, big_endian),
+            DynData::F32(o) => encode(o, big_endian),
+            DynData::F64(o) => encode(o, big_endian),
+        };
+        ByteBlock::new(c, byte_size, big_endian, data)
+    }
+}
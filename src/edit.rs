@@ -0,0 +1,429 @@
+use crate::format::*;
+use crate::sample::*;
+use crate::shared::*;
+use crate::units::*;
+use std::collections::VecDeque;
+use std::ops::Range;
+
+///A named position in frames of a Whole, kept in place by its edits.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Marker {
+    pub frame: usize,
+    pub name: String,
+}
+
+impl Marker {
+    ///Constructs a new Marker.
+    pub fn new(frame: usize, name: impl Into<String>) -> Self {
+        Self {
+            frame,
+            name: name.into(),
+        }
+    }
+}
+
+impl<T> Whole<T>
+where
+    T: Sample,
+{
+    ///Inserts frames of block at frame, clamped. Markers at or after it shift forward.
+    pub fn insert(&mut self, at: usize, o: Block<T>) {
+        self.replace(at..at, o);
+    }
+
+    ///Removes frames in range, clamped, returning them. Markers inside move to its start, later markers shift back.
+    pub fn delete(&mut self, range: Range<usize>) -> Block<T> {
+        self.replace(range, Block::new(0))
+    }
+
+    ///Replaces frames in range, clamped, by frames of block, returning the removed frames. Markers inside keep their offset, clamped to the new frames, and later markers shift by the change of length.
+    pub fn replace(&mut self, range: Range<usize>, mut o: Block<T>) -> Block<T> {
+        let end = range.end.min(self.len());
+        let start = range.start.min(end);
+        let n = o.len();
+        let removed: Vec<T> = self.splice(start..end, o.drain(..)).collect();
+        for m in self.markers_mut() {
+            if m.frame >= end {
+                m.frame = m.frame - (end - start) + n;
+            } else if m.frame >= start {
+                m.frame = start + (m.frame - start).min(n);
+            }
+        }
+        Block::from(removed)
+    }
+}
+
+///A region of frames of a source, with gain and linear fades in frames.
+pub struct Edit<T> {
+    pub source: SharedWhole<T>,
+    ///Frames of the source, clamped.
+    pub range: Range<usize>,
+    pub gain: f32,
+    pub fade_in: usize,
+    pub fade_out: usize,
+}
+
+impl<T> Clone for Edit<T> {
+    fn clone(&self) -> Self {
+        Self {
+            source: self.source.clone(),
+            range: self.range.clone(),
+            gain: self.gain,
+            fade_in: self.fade_in,
+            fade_out: self.fade_out,
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for Edit<T>
+where
+    T: Sample,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("Edit");
+        f.field("source", &self.source)
+            .field("range", &self.range)
+            .field("gain", &self.gain)
+            .field("fade_in", &self.fade_in)
+            .field("fade_out", &self.fade_out)
+            .finish()
+    }
+}
+
+impl<T> Edit<T>
+where
+    T: Sample,
+{
+    ///Constructs a new Edit of frames of source in range, at unity gain without fades.
+    pub fn new(source: SharedWhole<T>, range: Range<usize>) -> Self {
+        Self {
+            source,
+            range,
+            gain: 1.0,
+            fade_in: 0,
+            fade_out: 0,
+        }
+    }
+
+    ///Returns number of frames.
+    pub fn len(&self) -> usize {
+        let end = self.range.end.min(self.source.len());
+        end - self.range.start.min(end)
+    }
+
+    ///Returns true if there is no frame.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    ///Returns gain of frame i of n with fades.
+    pub(crate) fn gain_at(&self, i: usize, n: usize) -> f32 {
+        let mut g = self.gain;
+        if i < self.fade_in {
+            g *= i as f32 / self.fade_in as f32;
+        }
+        if n - 1 - i < self.fade_out {
+            g *= (n - 1 - i) as f32 / self.fade_out as f32;
+        }
+        g
+    }
+}
+
+///A non-destructive sequence of edits rendered to a Whole on demand. Sources are shared, not copied, and taken frame by frame regardless of their sample rate.
+pub struct EditList<T> {
+    sample_rate: SampleRate,
+    edits: Vec<Edit<T>>,
+}
+
+impl<T> Clone for EditList<T> {
+    fn clone(&self) -> Self {
+        Self {
+            sample_rate: self.sample_rate,
+            edits: self.edits.clone(),
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for EditList<T>
+where
+    T: Sample,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("EditList");
+        f.field("sample_rate", &self.sample_rate)
+            .field("edits", &self.edits)
+            .finish()
+    }
+}
+
+impl<T> EditList<T>
+where
+    T: Sample,
+{
+    ///Constructs a new, empty EditList rendering at sample rate.
+    pub fn new(sample_rate: SampleRate) -> Self {
+        Self {
+            sample_rate,
+            edits: Vec::new(),
+        }
+    }
+
+    ///Returns sample rate.
+    pub fn sample_rate(&self) -> SampleRate {
+        self.sample_rate
+    }
+
+    ///Appends an edit.
+    pub fn push(&mut self, o: Edit<T>) {
+        self.edits.push(o);
+    }
+
+    ///Returns edits.
+    pub fn edits(&self) -> &[Edit<T>] {
+        &self.edits
+    }
+
+    ///Returns mutable edits.
+    pub fn edits_mut(&mut self) -> &mut Vec<Edit<T>> {
+        &mut self.edits
+    }
+
+    ///Returns number of rendered frames.
+    pub fn len(&self) -> usize {
+        self.edits.iter().map(|o| o.len()).sum()
+    }
+
+    ///Returns true if there is no rendered frame.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> EditList<T>
+where
+    T: Frame,
+{
+    ///Renders edits in sequence into a new Whole.
+    pub fn render(&self) -> Whole<T> {
+        let mut v = Block::new(self.len());
+        for o in &self.edits {
+            let n = o.len();
+            let start = o.range.start.min(o.source.len());
+            for (i, f) in o.source[start..start + n].iter().enumerate() {
+                let mut f = *f;
+                f.scale(o.gain_at(i, n));
+                v.push(f);
+            }
+        }
+        Whole::from_block(self.sample_rate, v)
+    }
+}
+
+//a reversible step, swapping frames back into a region.
+struct Step<T> {
+    start: usize,
+    ///Frames of the region now in the whole.
+    len: usize,
+    frames: Block<T>,
+    ///Markers to restore, None to keep those adjusted by the swap.
+    markers: Option<Vec<Marker>>,
+}
+
+impl<T> Step<T>
+where
+    T: Sample,
+{
+    fn bytes(&self) -> usize {
+        self.frames.len() * std::mem::size_of::<T>()
+    }
+
+    //swaps frames and markers with the whole, returning the inverse step.
+    fn swap(self, o: &mut Whole<T>) -> Self {
+        let len = self.frames.len();
+        let markers = o.markers().to_vec();
+        let frames = o.replace(self.start..self.start + self.len, self.frames);
+        if let Some(m) = self.markers {
+            *o.markers_mut() = m;
+        }
+        Self {
+            start: self.start,
+            len,
+            frames,
+            markers: Some(markers),
+        }
+    }
+}
+
+///A Whole with a journal of its edits for undo and redo. Each step keeps only the frames of the affected region, and the oldest steps are dropped beyond a budget in bytes.
+pub struct Journal<T> {
+    whole: Whole<T>,
+    undo: VecDeque<Step<T>>,
+    redo: Vec<Step<T>>,
+    budget: usize,
+    used: usize,
+}
+
+impl<T> std::fmt::Debug for Journal<T>
+where
+    T: Sample,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("Journal");
+        f.field("whole", &self.whole)
+            .field("undo", &self.undo.len())
+            .field("redo", &self.redo.len())
+            .field("budget", &self.budget)
+            .field("used", &self.used)
+            .finish()
+    }
+}
+
+impl<T> Journal<T>
+where
+    T: Sample,
+{
+    ///Constructs a new Journal of whole, keeping history up to budget bytes.
+    pub fn new(whole: Whole<T>, budget: usize) -> Self {
+        Self {
+            whole,
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            budget,
+            used: 0,
+        }
+    }
+
+    ///Returns the whole.
+    pub fn whole(&self) -> &Whole<T> {
+        &self.whole
+    }
+
+    ///Returns the whole, dropping history.
+    pub fn into_whole(self) -> Whole<T> {
+        self.whole
+    }
+
+    ///Returns bytes kept by history.
+    pub fn used(&self) -> usize {
+        self.used
+    }
+
+    ///Returns true if there is a step to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    ///Returns true if there is a step to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    ///Drops all history.
+    pub fn clear_history(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.used = 0;
+    }
+
+    //records a step, dropping redo and the oldest steps beyond budget.
+    fn record(&mut self, o: Step<T>) {
+        for i in self.redo.drain(..) {
+            self.used -= i.bytes();
+        }
+        self.used += o.bytes();
+        self.undo.push_back(o);
+        self.trim();
+    }
+
+    //drops the oldest steps beyond budget.
+    fn trim(&mut self) {
+        while self.used > self.budget {
+            match self.undo.pop_front() {
+                Some(i) => self.used -= i.bytes(),
+                None => break,
+            }
+        }
+    }
+
+    ///Inserts frames of block at frame, clamped, like `Whole::insert`.
+    pub fn insert(&mut self, at: usize, o: Block<T>) {
+        self.replace(at..at, o);
+    }
+
+    ///Removes frames in range, clamped, like `Whole::delete`.
+    pub fn delete(&mut self, range: Range<usize>) {
+        self.replace(range, Block::new(0));
+    }
+
+    ///Replaces frames in range, clamped, by frames of block, like `Whole::replace`.
+    pub fn replace(&mut self, range: Range<usize>, o: Block<T>) {
+        let end = range.end.min(self.whole.len());
+        let start = range.start.min(end);
+        let step = Step {
+            start,
+            len: end - start,
+            frames: o,
+            markers: None,
+        };
+        let o = step.swap(&mut self.whole);
+        self.record(o);
+    }
+
+    ///Applies f to frames in range, clamped, keeping a copy of them.
+    pub fn modify(&mut self, range: Range<usize>, f: impl FnOnce(&mut [T]))
+    where
+        T: Clone,
+    {
+        let end = range.end.min(self.whole.len());
+        let start = range.start.min(end);
+        let frames = Block::from(self.whole[start..end].to_vec());
+        f(&mut self.whole[start..end]);
+        self.record(Step {
+            start,
+            len: end - start,
+            frames,
+            markers: None,
+        });
+    }
+
+    ///Replaces markers.
+    pub fn set_markers(&mut self, markers: Vec<Marker>) {
+        let step = Step {
+            start: 0,
+            len: 0,
+            frames: Block::new(0),
+            markers: Some(markers),
+        };
+        let o = step.swap(&mut self.whole);
+        self.record(o);
+    }
+
+    ///Reverts the last step. Returns false if there is none.
+    pub fn undo(&mut self) -> bool {
+        match self.undo.pop_back() {
+            Some(o) => {
+                self.used -= o.bytes();
+                let o = o.swap(&mut self.whole);
+                self.used += o.bytes();
+                self.redo.push(o);
+                true
+            }
+            None => false,
+        }
+    }
+
+    ///Reapplies the last undone step. Returns false if there is none.
+    pub fn redo(&mut self) -> bool {
+        match self.redo.pop() {
+            Some(o) => {
+                self.used -= o.bytes();
+                let o = o.swap(&mut self.whole);
+                self.used += o.bytes();
+                self.undo.push_back(o);
+                self.trim();
+                true
+            }
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use crate::biquad::*;
+use crate::delay::*;
+use crate::dynamics::*;
+use crate::eq::*;
+use crate::fir::*;
+use crate::format::*;
+use crate::lofi::*;
+use crate::loudness::*;
+use crate::modulation::*;
+use crate::reverb::*;
+use crate::sample::*;
+
+///A processor of blocks in place, such as a filter, dynamics or delay.
+///
+///Built-in effects allocate only while the channel count or block size grows, such as the gain reduction of Limiter sized to its largest block, so after a first block of the largest size, process and process_into are safe for real-time threads. Constructors and setters may allocate.
+pub trait Effect<T: Frame> {
+    ///Processes block in place.
+    fn process(&mut self, o: &mut Block<T>);
+
+    ///Processes input into output without allocating. Output must be as long as input, or it panics.
+    fn process_into(&mut self, input: &Block<T>, output: &mut Block<T>) {
+        copy_into(input, output);
+        self.process(output);
+    }
+
+    ///Clears the internal state.
+    fn reset(&mut self);
+
+    ///Returns latency in frames.
+    fn latency(&self) -> usize {
+        0
+    }
+}
+
+//copies input into output of the same length, the first step of process_into.
+fn copy_into<T: Copy>(input: &[T], output: &mut [T]) {
+    assert_eq!(
+        input.len(),
+        output.len(),
+        "process_into needs output as long as input"
+    );
+    output.copy_from_slice(input);
+}
+
+macro_rules! effect {
+    ($($t:ty),+) => {
+        $(
+            impl $t {
+                ///Processes input into output without allocating. Output must be as long as input, or it panics.
+                pub fn process_into<T: Frame>(&mut self, input: &Block<T>, output: &mut Block<T>) {
+                    copy_into(input, output);
+                    self.process(output);
+                }
+            }
+
+            impl<T: Frame> Effect<T> for $t {
+                fn process(&mut self, o: &mut Block<T>) {
+                    <$t>::process(self, o)
+                }
+
+                fn reset(&mut self) {
+                    <$t>::reset(self)
+                }
+            }
+        )+
+    };
+}
+
+effect!(
+    Biquad,
+    Fir,
+    ParametricEq,
+    Delay,
+    Compressor,
+    Gate,
+    ModulatedDelay,
+    Tremolo,
+    BitCrusher,
+    KWeighting
+);
+
+impl Vibrato {
+    ///Processes input into output without allocating. Output must be as long as input, or it panics.
+    pub fn process_into<T: Frame>(&mut self, input: &Block<T>, output: &mut Block<T>) {
+        copy_into(input, output);
+        self.process(output);
+    }
+}
+
+impl<T: Frame> Effect<T> for Vibrato {
+    fn process(&mut self, o: &mut Block<T>) {
+        Vibrato::process(self, o)
+    }
+
+    fn reset(&mut self) {
+        Vibrato::reset(self)
+    }
+
+    fn latency(&self) -> usize {
+        Vibrato::latency(self)
+    }
+}
+
+impl<T: Frame> Limiter<T> {
+    ///Processes input into output without allocating once the gain reduction has grown to the block size. Output must be as long as input, or it panics.
+    pub fn process_into(&mut self, input: &Block<T>, output: &mut Block<T>) {
+        copy_into(input, output);
+        self.process(output);
+    }
+}
+
+impl<T: Frame> Effect<T> for Limiter<T> {
+    fn process(&mut self, o: &mut Block<T>) {
+        Limiter::process(self, o)
+    }
+
+    fn reset(&mut self) {
+        Limiter::reset(self)
+    }
+
+    fn latency(&self) -> usize {
+        Limiter::latency(self)
+    }
+}
+
+impl Reverb {
+    ///Processes input into output without allocating. Output must be as long as input, or it panics.
+    pub fn process_into(&mut self, input: &Block<[f32; 2]>, output: &mut Block<[f32; 2]>) {
+        copy_into(input, output);
+        self.process(output);
+    }
+}
+
+impl Effect<[f32; 2]> for Reverb {
+    fn process(&mut self, o: &mut Block<[f32; 2]>) {
+        Reverb::process(self, o)
+    }
+
+    fn reset(&mut self) {
+        Reverb::reset(self)
+    }
+}
+
+impl<T: Frame> Effect<T> for Box<dyn Effect<T>> {
+    fn process(&mut self, o: &mut Block<T>) {
+        self.as_mut().process(o)
+    }
+
+    fn process_into(&mut self, input: &Block<T>, output: &mut Block<T>) {
+        self.as_mut().process_into(input, output)
+    }
+
+    fn reset(&mut self) {
+        self.as_mut().reset()
+    }
+
+    fn latency(&self) -> usize {
+        self.as_ref().latency()
+    }
+}
@@ -0,0 +1,86 @@
+use crate::format::*;
+use crate::sample::*;
+use crate::util::*;
+
+///Curve of envelope segment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Curve {
+    Linear,
+    Exponential,
+}
+
+impl Curve {
+    fn shape(self, u: f32) -> f32 {
+        match self {
+            Self::Linear => u,
+            Self::Exponential => (1.0 - (-5.0 * u).exp()) / (1.0 - (-5.0f32).exp()),
+        }
+    }
+}
+
+///An attack, decay, sustain, release envelope.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Envelope {
+    attack: f32,
+    decay: f32,
+    sustain: f32,
+    release: f32,
+    curve: Curve,
+}
+
+impl Envelope {
+    ///Constructs a new Envelope. Attack, decay and release are in milliseconds, sustain is linear level.
+    pub fn new(attack: f32, decay: f32, sustain: f32, release: f32, curve: Curve) -> Self {
+        Self {
+            attack,
+            decay,
+            sustain: sustain.clamp(0.0, 1.0),
+            release,
+            curve,
+        }
+    }
+
+    fn segment(&self, from: f32, to: f32, i: usize, n: usize) -> f32 {
+        from + (to - from) * self.curve.shape(i as f32 / n as f32)
+    }
+
+    fn hold_level(&self, i: usize, a: usize, d: usize) -> f32 {
+        if i < a {
+            self.segment(0.0, 1.0, i, a)
+        } else if i - a < d {
+            self.segment(1.0, self.sustain, i - a, d)
+        } else {
+            self.sustain
+        }
+    }
+
+    ///Renders gain curve of n frames. Release ends at the last frame.
+    pub fn render(&self, sample_rate: u32, n: usize) -> Vec<f32> {
+        let a = ms_to_frames(self.attack, sample_rate);
+        let d = ms_to_frames(self.decay, sample_rate);
+        let r = ms_to_frames(self.release, sample_rate).min(n);
+        let start = n - r;
+        let level = self.hold_level(start, a, d);
+        let mut v = Vec::with_capacity(n);
+        for i in 0..start {
+            v.push(self.hold_level(i, a, d));
+        }
+        for i in 0..r {
+            v.push(self.segment(level, 0.0, i + 1, r));
+        }
+        v
+    }
+
+    ///Applies envelope to block in place.
+    pub fn apply<T: Frame>(&self, sample_rate: u32, o: &mut Block<T>) {
+        let v = self.render(sample_rate, o.len());
+        for (i, g) in o.iter_mut().zip(v) {
+            i.scale(g);
+        }
+    }
+
+    ///Applies envelope to whole in place.
+    pub fn apply_whole<T: Frame>(&self, o: &mut Whole<T>) {
+        self.apply(o.sample_rate().get(), o)
+    }
+}
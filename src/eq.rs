@@ -0,0 +1,115 @@
+use crate::biquad::*;
+use crate::db::*;
+use crate::format::*;
+use crate::sample::*;
+
+///A band of parametric equalizer. Frequency is in Hz.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Band {
+    pub filter_type: FilterType,
+    pub freq: f32,
+    pub gain: Db,
+    pub q: f32,
+}
+
+impl Band {
+    ///Constructs a new Band.
+    pub fn new(filter_type: FilterType, freq: f32, gain: Db, q: f32) -> Self {
+        Self {
+            filter_type,
+            freq,
+            gain,
+            q,
+        }
+    }
+
+    fn coefficients(&self, sample_rate: u32) -> Coefficients {
+        Coefficients::new(self.filter_type, sample_rate, self.freq, self.q, self.gain)
+    }
+}
+
+///A parametric equalizer of cascaded biquad bands.
+#[derive(Clone, Debug)]
+pub struct ParametricEq {
+    sample_rate: u32,
+    bands: Vec<Band>,
+    filters: Vec<Biquad>,
+}
+
+impl ParametricEq {
+    ///Constructs a new ParametricEq.
+    pub fn new(sample_rate: u32, bands: Vec<Band>) -> Self {
+        let filters = bands
+            .iter()
+            .map(|o| Biquad::new(o.coefficients(sample_rate)))
+            .collect();
+        Self {
+            sample_rate,
+            bands,
+            filters,
+        }
+    }
+
+    ///Returns bands.
+    pub fn bands(&self) -> &[Band] {
+        &self.bands
+    }
+
+    ///Appends a band.
+    pub fn add_band(&mut self, band: Band) {
+        self.filters
+            .push(Biquad::new(band.coefficients(self.sample_rate)));
+        self.bands.push(band);
+    }
+
+    ///Replaces band n, keeping the filter state.
+    pub fn set_band(&mut self, n: usize, band: Band) {
+        self.filters[n].set_coefficients(band.coefficients(self.sample_rate));
+        self.bands[n] = band;
+    }
+
+    ///Removes band n.
+    pub fn remove_band(&mut self, n: usize) -> Band {
+        self.filters.remove(n);
+        self.bands.remove(n)
+    }
+
+    ///Clears the internal state.
+    pub fn reset(&mut self) {
+        for f in self.filters.iter_mut() {
+            f.reset();
+        }
+    }
+
+    ///Processes block in place.
+    pub fn process<T: Frame>(&mut self, o: &mut Block<T>) {
+        for f in self.filters.iter_mut() {
+            f.process(o);
+        }
+    }
+
+    ///Resets, adopts the sample rate of whole and processes it in place.
+    pub fn process_whole<T: Frame>(&mut self, o: &mut Whole<T>) {
+        let sr = o.sample_rate().get();
+        if sr != self.sample_rate {
+            *self = Self::new(sr, std::mem::take(&mut self.bands));
+        }
+        self.reset();
+        self.process(o);
+    }
+
+    ///Returns combined magnitude response in dB at each frequency.
+    pub fn magnitude_db(&self, freqs: &[f32]) -> Vec<Db> {
+        freqs
+            .iter()
+            .map(|f| {
+                let g = self
+                    .filters
+                    .iter()
+                    .map(|o| o.coefficients().magnitude(self.sample_rate, *f))
+                    .product();
+                Db::from_gain(g)
+            })
+            .collect()
+    }
+}
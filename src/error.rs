@@ -0,0 +1,111 @@
+use crate::cancel::Cancelled;
+use std::io;
+
+///Error of bssf.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    ///Channel size and byte size of a frame of the data differ from those requested, such as of the sample type.
+    LayoutMismatch {
+        expected: (u16, usize),
+        found: (u16, usize),
+    },
+    ///A length is not a multiple of the size of a sample or frame.
+    LengthNotMultiple {
+        len: usize,
+        multiple: usize,
+    },
+    ///Lengths of channels differ.
+    LengthMismatch {
+        expected: usize,
+        found: usize,
+    },
+    SampleRateMismatch {
+        expected: u32,
+        found: u32,
+    },
+    ///Sample rate is zero or out of range.
+    InvalidSampleRate(u32),
+    ///Checksum of data differs from the expected one.
+    ChecksumMismatch {
+        expected: u32,
+        found: u32,
+    },
+    ///Data is malformed.
+    InvalidData(&'static str),
+    ///Format is valid but not supported.
+    Unsupported(&'static str),
+    ///An argument is outside its valid range.
+    InvalidArgument(&'static str),
+    ///Stopped by a CancelToken.
+    Cancelled,
+    Io(io::Error),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LayoutMismatch { expected, found } => write!(
+                f,
+                "layout mismatch: expected {} channels in {} bytes, found {} channels in {} bytes",
+                expected.0, expected.1, found.0, found.1
+            ),
+            Self::LengthNotMultiple { len, multiple } => {
+                write!(f, "length {len} is not a multiple of {multiple}")
+            }
+            Self::LengthMismatch { expected, found } => {
+                write!(f, "length mismatch: expected {expected}, found {found}")
+            }
+            Self::SampleRateMismatch { expected, found } => {
+                write!(
+                    f,
+                    "sample rate mismatch: expected {expected} Hz, found {found} Hz"
+                )
+            }
+            Self::InvalidSampleRate(o) => write!(f, "invalid sample rate: {o} Hz"),
+            Self::ChecksumMismatch { expected, found } => {
+                write!(
+                    f,
+                    "checksum mismatch: expected {expected:08x}, found {found:08x}"
+                )
+            }
+            Self::InvalidData(o) => write!(f, "invalid data: {o}"),
+            Self::Unsupported(o) => write!(f, "unsupported: {o}"),
+            Self::InvalidArgument(o) => write!(f, "invalid argument: {o}"),
+            Self::Cancelled => Cancelled.fmt(f),
+            Self::Io(o) => write!(f, "io error: {o}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(o) => Some(o),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(o: io::Error) -> Self {
+        Self::Io(o)
+    }
+}
+
+impl From<Cancelled> for Error {
+    fn from(_: Cancelled) -> Self {
+        Self::Cancelled
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(o: Error) -> Self {
+        match o {
+            Error::Io(o) => o,
+            Error::Cancelled => Cancelled.into(),
+            Error::Unsupported(_) => io::Error::new(io::ErrorKind::Unsupported, o),
+            _ => io::Error::new(io::ErrorKind::InvalidData, o),
+        }
+    }
+}
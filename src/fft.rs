@@ -0,0 +1,100 @@
+use std::f64::consts::PI;
+use std::ops::{Add, Mul, Sub};
+
+///A complex number.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complex {
+    ///Constructs a new Complex.
+    pub fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+
+    ///Constructs a new Complex of polar form.
+    pub fn from_polar(norm: f64, arg: f64) -> Self {
+        Self::new(norm * arg.cos(), norm * arg.sin())
+    }
+
+    ///Returns the conjugate.
+    pub fn conj(self) -> Self {
+        Self::new(self.re, -self.im)
+    }
+
+    ///Returns the modulus.
+    pub fn norm(self) -> f64 {
+        self.re.hypot(self.im)
+    }
+
+    ///Returns the argument in radians.
+    pub fn arg(self) -> f64 {
+        self.im.atan2(self.re)
+    }
+}
+
+impl Add for Complex {
+    type Output = Self;
+
+    fn add(self, o: Self) -> Self {
+        Self::new(self.re + o.re, self.im + o.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Self;
+
+    fn sub(self, o: Self) -> Self {
+        Self::new(self.re - o.re, self.im - o.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Self;
+
+    fn mul(self, o: Self) -> Self {
+        Self::new(
+            self.re * o.re - self.im * o.im,
+            self.re * o.im + self.im * o.re,
+        )
+    }
+}
+
+///In-place radix-2 FFT, the length must be a power of two. Inverse is not normalized.
+pub(crate) fn fft(v: &mut [Complex], inverse: bool) {
+    let n = v.len();
+    if n < 2 {
+        return;
+    }
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            v.swap(i, j);
+        }
+    }
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let mut len = 2;
+    while len <= n {
+        let a = sign * 2.0 * PI / len as f64;
+        let w = Complex::new(a.cos(), a.sin());
+        for s in (0..n).step_by(len) {
+            let mut t = Complex::new(1.0, 0.0);
+            for k in 0..len / 2 {
+                let x = v[s + k];
+                let y = v[s + k + len / 2] * t;
+                v[s + k] = x + y;
+                v[s + k + len / 2] = x - y;
+                t = t * w;
+            }
+        }
+        len <<= 1;
+    }
+}
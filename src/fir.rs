@@ -0,0 +1,137 @@
+//!FIR filtering with user supplied taps.
+
+use crate::fft::*;
+use crate::format::*;
+use crate::sample::*;
+
+//kernels longer than this use overlap-save.
+const DIRECT_MAX: usize = 64;
+
+fn direct(x: &[f32], taps: &[f32], v: &mut Vec<f32>) {
+    let m = taps.len();
+    v.clear();
+    for j in 0..x.len() + 1 - m {
+        let mut s = 0.0;
+        for (i, h) in taps.iter().enumerate() {
+            s += h * x[j + m - 1 - i];
+        }
+        v.push(s);
+    }
+}
+
+//kernel of taps and the working buffer of overlap-save.
+#[derive(Clone, Debug, Default)]
+struct Kernel {
+    h: Vec<Complex>,
+    buf: Vec<Complex>,
+}
+
+impl Kernel {
+    fn new(taps: &[f32]) -> Self {
+        let n = (4 * taps.len()).next_power_of_two();
+        let mut h = vec![Complex::default(); n];
+        for (i, t) in taps.iter().enumerate() {
+            h[i].re = *t as f64;
+        }
+        fft(&mut h, false);
+        Self {
+            h,
+            buf: vec![Complex::default(); n],
+        }
+    }
+
+    fn overlap_save(&mut self, x: &[f32], m: usize, v: &mut Vec<f32>) {
+        let n = self.h.len();
+        let out = x.len() + 1 - m;
+        let step = n - m + 1;
+        v.clear();
+        let mut s = 0;
+        while s < out {
+            for (k, b) in self.buf.iter_mut().enumerate() {
+                *b = Complex::new(x.get(s + k).map_or(0.0, |o| *o as f64), 0.0);
+            }
+            fft(&mut self.buf, false);
+            for (b, h) in self.buf.iter_mut().zip(self.h.iter()) {
+                *b = *b * *h;
+            }
+            fft(&mut self.buf, true);
+            let e = step.min(out - s);
+            for b in &self.buf[m - 1..m - 1 + e] {
+                v.push((b.re / n as f64) as f32);
+            }
+            s += step;
+        }
+    }
+}
+
+///Filters block with taps, returns a block of the same length. Long kernels use overlap-save.
+pub fn apply<T: Frame>(o: &Block<T>, taps: &[f32]) -> Block<T> {
+    let mut f = Fir::new(taps.to_vec());
+    let mut v = Block::from(o.to_vec());
+    f.process(&mut v);
+    v
+}
+
+///A stateful FIR filter carrying overlap between successive blocks.
+///
+///Processing allocates only while the channel count or block size grows, so steady blocks of the same size are allocation free.
+#[derive(Clone, Debug)]
+pub struct Fir {
+    taps: Vec<f32>,
+    kernel: Option<Kernel>,
+    history: Vec<Vec<f32>>,
+    y: Vec<f32>,
+}
+
+impl Fir {
+    ///Constructs a new Fir.
+    pub fn new(taps: Vec<f32>) -> Self {
+        let kernel = (taps.len() > DIRECT_MAX).then(|| Kernel::new(&taps));
+        Self {
+            taps,
+            kernel,
+            history: Vec::new(),
+            y: Vec::new(),
+        }
+    }
+
+    ///Returns taps.
+    pub fn taps(&self) -> &[f32] {
+        &self.taps
+    }
+
+    ///Clears the internal state.
+    pub fn reset(&mut self) {
+        let m = self.taps.len().saturating_sub(1);
+        for h in self.history.iter_mut() {
+            h.clear();
+            h.resize(m, 0.0);
+        }
+    }
+
+    ///Processes block in place.
+    pub fn process<T: Frame>(&mut self, o: &mut Block<T>) {
+        let m = self.taps.len();
+        if m == 0 {
+            for i in o.iter_mut() {
+                *i = T::default();
+            }
+            return;
+        }
+        let c = T::CHANNEL_SIZE as usize;
+        if self.history.len() != c {
+            self.history.resize(c, vec![0.0; m - 1]);
+        }
+        for (n, x) in self.history.iter_mut().enumerate() {
+            x.extend(o.iter().map(|i| i.channel(n)));
+            match self.kernel.as_mut() {
+                Some(s) => s.overlap_save(x, m, &mut self.y),
+                None => direct(x, &self.taps, &mut self.y),
+            }
+            for (i, v) in o.iter_mut().zip(self.y.iter()) {
+                i.set_channel(n, *v);
+            }
+            x.drain(..x.len() + 1 - m);
+        }
+    }
+}
@@ -1,26 +1,97 @@
+use crate::edit::Marker;
+use crate::error::*;
 use crate::sample::*;
+use crate::swap::*;
+use crate::units::SampleRate;
+use std::io::{self, Write};
 use std::mem::ManuallyDrop;
 use std::ops::{Deref, DerefMut};
 use std::slice::{from_raw_parts, Iter};
 
 ///A contiguous growable block of sample.
+///
+///With the nightly `allocator-api` feature, the storage is parameterized over an allocator, Global by default.
 #[repr(C)]
-pub struct Block<T>(Vec<T>);
+pub struct Block<T, #[cfg(feature = "allocator-api")] A: std::alloc::Allocator = std::alloc::Global>(
+    #[cfg(not(feature = "allocator-api"))] Vec<T>,
+    #[cfg(feature = "allocator-api")] Vec<T, A>,
+);
+
+//implements methods for Block of any allocator with the allocator-api feature, else of the global allocator.
+macro_rules! block_impl {
+    (impl Block<$t:ty> { $($body:tt)* }) => {
+        #[cfg(not(feature = "allocator-api"))]
+        impl Block<$t> {
+            $($body)*
+        }
+
+        #[cfg(feature = "allocator-api")]
+        impl<A> Block<$t, A>
+        where
+            A: std::alloc::Allocator,
+        {
+            $($body)*
+        }
+    };
+    (impl<$g:ident> Block<$t:ty> where $($rest:tt)*) => {
+        $crate::format::block_impl!(@where [$g] [$t] [] $($rest)*);
+    };
+    (impl<$g:ident, const $n:ident: usize> Block<$t:ty> where $($rest:tt)*) => {
+        $crate::format::block_impl!(@where [$g, const $n: usize] [$t] [] $($rest)*);
+    };
+    (@where [$($g:tt)*] [$t:ty] [$($w:tt)*] { $($body:tt)* }) => {
+        #[cfg(not(feature = "allocator-api"))]
+        impl<$($g)*> Block<$t>
+        where
+            $($w)*
+        {
+            $($body)*
+        }
 
+        #[cfg(feature = "allocator-api")]
+        impl<$($g)*, A> Block<$t, A>
+        where
+            A: std::alloc::Allocator,
+            $($w)*
+        {
+            $($body)*
+        }
+    };
+    (@where $g:tt $t:tt [$($w:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::format::block_impl!(@where $g $t [$($w)* $next] $($rest)*);
+    };
+}
+
+pub(crate) use block_impl;
+
+//frames shown at each end by the alternate Debug of Block.
+const PREVIEW: usize = 3;
+
+///The alternate form `{:#?}` also previews the first and last few frames.
 impl<T> std::fmt::Debug for Block<T>
 where
-    T: Sample,
+    T: Sample + std::fmt::Debug,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let alternate = f.alternate();
         let mut f = f.debug_struct("Block");
         f.field("channel_size", &self.channel_size())
             .field("byte_size", &self.byte_size())
             .field("bit_depth", &self.bit_depth())
-            .field("data_size", &self.0.len())
-            .finish()
+            .field("data_size", &self.0.len());
+        if alternate {
+            if self.0.len() <= 2 * PREVIEW {
+                f.field("data", &&self.0[..]);
+            } else {
+                f.field("head", &&self.0[..PREVIEW])
+                    .field("tail", &&self.0[self.0.len() - PREVIEW..]);
+            }
+        }
+        f.finish()
     }
 }
 
+#[cfg(not(feature = "allocator-api"))]
 impl<T> Deref for Block<T>
 where
     T: Sample,
@@ -32,6 +103,7 @@ where
     }
 }
 
+#[cfg(not(feature = "allocator-api"))]
 impl<T> DerefMut for Block<T>
 where
     T: Sample,
@@ -41,71 +113,215 @@ where
     }
 }
 
-impl<T> Block<T>
+#[cfg(feature = "allocator-api")]
+impl<T, A> Deref for Block<T, A>
 where
     T: Sample,
+    A: std::alloc::Allocator,
 {
-    ///Constructs a new, empty Block with the specified capacity.
-    pub fn new(n: usize) -> Self {
-        Self(Vec::with_capacity(n))
+    type Target = Vec<T, A>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
     }
+}
 
-    ///Returns channel size.
-    pub fn channel_size(&self) -> u16 {
-        T::CHANNEL_SIZE
+#[cfg(feature = "allocator-api")]
+impl<T, A> DerefMut for Block<T, A>
+where
+    T: Sample,
+    A: std::alloc::Allocator,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
     }
+}
 
-    ///Returns byte size.
-    pub fn byte_size(&self) -> usize {
-        T::BYTE_SIZE
+#[cfg(feature = "allocator-api")]
+impl<T, A> Block<T, A>
+where
+    T: Sample,
+    A: std::alloc::Allocator,
+{
+    ///Constructs a new, empty Block with the specified capacity in allocator.
+    pub fn new_in(n: usize, alloc: A) -> Self {
+        Self(Vec::with_capacity_in(n, alloc))
     }
 
-    ///Returns bit depth.
-    pub fn bit_depth(&self) -> usize {
-        8 * (T::BYTE_SIZE / T::CHANNEL_SIZE as usize)
+    ///Constructs a Block of a vector in allocator.
+    pub fn from_vec_in(o: Vec<T, A>) -> Self {
+        Self(o)
     }
 
-    fn u8_size(&self) -> usize {
-        T::BYTE_SIZE * self.0.len()
+    ///Returns the allocator.
+    pub fn allocator(&self) -> &A {
+        self.0.allocator()
     }
 
-    ///Returns a slice of u8 bytes.
-    pub fn bytes_slice(&self) -> &[u8] {
-        let n = self.u8_size();
-        let ptr = self.0.as_ptr() as *mut u8;
-        unsafe { from_raw_parts(ptr, n) }
+    ///Returns the inner vector.
+    pub fn into_vec(self) -> Vec<T, A> {
+        self.0
     }
 
-    ///Copies self into a new `Vec<u8>` as a byte array in native byte order.
-    pub fn copy_to_ne_bytes(&self) -> Vec<u8> {
-        let mut v = Vec::with_capacity(self.u8_size());
-        for i in self.0.iter() {
-            v.extend_from_slice(&i.copy_to_ne_bytes());
-        }
-        v
+    ///Copies samples into a Block of the global allocator.
+    pub fn to_global(&self) -> Block<T>
+    where
+        T: Clone,
+    {
+        Block(self.0.to_vec())
+    }
+}
+
+impl<T> Block<T>
+where
+    T: Sample,
+{
+    ///Constructs a new, empty Block with the specified capacity.
+    pub fn new(n: usize) -> Self {
+        Self(Vec::with_capacity(n))
     }
 
-    ///Copies self into a new `Vec<u8>` as a byte array in big-endian byte order.
-    pub fn copy_to_be_bytes(&self) -> Vec<u8> {
-        let mut v = Vec::with_capacity(self.u8_size());
-        for i in self.0.iter() {
-            v.extend_from_slice(&i.copy_to_be_bytes());
+    ///Converts self into a ByteBlock in native byte order reusing the allocation, which is sound only for samples of alignment 1 such as `u8` and `[i8; 2]`. Returns self back otherwise.
+    pub fn into_byte_block_zero_copy(self) -> Result<ByteBlock, Self> {
+        if align_of::<T>() != 1 {
+            return Err(self);
         }
-        v
+        let mut o = ManuallyDrop::new(self.0);
+        let (n, cap) = (o.len() * T::BYTE_SIZE, o.capacity() * T::BYTE_SIZE);
+        let ptr = o.as_mut_ptr() as *mut u8;
+        //same allocation, size and alignment, and samples have no padding.
+        let data = unsafe { Vec::from_raw_parts(ptr, n, cap) };
+        Ok(ByteBlock::new(
+            T::CHANNEL_SIZE,
+            T::BYTE_SIZE,
+            cfg!(target_endian = "big"),
+            data,
+        ))
     }
+}
 
-    ///Copies self into a new `Vec<u8>` as a byte array in little-endian byte order.
-    pub fn copy_to_le_bytes(&self) -> Vec<u8> {
-        let mut v = Vec::with_capacity(self.u8_size());
-        for i in self.0.iter() {
-            v.extend_from_slice(&i.copy_to_le_bytes());
+block_impl! {
+    impl<T> Block<T>
+    where
+        T: Sample,
+    {
+        ///Returns channel size.
+        pub fn channel_size(&self) -> u16 {
+            T::CHANNEL_SIZE
+        }
+
+        ///Returns byte size.
+        pub fn byte_size(&self) -> usize {
+            T::BYTE_SIZE
+        }
+
+        ///Returns bit depth.
+        pub fn bit_depth(&self) -> usize {
+            8 * (T::BYTE_SIZE / T::CHANNEL_SIZE as usize)
+        }
+
+        fn u8_size(&self) -> usize {
+            T::BYTE_SIZE * self.0.len()
+        }
+
+        ///Returns a slice of u8 bytes. With the bytemuck feature, prefer the checked `as_bytes`.
+        pub fn bytes_slice(&self) -> &[u8] {
+            let n = self.u8_size();
+            let ptr = self.0.as_ptr() as *mut u8;
+            unsafe { from_raw_parts(ptr, n) }
+        }
+
+        ///Copies self into a new `Vec<u8>` as a byte array in native byte order.
+        pub fn copy_to_ne_bytes(&self) -> Vec<u8> {
+            self.bytes_slice().to_vec()
+        }
+
+        ///Converts self into a `Vec<u8>` in native byte order by copying.
+        pub fn into_bytes(self) -> Vec<u8> {
+            self.copy_to_ne_bytes()
+        }
+
+        //copies bytes, swapping each scalar unless in native byte order.
+        fn copy_to_bytes(&self, big_endian: bool) -> Vec<u8> {
+            let mut v = Vec::new();
+            self.extend_into(big_endian, &mut v);
+            v
+        }
+
+        ///Appends self to a `Vec<u8>` in big-endian or little-endian byte order.
+        pub fn extend_into(&self, big_endian: bool, v: &mut Vec<u8>) {
+            let n = v.len();
+            v.extend_from_slice(self.bytes_slice());
+            if big_endian != cfg!(target_endian = "big") {
+                swap_bytes(&mut v[n..], T::BYTE_SIZE / T::CHANNEL_SIZE as usize);
+            }
+        }
+
+        //writes bytes through a stack buffer, swapping each scalar unless in native byte order.
+        fn write_bytes(&self, big_endian: bool, w: &mut impl Write) -> io::Result<()> {
+            let bytes = self.bytes_slice();
+            if big_endian == cfg!(target_endian = "big") {
+                return w.write_all(bytes);
+            }
+            let width = T::BYTE_SIZE / T::CHANNEL_SIZE as usize;
+            let mut buf = [0u8; 4096];
+            let n = buf.len() / width.max(1) * width.max(1);
+            for o in bytes.chunks(n) {
+                let b = &mut buf[..o.len()];
+                b.copy_from_slice(o);
+                swap_bytes(b, width);
+                w.write_all(b)?;
+            }
+            Ok(())
+        }
+
+        ///Writes self to a writer in big-endian byte order without an intermediate copy of the whole block.
+        pub fn write_be_bytes(&self, w: &mut impl Write) -> io::Result<()> {
+            self.write_bytes(true, w)
+        }
+
+        ///Writes self to a writer in little-endian byte order without an intermediate copy of the whole block.
+        pub fn write_le_bytes(&self, w: &mut impl Write) -> io::Result<()> {
+            self.write_bytes(false, w)
+        }
+
+        ///Copies self into a new `Vec<u8>` as a byte array in big-endian byte order.
+        pub fn copy_to_be_bytes(&self) -> Vec<u8> {
+            self.copy_to_bytes(true)
+        }
+
+        ///Copies self into a new `Vec<u8>` as a byte array in little-endian byte order.
+        pub fn copy_to_le_bytes(&self) -> Vec<u8> {
+            self.copy_to_bytes(false)
+        }
+
+        ///Returns an iterator.
+        pub fn channel_iter(&self, n: usize) -> ChannelIter<'_, T> {
+            ChannelIter::new(self.iter(), n)
         }
-        v
     }
+}
 
-    ///Returns an iterator.
-    pub fn channel_iter(&self, n: usize) -> ChannelIter<'_, T> {
-        ChannelIter::new(self.iter(), n)
+#[cfg(feature = "bytemuck")]
+block_impl! {
+    impl<T> Block<T>
+    where
+        T: Sample + bytemuck::Pod,
+    {
+        ///Returns samples as bytes in native byte order without copying.
+        pub fn as_bytes(&self) -> &[u8] {
+            bytemuck::cast_slice(&self.0)
+        }
+
+        ///Returns samples as mutable bytes in native byte order without copying.
+        pub fn as_bytes_mut(&mut self) -> &mut [u8] {
+            bytemuck::cast_slice_mut(&mut self.0)
+        }
+
+        ///Returns samples reinterpreted as a slice of U, such as frames as scalars, failing on size or alignment mismatch.
+        pub fn try_cast_slice<U: bytemuck::Pod>(&self) -> Result<&[U], bytemuck::PodCastError> {
+            bytemuck::try_cast_slice(&self.0)
+        }
     }
 }
 
@@ -136,6 +352,7 @@ where
     }
 }
 
+#[allow(clippy::from_over_into)]
 impl<T> Into<Box<[u8]>> for Block<T>
 where
     T: Sample,
@@ -145,18 +362,17 @@ where
     }
 }
 
+#[allow(clippy::from_over_into)]
 impl<T> Into<Vec<u8>> for Block<T>
 where
     T: Sample,
 {
     fn into(self) -> Vec<u8> {
-        let n = self.u8_size();
-        let mut o = ManuallyDrop::new(self.0);
-        let ptr = o.as_mut_ptr() as *mut u8;
-        unsafe { Vec::<u8>::from_raw_parts(ptr, n, n) }
+        self.into_bytes()
     }
 }
 
+#[allow(clippy::from_over_into)]
 impl<T> Into<ByteBlock> for Block<T>
 where
     T: Sample,
@@ -218,6 +434,28 @@ impl std::fmt::Debug for ByteBlock {
     }
 }
 
+///Shows channels, bit depth, byte order and frames, e.g. "2 ch, 16-bit, little-endian, 1024 frames".
+impl std::fmt::Display for ByteBlock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} ch, {}-bit, {}, {} frames",
+            self.channel_size,
+            self.bit_depth(),
+            if self.big_endian {
+                "big-endian"
+            } else {
+                "little-endian"
+            },
+            self.data.len().checked_div(self.byte_size).unwrap_or(0)
+        )?;
+        match self.data.len().checked_rem(self.byte_size) {
+            Some(n) if n > 0 => write!(f, " + {n} bytes"),
+            _ => Ok(()),
+        }
+    }
+}
+
 impl Deref for ByteBlock {
     type Target = Vec<u8>;
 
@@ -267,30 +505,101 @@ impl ByteBlock {
         self.byte_size
     }
 
-    ///Returns bit depth.
+    ///Returns bit depth, 0 if channel size is 0.
     pub fn bit_depth(&self) -> usize {
-        8 * (self.byte_size / self.channel_size as usize)
+        (8 * self.byte_size)
+            .checked_div(self.channel_size as usize)
+            .unwrap_or(0)
+    }
+
+    ///Returns true if data is in big-endian byte order.
+    pub fn is_big_endian(&self) -> bool {
+        self.big_endian
+    }
+
+    ///Converts data in place to big-endian or little-endian byte order.
+    pub fn convert_endian(&mut self, big_endian: bool) {
+        if self.big_endian != big_endian {
+            swap_bytes(
+                &mut self.data,
+                self.byte_size / self.channel_size.max(1) as usize,
+            );
+            self.big_endian = big_endian;
+        }
+    }
+
+    ///Constructs a new ByteBlock, failing if byte size is not a multiple of channel size or data is not a multiple of byte size.
+    pub fn try_new(
+        channel_size: u16,
+        byte_size: usize,
+        big_endian: bool,
+        data: Vec<u8>,
+    ) -> Result<Self, Error> {
+        if channel_size == 0 || !byte_size.is_multiple_of(channel_size as usize) {
+            return Err(Error::LengthNotMultiple {
+                len: byte_size,
+                multiple: channel_size as usize,
+            });
+        }
+        if byte_size == 0 || !data.len().is_multiple_of(byte_size) {
+            return Err(Error::LengthNotMultiple {
+                len: data.len(),
+                multiple: byte_size,
+            });
+        }
+        Ok(Self::new(channel_size, byte_size, big_endian, data))
     }
 
-    ///Converts Self into Block.
-    pub fn into_block<T: Sample>(self, f: impl Fn(&[u8]) -> T) -> Block<T> {
-        let byte_size = self.byte_size;
-        let len = self.data.len();
-        let data = &self.data;
-        let mut v = Vec::new();
-        let a = len % byte_size;
-        let b = len - a;
-        let mut i = 0;
-        while i < b {
-            let o = unsafe { from_raw_parts(&data[i], byte_size) };
-            v.push(f(o));
-            i += byte_size;
+    ///Converts Self into Block, passing each frame of byte size to f. Leftover bytes shorter than a frame are handled by trailing. A byte size of 0 gives an empty block. Fails if the channel size of T differs.
+    pub fn into_block<T: Sample>(
+        self,
+        trailing: Trailing,
+        f: impl Fn(&[u8]) -> T,
+    ) -> Result<Block<T>, Error> {
+        if T::CHANNEL_SIZE != self.channel_size {
+            return Err(Error::LayoutMismatch {
+                expected: (T::CHANNEL_SIZE, T::BYTE_SIZE),
+                found: (self.channel_size, self.byte_size),
+            });
         }
-        if a > 0 {
-            let o = unsafe { from_raw_parts(&data[i], a) };
-            v.push(f(o));
+        if self.byte_size == 0 {
+            return Ok(Block::from(Vec::new()));
         }
-        Block::from(v)
+        let chunks = self.data.chunks_exact(self.byte_size);
+        let rest = chunks.remainder();
+        if !rest.is_empty() && trailing == Trailing::Error {
+            return Err(Error::LengthNotMultiple {
+                len: self.data.len(),
+                multiple: self.byte_size,
+            });
+        }
+        let mut v = Vec::with_capacity(self.data.len().div_ceil(self.byte_size));
+        v.extend(chunks.map(&f));
+        if !rest.is_empty() && trailing == Trailing::ZeroPad {
+            let mut o = vec![0; self.byte_size];
+            o[..rest.len()].copy_from_slice(rest);
+            v.push(f(&o));
+        }
+        Ok(Block::from(v))
+    }
+}
+
+///Policy of leftover bytes shorter than a frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Trailing {
+    ///Fails with Error::LengthNotMultiple.
+    Error,
+    ///Drops the bytes.
+    Ignore,
+    ///Pads the bytes with zero to a sample.
+    ZeroPad,
+}
+
+#[cfg(feature = "bytemuck")]
+impl ByteBlock {
+    ///Returns data reinterpreted as samples in native byte order regardless of byte order, failing on size or alignment mismatch.
+    pub fn try_cast_slice<T: bytemuck::Pod>(&self) -> Result<&[T], bytemuck::PodCastError> {
+        bytemuck::try_cast_slice(&self.data)
     }
 }
 
@@ -311,23 +620,24 @@ where
     }
 }
 
-impl Into<Box<[u8]>> for ByteBlock {
-    fn into(self) -> Box<[u8]> {
-        self.data.into_boxed_slice()
+impl From<ByteBlock> for Box<[u8]> {
+    fn from(o: ByteBlock) -> Self {
+        o.data.into_boxed_slice()
     }
 }
 
-impl Into<Vec<u8>> for ByteBlock {
-    fn into(self) -> Vec<u8> {
-        self.data
+impl From<ByteBlock> for Vec<u8> {
+    fn from(o: ByteBlock) -> Self {
+        o.data
     }
 }
 
 ///Whole info of audio.
 #[repr(C)]
 pub struct Whole<T> {
-    sample_rate: u32,
+    sample_rate: SampleRate,
     data: Block<T>,
+    markers: Vec<Marker>,
 }
 
 impl<T> std::fmt::Debug for Whole<T>
@@ -341,10 +651,35 @@ where
             .field("byte_size", &self.byte_size())
             .field("bit_depth", &self.bit_depth())
             .field("data_size", &self.0.len())
+            .field("markers", &self.markers.len())
             .finish()
     }
 }
 
+///Shows sample rate, channels, bit depth and duration, e.g. "48000 Hz, 2 ch, 24-bit, 03:25.127".
+impl<T> std::fmt::Display for Whole<T>
+where
+    T: Sample,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}, {} ch, {}-bit, ",
+            self.sample_rate,
+            self.channel_size(),
+            self.bit_depth()
+        )?;
+        let sr = self.sample_rate.get() as u128;
+        let ms = (self.len() as u128 * 1000 + sr / 2) / sr;
+        let (h, m, s, ms) = (ms / 3_600_000, ms / 60_000 % 60, ms / 1000 % 60, ms % 1000);
+        if h > 0 {
+            write!(f, "{h}:{m:02}:{s:02}.{ms:03}")
+        } else {
+            write!(f, "{m:02}:{s:02}.{ms:03}")
+        }
+    }
+}
+
 impl<T> Deref for Whole<T>
 where
     T: Sample,
@@ -369,28 +704,64 @@ impl<T> Whole<T>
 where
     T: Sample,
 {
-    ///Constructs a new, empty Whole with the specified capacity.
-    pub fn new(sample_rate: u32, n: usize) -> Self {
+    ///Constructs a new, empty Whole of a valid sample rate with the specified capacity.
+    pub fn new(sample_rate: SampleRate, n: usize) -> Self {
         Self::from_block(sample_rate, Block::new(n))
     }
 
     ///Constructs a new Whole from Block.
-    pub fn from_block(sample_rate: u32, data: Block<T>) -> Self {
-        Self { sample_rate, data }
+    pub fn from_block(sample_rate: SampleRate, data: Block<T>) -> Self {
+        Self {
+            sample_rate,
+            data,
+            markers: Vec::new(),
+        }
+    }
+
+    ///Returns sample rate.
+    pub fn sample_rate(&self) -> SampleRate {
+        self.sample_rate
     }
 
     ///Add elements of other into self.
     pub fn add(&mut self, mut o: Block<T>) {
         self.data.append(&mut o);
     }
+
+    ///Returns markers.
+    pub fn markers(&self) -> &[Marker] {
+        &self.markers
+    }
+
+    ///Returns mutable markers.
+    pub fn markers_mut(&mut self) -> &mut Vec<Marker> {
+        &mut self.markers
+    }
+
+    ///Appends frames and markers of other, failing if sample rates differ.
+    pub fn concat(&mut self, mut o: Whole<T>) -> Result<(), Error> {
+        if o.sample_rate != self.sample_rate {
+            return Err(Error::SampleRateMismatch {
+                expected: self.sample_rate.get(),
+                found: o.sample_rate.get(),
+            });
+        }
+        let n = self.data.len();
+        self.markers.extend(o.markers.drain(..).map(|mut m| {
+            m.frame += n;
+            m
+        }));
+        self.data.append(&mut o.data);
+        Ok(())
+    }
 }
 
-impl<T> Into<Block<T>> for Whole<T>
+impl<T> From<Whole<T>> for Block<T>
 where
     T: Sample,
 {
-    fn into(self) -> Block<T> {
-        self.data
+    fn from(o: Whole<T>) -> Self {
+        o.data
     }
 }
 
@@ -447,6 +818,45 @@ macro_rules! max_merge {
     }};
 }
 
+///Build planar channels of equal length to packed block, failing if lengths differ.
+pub fn try_build<T, const N: usize>(channels: [&[T]; N]) -> Result<Block<[T; N]>, Error>
+where
+    T: Type + Clone,
+    [T; N]: Sample,
+{
+    let n = channels.first().map_or(0, |o| o.len());
+    if let Some(o) = channels.iter().find(|o| o.len() != n) {
+        return Err(Error::LengthMismatch {
+            expected: n,
+            found: o.len(),
+        });
+    }
+    let mut v = Block::new(n);
+    (0..n).for_each(|i| v.push(std::array::from_fn(|c| channels[c][i].clone())));
+    Ok(v)
+}
+
+impl<T, const N: usize> Block<[T; N]>
+where
+    T: Type + Clone,
+    [T; N]: Sample,
+{
+    ///Constructs a new Block from interleaved samples, failing if their number is not a multiple of N.
+    pub fn from_interleaved(o: &[T]) -> Result<Self, Error> {
+        if N == 0 || !o.len().is_multiple_of(N) {
+            return Err(Error::LengthNotMultiple {
+                len: o.len(),
+                multiple: N,
+            });
+        }
+        let mut v = Block::new(o.len() / N);
+        for i in o.chunks_exact(N) {
+            v.push(std::array::from_fn(|c| i[c].clone()));
+        }
+        Ok(v)
+    }
+}
+
 ///Build a channel to block.
 pub fn build_mono<T>(a: &[T]) -> Block<T>
 where
@@ -584,6 +994,7 @@ where
 }
 
 ///Build 8 planar channels to packed block.
+#[allow(clippy::too_many_arguments)]
 pub fn build_8<T>(
     a: &[T],
     b: &[T],
@@ -601,6 +1012,7 @@ where
 }
 
 ///Build 8 planar channels to packed block. Padding if slice's number are different.
+#[allow(clippy::too_many_arguments)]
 pub fn build_8_padding<T>(
     a: &[T],
     b: &[T],
@@ -618,6 +1030,7 @@ where
 }
 
 ///Build 8 planar channels to packed block.
+#[allow(clippy::too_many_arguments)]
 pub fn build_octagonal<T>(
     a: &[T],
     b: &[T],
@@ -635,6 +1048,7 @@ where
 }
 
 ///Build 8 planar channels to packed block.
+#[allow(clippy::too_many_arguments)]
 pub fn build_cube<T>(
     a: &[T],
     b: &[T],
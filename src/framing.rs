@@ -0,0 +1,128 @@
+use crate::error::*;
+use crate::format::*;
+use crate::sample::*;
+use std::num::NonZeroUsize;
+use std::time::Duration;
+
+///Handling of the final chunk shorter than the chunk size.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Padding<T> {
+    ///Yield it shorter than the others.
+    Partial,
+    ///Skip it, leaving it to `remainder`.
+    Drop,
+    ///Yield it filled up to the chunk size with a frame, such as silence.
+    Fill(T),
+}
+
+///An iterator of blocks of exactly n frames, as for codecs of fixed frame sizes.
+pub struct FrameChunks<'a, T> {
+    data: &'a [T],
+    n: usize,
+    padding: Padding<T>,
+}
+
+impl<T> std::fmt::Debug for FrameChunks<'_, T>
+where
+    T: Sample,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("FrameChunks");
+        f.field("channel_size", &T::CHANNEL_SIZE)
+            .field("chunk_size", &self.n)
+            .field("data_size", &self.data.len())
+            .finish()
+    }
+}
+
+impl<'a, T> FrameChunks<'a, T>
+where
+    T: Sample + Clone,
+{
+    ///Returns frames of a chunk.
+    pub fn chunk_size(&self) -> usize {
+        self.n
+    }
+
+    ///Returns the final frames short of a chunk, not yet yielded.
+    pub fn remainder(&self) -> &'a [T] {
+        &self.data[self.data.len() / self.n * self.n..]
+    }
+}
+
+impl<T> Iterator for FrameChunks<'_, T>
+where
+    T: Sample + Clone,
+{
+    type Item = Block<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.len() >= self.n {
+            let (a, b) = self.data.split_at(self.n);
+            self.data = b;
+            return Some(Block::from(a.to_vec()));
+        }
+        if self.data.is_empty() {
+            return None;
+        }
+        let v = match &self.padding {
+            Padding::Partial => self.data.to_vec(),
+            Padding::Drop => return None,
+            Padding::Fill(o) => {
+                let mut v = self.data.to_vec();
+                v.resize(self.n, o.clone());
+                v
+            }
+        };
+        self.data = &[];
+        Some(Block::from(v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = match self.padding {
+            Padding::Drop => self.data.len() / self.n,
+            _ => self.data.len().div_ceil(self.n),
+        };
+        (n, Some(n))
+    }
+}
+
+impl<T> ExactSizeIterator for FrameChunks<'_, T> where T: Sample + Clone {}
+
+block_impl! {
+    impl<T> Block<T>
+    where
+        T: Sample + Clone,
+    {
+        ///Returns an iterator of blocks of n frames, the final partial chunk handled by padding.
+        pub fn chunks_frames(&self, n: NonZeroUsize, padding: Padding<T>) -> FrameChunks<'_, T> {
+            FrameChunks {
+                data: self,
+                n: n.get(),
+                padding,
+            }
+        }
+    }
+}
+
+impl<T> Whole<T>
+where
+    T: Sample + Clone,
+{
+    ///Returns an iterator of blocks of duration, such as 20 ms for 960 frames or 2.5 ms for 120 frames at 48 kHz, the final partial chunk handled by padding. Fails unless the duration is a whole, nonzero number of frames.
+    pub fn chunk_by_duration(
+        &self,
+        duration: Duration,
+        padding: Padding<T>,
+    ) -> Result<FrameChunks<'_, T>, Error> {
+        let n = self.sample_rate().get() as u128 * duration.as_nanos();
+        if !n.is_multiple_of(1_000_000_000) {
+            return Err(Error::Unsupported("duration not a whole number of frames"));
+        }
+        let n = usize::try_from(n / 1_000_000_000)
+            .ok()
+            .and_then(NonZeroUsize::new)
+            .ok_or(Error::Unsupported("duration not a whole number of frames"))?;
+        Ok(self.chunks_frames(n, padding))
+    }
+}
@@ -0,0 +1,663 @@
+//!Signal generators.
+
+use crate::db::*;
+use crate::error::*;
+use crate::format::*;
+use crate::sample::*;
+use crate::units::*;
+use crate::util::*;
+use std::f64::consts::TAU;
+
+fn frames(sample_rate: SampleRate, duration: f32) -> usize {
+    (duration.max(0.0) as f64 * sample_rate.get() as f64).round() as usize
+}
+
+fn whole(sample_rate: SampleRate, duration: f32, i: impl Iterator<Item = f32>) -> Whole<f32> {
+    let n = frames(sample_rate, duration);
+    let mut v = Block::new(n);
+    v.extend(i.take(n));
+    Whole::from_block(sample_rate, v)
+}
+
+///A phase accumulator in cycles.
+#[derive(Clone, Debug)]
+struct Phasor {
+    step: f64,
+    phase: f64,
+}
+
+impl Phasor {
+    fn new(sample_rate: SampleRate, freq: f32, phase: f32) -> Self {
+        Self {
+            step: freq as f64 / sample_rate.get() as f64,
+            phase: (phase as f64 / TAU).rem_euclid(1.0),
+        }
+    }
+
+    fn next(&mut self) -> f64 {
+        let p = self.phase;
+        self.phase = (self.phase + self.step).rem_euclid(1.0);
+        p
+    }
+}
+
+///An endless sine wave iterator.
+#[derive(Clone, Debug)]
+pub struct Sine {
+    phasor: Phasor,
+    amplitude: f32,
+}
+
+impl Sine {
+    ///Constructs a new Sine. Frequency is in Hz, phase is in radians.
+    pub fn new(sample_rate: SampleRate, freq: f32, amplitude: f32, phase: f32) -> Self {
+        Self {
+            phasor: Phasor::new(sample_rate, freq, phase),
+            amplitude,
+        }
+    }
+}
+
+impl Iterator for Sine {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        Some(self.amplitude * (TAU * self.phasor.next()).sin() as f32)
+    }
+}
+
+///Returns a sine wave whole. Frequency is in Hz, duration is in seconds, phase is in radians.
+pub fn sine(
+    sample_rate: SampleRate,
+    freq: f32,
+    duration: f32,
+    amplitude: f32,
+    phase: f32,
+) -> Whole<f32> {
+    whole(
+        sample_rate,
+        duration,
+        Sine::new(sample_rate, freq, amplitude, phase),
+    )
+}
+
+///An endless naive square wave iterator.
+#[derive(Clone, Debug)]
+pub struct Square {
+    phasor: Phasor,
+    amplitude: f32,
+    duty: f64,
+}
+
+impl Square {
+    ///Constructs a new Square. Duty is the high proportion of a cycle from 0 to 1.
+    pub fn new(sample_rate: SampleRate, freq: f32, amplitude: f32, phase: f32, duty: f32) -> Self {
+        Self {
+            phasor: Phasor::new(sample_rate, freq, phase),
+            amplitude,
+            duty: duty.clamp(0.0, 1.0) as f64,
+        }
+    }
+}
+
+impl Iterator for Square {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        Some(if self.phasor.next() < self.duty {
+            self.amplitude
+        } else {
+            -self.amplitude
+        })
+    }
+}
+
+///An endless naive rising sawtooth wave iterator, crossing zero upwards at phase 0.
+#[derive(Clone, Debug)]
+pub struct Saw {
+    phasor: Phasor,
+    amplitude: f32,
+}
+
+impl Saw {
+    ///Constructs a new Saw.
+    pub fn new(sample_rate: SampleRate, freq: f32, amplitude: f32, phase: f32) -> Self {
+        Self {
+            phasor: Phasor::new(sample_rate, freq, phase),
+            amplitude,
+        }
+    }
+}
+
+impl Iterator for Saw {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let p = (self.phasor.next() + 0.5).fract();
+        Some(self.amplitude * (2.0 * p - 1.0) as f32)
+    }
+}
+
+///An endless triangle wave iterator, in phase with sine.
+#[derive(Clone, Debug)]
+pub struct Triangle {
+    phasor: Phasor,
+    amplitude: f32,
+}
+
+impl Triangle {
+    ///Constructs a new Triangle.
+    pub fn new(sample_rate: SampleRate, freq: f32, amplitude: f32, phase: f32) -> Self {
+        Self {
+            phasor: Phasor::new(sample_rate, freq, phase),
+            amplitude,
+        }
+    }
+}
+
+impl Iterator for Triangle {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let p = (self.phasor.next() + 0.25).fract();
+        Some(self.amplitude * (1.0 - 4.0 * (p - 0.5).abs()) as f32)
+    }
+}
+
+///Returns a square wave whole. Duty is the high proportion of a cycle from 0 to 1.
+pub fn square(
+    sample_rate: SampleRate,
+    freq: f32,
+    duration: f32,
+    amplitude: f32,
+    phase: f32,
+    duty: f32,
+) -> Whole<f32> {
+    whole(
+        sample_rate,
+        duration,
+        Square::new(sample_rate, freq, amplitude, phase, duty),
+    )
+}
+
+///Returns a sawtooth wave whole.
+pub fn saw(
+    sample_rate: SampleRate,
+    freq: f32,
+    duration: f32,
+    amplitude: f32,
+    phase: f32,
+) -> Whole<f32> {
+    whole(
+        sample_rate,
+        duration,
+        Saw::new(sample_rate, freq, amplitude, phase),
+    )
+}
+
+///Returns a triangle wave whole.
+pub fn triangle(
+    sample_rate: SampleRate,
+    freq: f32,
+    duration: f32,
+    amplitude: f32,
+    phase: f32,
+) -> Whole<f32> {
+    whole(
+        sample_rate,
+        duration,
+        Triangle::new(sample_rate, freq, amplitude, phase),
+    )
+}
+
+///Distribution of white noise.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Distribution {
+    Uniform,
+    Gaussian,
+}
+
+///An endless white noise iterator.
+#[derive(Clone, Debug)]
+pub struct WhiteNoise {
+    rng: Rng,
+    rms: f64,
+    distribution: Distribution,
+}
+
+impl WhiteNoise {
+    ///Constructs a new WhiteNoise of RMS level in dBFS.
+    pub fn new(level: Db, distribution: Distribution, seed: u64) -> Self {
+        Self {
+            rng: Rng::new(seed),
+            rms: level.to_gain() as f64,
+            distribution,
+        }
+    }
+
+    fn unit(&mut self) -> f64 {
+        match self.distribution {
+            Distribution::Uniform => (2.0 * self.rng.uniform() - 1.0) * 3f64.sqrt(),
+            Distribution::Gaussian => self.rng.gaussian(),
+        }
+    }
+}
+
+impl Iterator for WhiteNoise {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        Some((self.rms * self.unit()) as f32)
+    }
+}
+
+//RMS gain of the pink filter for unit white noise.
+const PINK_GAIN: f64 = 3.059;
+
+///An endless pink noise iterator of filtered uniform white noise.
+#[derive(Clone, Debug)]
+pub struct PinkNoise {
+    white: WhiteNoise,
+    b: [f64; 7],
+}
+
+impl PinkNoise {
+    ///Constructs a new PinkNoise of RMS level in dBFS.
+    pub fn new(level: Db, seed: u64) -> Self {
+        let mut white = WhiteNoise::new(Db::UNITY, Distribution::Uniform, seed);
+        white.rms = level.to_gain() as f64 / PINK_GAIN;
+        Self { white, b: [0.0; 7] }
+    }
+}
+
+impl Iterator for PinkNoise {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        //refined filter of Paul Kellet.
+        let w = self.white.unit();
+        let b = &mut self.b;
+        b[0] = 0.99886 * b[0] + w * 0.0555179;
+        b[1] = 0.99332 * b[1] + w * 0.0750759;
+        b[2] = 0.96900 * b[2] + w * 0.1538520;
+        b[3] = 0.86650 * b[3] + w * 0.3104856;
+        b[4] = 0.55000 * b[4] + w * 0.5329522;
+        b[5] = -0.7616 * b[5] - w * 0.0168980;
+        let p = b.iter().sum::<f64>() + w * 0.5362;
+        b[6] = w * 0.115926;
+        Some((self.white.rms * p) as f32)
+    }
+}
+
+///Returns a white noise whole of RMS level in dBFS.
+pub fn white_noise(
+    sample_rate: SampleRate,
+    duration: f32,
+    level: Db,
+    distribution: Distribution,
+    seed: u64,
+) -> Whole<f32> {
+    whole(
+        sample_rate,
+        duration,
+        WhiteNoise::new(level, distribution, seed),
+    )
+}
+
+///Returns a pink noise whole of RMS level in dBFS.
+pub fn pink_noise(sample_rate: SampleRate, duration: f32, level: Db, seed: u64) -> Whole<f32> {
+    whole(sample_rate, duration, PinkNoise::new(level, seed))
+}
+
+///Frequency progression of sweep.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SweepKind {
+    Linear,
+    Logarithmic,
+}
+
+///Returns a sine sweep whole from start to end frequency in Hz. Logarithmic sweep requires positive frequencies.
+pub fn sweep(
+    sample_rate: SampleRate,
+    start: f32,
+    end: f32,
+    duration: f32,
+    amplitude: f32,
+    kind: SweepKind,
+) -> Whole<f32> {
+    let n = frames(sample_rate, duration);
+    let (f0, f1) = (start as f64, end as f64);
+    let t1 = duration.max(f32::MIN_POSITIVE) as f64;
+    let r = (f1 / f0).ln();
+    let mut v = Block::new(n);
+    for i in 0..n {
+        let t = i as f64 / sample_rate.get() as f64;
+        let cycles = match kind {
+            SweepKind::Logarithmic if r.is_finite() && r != 0.0 => {
+                f0 * t1 / r * ((t * r / t1).exp() - 1.0)
+            }
+            SweepKind::Logarithmic => f0 * t,
+            SweepKind::Linear => f0 * t + (f1 - f0) * t * t / (2.0 * t1),
+        };
+        v.push(amplitude * (TAU * cycles.fract()).sin() as f32);
+    }
+    Whole::from_block(sample_rate, v)
+}
+
+///Returns a whole of unit impulses of amplitude at frame positions, positions beyond duration are ignored.
+pub fn impulse(
+    sample_rate: SampleRate,
+    duration: f32,
+    positions: &[usize],
+    amplitude: f32,
+) -> Whole<f32> {
+    let n = frames(sample_rate, duration);
+    let mut v = Block::from(vec![0.0f32; n]);
+    for p in positions {
+        if let Some(o) = v.get_mut(*p) {
+            *o += amplitude;
+        }
+    }
+    Whole::from_block(sample_rate, v)
+}
+
+///Returns a whole of band-limited clicks centered at frame positions, Hann windowed sinc pulses of cutoff in Hz with peak amplitude.
+pub fn click(
+    sample_rate: SampleRate,
+    duration: f32,
+    positions: &[usize],
+    amplitude: f32,
+    cutoff: f32,
+) -> Whole<f32> {
+    let n = frames(sample_rate, duration);
+    let mut v = Block::from(vec![0.0f32; n]);
+    let k = 2.0 * cutoff.max(1.0) as f64 / sample_rate.get() as f64;
+    //three zero crossings each side.
+    let half = (3.0 / k).ceil() as isize;
+    for p in positions {
+        for d in -half..=half {
+            let Some(o) = (*p as isize + d)
+                .try_into()
+                .ok()
+                .and_then(|i: usize| v.get_mut(i))
+            else {
+                continue;
+            };
+            let x = std::f64::consts::PI * k * d as f64;
+            let sinc = if d == 0 { 1.0 } else { x.sin() / x };
+            let w = 0.5 + 0.5 * (std::f64::consts::PI * d as f64 / (half + 1) as f64).cos();
+            *o += amplitude * (sinc * w) as f32;
+        }
+    }
+    Whole::from_block(sample_rate, v)
+}
+
+///Returns a zeroed whole of duration in seconds. Fails if channels is not the channel size of T.
+pub fn silence<T>(sample_rate: SampleRate, channels: u16, duration: f32) -> Result<Whole<T>, Error>
+where
+    T: Sample + Default + Clone,
+{
+    if channels != T::CHANNEL_SIZE {
+        let width = T::BYTE_SIZE / T::CHANNEL_SIZE as usize;
+        return Err(Error::LayoutMismatch {
+            expected: (T::CHANNEL_SIZE, T::BYTE_SIZE),
+            found: (channels, channels as usize * width),
+        });
+    }
+    let n = frames(sample_rate, duration);
+    Ok(Whole::from_block(
+        sample_rate,
+        Block::from(vec![T::default(); n]),
+    ))
+}
+
+//Level of line-up tones in dBFS.
+const LINEUP_LEVEL: Db = Db(-18.0);
+
+//Renders sines of channel frequencies, each muted where on returns false for channel and time in seconds.
+fn gated<const N: usize>(
+    sample_rate: SampleRate,
+    duration: f32,
+    freqs: [f32; N],
+    level: Db,
+    on: impl Fn(usize, f64) -> bool,
+) -> Whole<[f32; N]>
+where
+    [f32; N]: Sample,
+{
+    let n = frames(sample_rate, duration);
+    let a = level.to_gain();
+    let mut v = Block::new(n);
+    for i in 0..n {
+        let t = i as f64 / sample_rate.get() as f64;
+        let mut o = [0.0; N];
+        for (c, s) in o.iter_mut().enumerate() {
+            if on(c, t) {
+                *s = a * (TAU * (freqs[c] as f64 * t).fract()).sin() as f32;
+            }
+        }
+        v.push(o);
+    }
+    Whole::from_block(sample_rate, v)
+}
+
+///Returns a 1 kHz sine reference tone at -20 dBFS peak.
+pub fn reference_tone(sample_rate: SampleRate, duration: f32) -> Whole<f32> {
+    sine(sample_rate, 1000.0, duration, Db(-20.0).to_gain(), 0.0)
+}
+
+///Returns an EBU stereo line-up tone, 1 kHz at -18 dBFS with the left channel interrupted for 250 ms every 3 seconds.
+pub fn ebu_lineup(sample_rate: SampleRate, duration: f32) -> Whole<[f32; 2]> {
+    gated(sample_rate, duration, [1000.0; 2], LINEUP_LEVEL, |c, t| {
+        c != 0 || t % 3.0 >= 0.25
+    })
+}
+
+//Returns true if channel is sounding at time in seconds of a GLITS cycle.
+fn glits_on(c: usize, t: f64) -> bool {
+    let t = t % 4.0;
+    match c {
+        0 => !(0.0..0.25).contains(&t),
+        _ => !(0.5..0.75).contains(&t) && !(1.0..1.25).contains(&t),
+    }
+}
+
+///Returns a GLITS stereo ident, 1 kHz at -18 dBFS repeating a 4 second cycle: left interrupted once for 250 ms, then right interrupted twice.
+pub fn glits(sample_rate: SampleRate, duration: f32) -> Whole<[f32; 2]> {
+    gated(sample_rate, duration, [1000.0; 2], LINEUP_LEVEL, glits_on)
+}
+
+///Returns a BLITS 5.1 ident in channel order L, R, C, LFE, Ls, Rs at -18 dBFS.
+///
+///Each channel sounds in turn for 600 ms followed by 200 ms of silence (880 Hz front, 1320 Hz centre, 82.5 Hz LFE, 660 Hz surround), then the fronts carry a GLITS cycle for the remaining duration.
+pub fn blits(sample_rate: SampleRate, duration: f32) -> Whole<[f32; 6]> {
+    const IDENT: f64 = 0.8;
+    let freqs = [880.0, 880.0, 1320.0, 82.5, 660.0, 660.0];
+    gated(sample_rate, duration, freqs, LINEUP_LEVEL, |c, t| {
+        if t < 6.0 * IDENT {
+            (t / IDENT) as usize == c && t % IDENT < 0.6
+        } else {
+            c < 2 && glits_on(c, t - 6.0 * IDENT)
+        }
+    })
+}
+
+///Returns a metronome click track of tempo in beats per minute, the first beat of each bar is accented with a higher and louder click.
+pub fn click_track(
+    sample_rate: SampleRate,
+    bpm: f32,
+    beats_per_bar: u32,
+    duration: f32,
+) -> Whole<f32> {
+    let n = frames(sample_rate, duration);
+    let mut v = Block::from(vec![0.0f32; n]);
+    if bpm <= 0.0 {
+        return Whole::from_block(sample_rate, v);
+    }
+    let sr = sample_rate.get() as f64;
+    let beat = 60.0 * sr / bpm as f64;
+    let len = ms_to_frames(30.0, sample_rate.get());
+    let decay = time_coef(5.0, sample_rate.get()) as f64;
+    let mut k = 0;
+    loop {
+        let start = (k as f64 * beat).round() as usize;
+        if start >= n {
+            break;
+        }
+        let (freq, amplitude) = if k % beats_per_bar.max(1) as usize == 0 {
+            (1500.0, 1.0)
+        } else {
+            (1000.0, 0.5)
+        };
+        let mut g = amplitude;
+        for (i, o) in v.iter_mut().skip(start).take(len).enumerate() {
+            *o = (g * (TAU * freq * i as f64 / sr).sin()) as f32;
+            g *= decay;
+        }
+        k += 1;
+    }
+    Whole::from_block(sample_rate, v)
+}
+
+///Frequency of A4 in Hz of standard tuning.
+pub const A4: f32 = 440.0;
+
+///Returns frequency in Hz of MIDI note number, 69 being A4 of frequency a4.
+pub fn note_to_freq(note: f32, a4: f32) -> f32 {
+    a4 * ((note - 69.0) / 12.0).exp2()
+}
+
+///Returns fractional MIDI note number of frequency in Hz, 69 being A4 of frequency a4.
+pub fn freq_to_note(freq: f32, a4: f32) -> f32 {
+    69.0 + 12.0 * (freq / a4).log2()
+}
+
+///A musical pitch as MIDI note number, parsed from names such as "C4", "F#3" or "Bb-1".
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct Note(pub f32);
+
+impl Note {
+    ///Returns frequency in Hz of standard tuning.
+    pub fn freq(self) -> f32 {
+        note_to_freq(self.0, A4)
+    }
+
+    ///Returns name of the nearest note with sharps, such as "C#4".
+    pub fn name(self) -> String {
+        const NAMES: [&str; 12] = [
+            "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
+        ];
+        let n = self.0.round() as i32;
+        format!(
+            "{}{}",
+            NAMES[n.rem_euclid(12) as usize],
+            n.div_euclid(12) - 1
+        )
+    }
+}
+
+impl From<u8> for Note {
+    fn from(o: u8) -> Self {
+        Self(o as f32)
+    }
+}
+
+///Error of parsing note name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseNoteError;
+
+impl std::fmt::Display for ParseNoteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("invalid note name")
+    }
+}
+
+impl std::error::Error for ParseNoteError {}
+
+impl std::str::FromStr for Note {
+    type Err = ParseNoteError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut c = s.trim().chars();
+        let mut n: i32 = match c.next().map(|o| o.to_ascii_uppercase()) {
+            Some('C') => 0,
+            Some('D') => 2,
+            Some('E') => 4,
+            Some('F') => 5,
+            Some('G') => 7,
+            Some('A') => 9,
+            Some('B') => 11,
+            _ => return Err(ParseNoteError),
+        };
+        let mut rest = c.as_str();
+        loop {
+            if let Some(o) = rest.strip_prefix('#') {
+                n += 1;
+                rest = o;
+            } else if let Some(o) = rest.strip_prefix('b') {
+                n -= 1;
+                rest = o;
+            } else {
+                break;
+            }
+        }
+        let octave: i32 = rest.parse().map_err(|_| ParseNoteError)?;
+        Ok(Self(((octave + 1) * 12 + n) as f32))
+    }
+}
+
+///Returns a sine wave whole of note in standard tuning.
+pub fn sine_note(sample_rate: SampleRate, note: Note, duration: f32, amplitude: f32) -> Whole<f32> {
+    sine(sample_rate, note.freq(), duration, amplitude, 0.0)
+}
+
+///Returns a square wave whole of note in standard tuning with duty 0.5.
+pub fn square_note(
+    sample_rate: SampleRate,
+    note: Note,
+    duration: f32,
+    amplitude: f32,
+) -> Whole<f32> {
+    square(sample_rate, note.freq(), duration, amplitude, 0.0, 0.5)
+}
+
+///Returns a sawtooth wave whole of note in standard tuning.
+pub fn saw_note(sample_rate: SampleRate, note: Note, duration: f32, amplitude: f32) -> Whole<f32> {
+    saw(sample_rate, note.freq(), duration, amplitude, 0.0)
+}
+
+///Returns a triangle wave whole of note in standard tuning.
+pub fn triangle_note(
+    sample_rate: SampleRate,
+    note: Note,
+    duration: f32,
+    amplitude: f32,
+) -> Whole<f32> {
+    triangle(sample_rate, note.freq(), duration, amplitude, 0.0)
+}
+
+///Returns DTMF tones of digits, each of tone and gap length in milliseconds with each sine of amplitude. Characters other than DTMF digits render as a silent tone length.
+pub fn dtmf(
+    sample_rate: SampleRate,
+    digits: &str,
+    tone: f32,
+    gap: f32,
+    amplitude: f32,
+) -> Whole<f32> {
+    let tone = ms_to_frames(tone, sample_rate.get());
+    let gap = ms_to_frames(gap, sample_rate.get());
+    let sr = sample_rate.get() as f64;
+    let mut v = Block::new(digits.chars().count() * (tone + gap));
+    for d in digits.chars() {
+        match crate::dtmf::dtmf_freqs(d) {
+            Some((r, c)) => v.extend((0..tone).map(|i| {
+                let t = i as f64 / sr;
+                let s = (TAU * r as f64 * t).sin() + (TAU * c as f64 * t).sin();
+                amplitude * s as f32
+            })),
+            None => v.extend(std::iter::repeat_n(0.0, tone)),
+        }
+        v.extend(std::iter::repeat_n(0.0, gap));
+    }
+    Whole::from_block(sample_rate, v)
+}
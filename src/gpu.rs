@@ -0,0 +1,220 @@
+//!Resampling on a compute shader. Only resampling is offloaded: sample format encoding stays on the CPU, and each whole is a dispatch of its own, so a batch of many wholes makes a round trip for each.
+
+use crate::resample::Kernel;
+use std::sync::OnceLock;
+use wgpu::util::DeviceExt;
+
+const SHADER: &str = r#"
+struct Params {
+    in_len: u32,
+    out_len: u32,
+    channels: u32,
+    l: u32,
+    m: u32,
+    span: i32,
+    cutoff: f32,
+    half: f32,
+    row: u32,
+    pad0: u32,
+    pad1: u32,
+    pad2: u32,
+}
+
+@group(0) @binding(0) var<uniform> p: Params;
+@group(0) @binding(1) var<storage, read> x: array<f32>;
+@group(0) @binding(2) var<storage, read_write> y: array<f32>;
+
+const PI: f32 = 3.14159265358979;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let g = id.y * p.row + id.x;
+    if (g >= p.out_len * p.channels) {
+        return;
+    }
+    let c = g / p.out_len;
+    let i = g % p.out_len;
+    let q = i / p.m;
+    let r = i % p.m;
+    let base = i32(q * p.l + (r * p.l) / p.m);
+    let frac = f32((r * p.l) % p.m) / f32(p.m);
+    var acc = 0.0;
+    for (var k = 1 - p.span; k <= p.span; k++) {
+        let j = base + k;
+        let d = frac - f32(k);
+        if (j < 0 || j >= i32(p.in_len) || abs(d) >= p.half) {
+            continue;
+        }
+        let a = p.cutoff * d;
+        var s = 1.0;
+        if (abs(a) > 1e-6) {
+            s = sin(PI * a) / (PI * a);
+        }
+        let w = 0.5 + 0.5 * cos(PI * d / p.half);
+        acc += x[c * p.in_len + u32(j)] * p.cutoff * s * w;
+    }
+    y[c * p.out_len + i] = acc;
+}
+"#;
+
+struct Gpu {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+}
+
+fn init() -> Option<Gpu> {
+    let instance = wgpu::Instance::default();
+    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference: wgpu::PowerPreference::HighPerformance,
+        ..Default::default()
+    }))?;
+    if !adapter
+        .get_downlevel_capabilities()
+        .flags
+        .contains(wgpu::DownlevelFlags::COMPUTE_SHADERS)
+    {
+        return None;
+    }
+    let (device, queue) = pollster::block_on(adapter.request_device(
+        &wgpu::DeviceDescriptor {
+            required_limits: adapter.limits(),
+            ..Default::default()
+        },
+        None,
+    ))
+    .ok()?;
+    let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("bssf resample"),
+        source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+    });
+    let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: Some("bssf resample"),
+        layout: None,
+        module: &module,
+        entry_point: Some("main"),
+        compilation_options: Default::default(),
+        cache: None,
+    });
+    Some(Gpu {
+        device,
+        queue,
+        pipeline,
+    })
+}
+
+///Resamples planar channels of equal length to frames on a compute shader. Returns None without an adapter or beyond its limits.
+pub(crate) fn resample(x: &[Vec<f32>], k: &Kernel, frames: usize) -> Option<Vec<Vec<f32>>> {
+    static GPU: OnceLock<Option<Gpu>> = OnceLock::new();
+    let gpu = GPU.get_or_init(init).as_ref()?;
+    let limits = gpu.device.limits();
+    let channels = x.len();
+    let in_len = x.first()?.len();
+    let (in_size, out_size) = (
+        (in_len * channels * 4) as u64,
+        (frames * channels * 4) as u64,
+    );
+    let max = (limits.max_storage_buffer_binding_size as u64).min(limits.max_buffer_size);
+    //positions are computed in u32.
+    if in_size > max
+        || out_size > max
+        || k.l * k.m > u32::MAX as u64
+        || in_size == 0
+        || out_size == 0
+    {
+        return None;
+    }
+    let groups = (frames * channels).div_ceil(64) as u32;
+    let gx = groups.min(limits.max_compute_workgroups_per_dimension);
+    let gy = groups.div_ceil(gx);
+    if gy > limits.max_compute_workgroups_per_dimension {
+        return None;
+    }
+    let params: Vec<u8> = [
+        in_len as u32,
+        frames as u32,
+        channels as u32,
+        k.l as u32,
+        k.m as u32,
+        k.span as i32 as u32,
+        (k.cutoff as f32).to_bits(),
+        (k.half as f32).to_bits(),
+        gx * 64,
+        0,
+        0,
+        0,
+    ]
+    .iter()
+    .flat_map(|o| o.to_ne_bytes())
+    .collect();
+    let input: Vec<u8> = x.iter().flatten().flat_map(|o| o.to_ne_bytes()).collect();
+    let device = &gpu.device;
+    let uniform = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: None,
+        contents: &params,
+        usage: wgpu::BufferUsages::UNIFORM,
+    });
+    let input = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: None,
+        contents: &input,
+        usage: wgpu::BufferUsages::STORAGE,
+    });
+    let output = device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: out_size,
+        usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+        mapped_at_creation: false,
+    });
+    let staging = device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: out_size,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let bind = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: None,
+        layout: &gpu.pipeline.get_bind_group_layout(0),
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: input.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: output.as_entire_binding(),
+            },
+        ],
+    });
+    let mut encoder = device.create_command_encoder(&Default::default());
+    {
+        let mut pass = encoder.begin_compute_pass(&Default::default());
+        pass.set_pipeline(&gpu.pipeline);
+        pass.set_bind_group(0, &bind, &[]);
+        pass.dispatch_workgroups(gx, gy, 1);
+    }
+    encoder.copy_buffer_to_buffer(&output, 0, &staging, 0, out_size);
+    gpu.queue.submit([encoder.finish()]);
+    let (tx, rx) = std::sync::mpsc::channel();
+    let slice = staging.slice(..);
+    slice.map_async(wgpu::MapMode::Read, move |r| {
+        let _ = tx.send(r);
+    });
+    device.poll(wgpu::Maintain::Wait);
+    rx.recv().ok()?.ok()?;
+    let data = slice.get_mapped_range();
+    let y: Vec<Vec<f32>> = data
+        .chunks_exact(frames * 4)
+        .map(|c| {
+            c.chunks_exact(4)
+                .map(|b| f32::from_ne_bytes([b[0], b[1], b[2], b[3]]))
+                .collect()
+        })
+        .collect();
+    drop(data);
+    staging.unmap();
+    Some(y)
+}
@@ -0,0 +1,396 @@
+//!A graph of nodes connected by edges carrying blocks, rendered in topological order.
+
+use crate::dynamics::*;
+use crate::effect::*;
+use crate::format::*;
+use crate::sample::*;
+use crate::units::*;
+
+///A node of graph with input and output ports.
+pub trait Node<T: Frame> {
+    ///Returns number of input ports.
+    fn inputs(&self) -> usize;
+
+    ///Returns number of output ports.
+    fn outputs(&self) -> usize;
+
+    ///Renders one block. Each input holds the sum of its edges, each output is zeroed, all of the same frames.
+    fn render(&mut self, inputs: &[Block<T>], outputs: &mut [Block<T>]);
+
+    ///Clears the internal state.
+    fn reset(&mut self) {}
+}
+
+///Index of node in graph.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NodeId(usize);
+
+///Error of connecting graph.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GraphError {
+    ///Node is not in graph.
+    InvalidNode,
+    ///Port is out of range of node.
+    InvalidPort,
+    ///Edge would make a cycle.
+    Cycle,
+}
+
+impl std::fmt::Display for GraphError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::InvalidNode => "invalid node",
+            Self::InvalidPort => "invalid port",
+            Self::Cycle => "edge would make a cycle",
+        })
+    }
+}
+
+impl std::error::Error for GraphError {}
+
+#[derive(Clone, Copy, Debug)]
+struct Edge {
+    from: usize,
+    output: usize,
+    to: usize,
+    input: usize,
+    gain: f32,
+}
+
+fn mix<T: Frame>(o: &mut Block<T>, x: &Block<T>, gain: f32) {
+    for (a, b) in o.iter_mut().zip(x.iter()) {
+        for n in 0..T::CHANNEL_SIZE as usize {
+            a.set_channel(n, a.channel(n) + gain * b.channel(n));
+        }
+    }
+}
+
+fn zero<T: Frame>(o: &mut Block<T>, frames: usize) {
+    o.clear();
+    o.resize(frames, T::default());
+}
+
+///A directed acyclic graph of nodes.
+pub struct Graph<T> {
+    nodes: Vec<Box<dyn Node<T>>>,
+    edges: Vec<Edge>,
+    order: Vec<usize>,
+    inputs: Vec<Vec<Block<T>>>,
+    outputs: Vec<Vec<Block<T>>>,
+    output: Option<usize>,
+}
+
+impl<T> std::fmt::Debug for Graph<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("Graph");
+        f.field("nodes", &self.nodes.len())
+            .field("edges", &self.edges)
+            .field("order", &self.order)
+            .field("output", &self.output)
+            .finish()
+    }
+}
+
+impl<T> Default for Graph<T>
+where
+    T: Frame,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Graph<T>
+where
+    T: Frame,
+{
+    ///Constructs a new, empty Graph.
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            order: Vec::new(),
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            output: None,
+        }
+    }
+
+    ///Adds a node.
+    pub fn add(&mut self, node: impl Node<T> + 'static) -> NodeId {
+        let n = self.nodes.len();
+        self.inputs
+            .push((0..node.inputs()).map(|_| Block::new(0)).collect());
+        self.outputs
+            .push((0..node.outputs()).map(|_| Block::new(0)).collect());
+        self.nodes.push(Box::new(node));
+        self.order.push(n);
+        NodeId(n)
+    }
+
+    ///Connects output port of a node to input port of another with gain. Multiple edges into a port are summed.
+    pub fn connect(
+        &mut self,
+        from: NodeId,
+        output: usize,
+        to: NodeId,
+        input: usize,
+        gain: f32,
+    ) -> Result<(), GraphError> {
+        let (Some(a), Some(b)) = (self.nodes.get(from.0), self.nodes.get(to.0)) else {
+            return Err(GraphError::InvalidNode);
+        };
+        if output >= a.outputs() || input >= b.inputs() {
+            return Err(GraphError::InvalidPort);
+        }
+        self.edges.push(Edge {
+            from: from.0,
+            output,
+            to: to.0,
+            input,
+            gain,
+        });
+        match self.sort() {
+            Some(o) => {
+                self.order = o;
+                Ok(())
+            }
+            None => {
+                self.edges.pop();
+                Err(GraphError::Cycle)
+            }
+        }
+    }
+
+    ///Removes all edges between two nodes.
+    pub fn disconnect(&mut self, from: NodeId, to: NodeId) {
+        self.edges.retain(|e| e.from != from.0 || e.to != to.0);
+    }
+
+    ///Sets the node whose first output is collected by render_whole.
+    pub fn set_output(&mut self, node: NodeId) {
+        self.output = Some(node.0);
+    }
+
+    ///Clears the internal state of all nodes.
+    pub fn reset(&mut self) {
+        for i in self.nodes.iter_mut() {
+            i.reset();
+        }
+    }
+
+    //Kahn's algorithm, None if there is a cycle.
+    fn sort(&self) -> Option<Vec<usize>> {
+        let mut degree = vec![0usize; self.nodes.len()];
+        for e in self.edges.iter() {
+            degree[e.to] += 1;
+        }
+        let mut ready: Vec<usize> = (0..self.nodes.len()).filter(|n| degree[*n] == 0).collect();
+        ready.reverse();
+        let mut v = Vec::with_capacity(self.nodes.len());
+        while let Some(n) = ready.pop() {
+            v.push(n);
+            for e in self.edges.iter().filter(|e| e.from == n) {
+                degree[e.to] -= 1;
+                if degree[e.to] == 0 {
+                    ready.push(e.to);
+                }
+            }
+        }
+        (v.len() == self.nodes.len()).then_some(v)
+    }
+
+    ///Renders all nodes for frames in topological order.
+    pub fn render(&mut self, frames: usize) {
+        for n in self.order.iter().copied() {
+            for i in self.inputs[n].iter_mut() {
+                zero(i, frames);
+            }
+            for e in self.edges.iter().filter(|e| e.to == n) {
+                let (a, b) = (
+                    &mut self.inputs[n][e.input],
+                    &self.outputs[e.from][e.output],
+                );
+                mix(a, b, e.gain);
+            }
+            for o in self.outputs[n].iter_mut() {
+                zero(o, frames);
+            }
+            self.nodes[n].render(&self.inputs[n], &mut self.outputs[n]);
+        }
+    }
+
+    ///Returns output port of node rendered last.
+    pub fn output(&self, node: NodeId, port: usize) -> Option<&Block<T>> {
+        self.outputs.get(node.0).and_then(|o| o.get(port))
+    }
+
+    ///Resets and renders frames in blocks of block frames, collecting the first output of the output node.
+    pub fn render_whole(
+        &mut self,
+        sample_rate: SampleRate,
+        frames: usize,
+        block: usize,
+    ) -> Whole<T> {
+        self.reset();
+        let mut w = Whole::new(sample_rate, frames);
+        let block = block.max(1);
+        let mut i = 0;
+        while i < frames {
+            let n = block.min(frames - i);
+            self.render(n);
+            match self.output.and_then(|o| self.output(NodeId(o), 0)) {
+                Some(o) => w.extend_from_slice(o),
+                None => w.extend(std::iter::repeat_n(T::default(), n)),
+            }
+            i += n;
+        }
+        w
+    }
+}
+
+///A source node playing a whole from the start, silent after its end.
+pub struct Player<T> {
+    data: Whole<T>,
+    index: usize,
+}
+
+impl<T> std::fmt::Debug for Player<T>
+where
+    T: Sample,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("Player");
+        f.field("data", &self.data)
+            .field("index", &self.index)
+            .finish()
+    }
+}
+
+impl<T> Player<T>
+where
+    T: Frame,
+{
+    ///Constructs a new Player.
+    pub fn new(data: Whole<T>) -> Self {
+        Self { data, index: 0 }
+    }
+}
+
+impl<T> Node<T> for Player<T>
+where
+    T: Frame,
+{
+    fn inputs(&self) -> usize {
+        0
+    }
+
+    fn outputs(&self) -> usize {
+        1
+    }
+
+    fn render(&mut self, _: &[Block<T>], outputs: &mut [Block<T>]) {
+        for o in outputs[0].iter_mut() {
+            if let Some(i) = self.data.get(self.index) {
+                *o = *i;
+                self.index += 1;
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.index = 0;
+    }
+}
+
+///A node of an effect of one input and one output.
+#[derive(Debug)]
+pub struct EffectNode<E>(pub E);
+
+impl<T, E> Node<T> for EffectNode<E>
+where
+    T: Frame,
+    E: Effect<T>,
+{
+    fn inputs(&self) -> usize {
+        1
+    }
+
+    fn outputs(&self) -> usize {
+        1
+    }
+
+    fn render(&mut self, inputs: &[Block<T>], outputs: &mut [Block<T>]) {
+        outputs[0].copy_from_slice(&inputs[0]);
+        self.0.process(&mut outputs[0]);
+    }
+
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+}
+
+///A node summing inputs with gains into one output.
+#[derive(Clone, Debug)]
+pub struct Mixer {
+    gains: Vec<f32>,
+}
+
+impl Mixer {
+    ///Constructs a new Mixer of an input for each gain.
+    pub fn new(gains: Vec<f32>) -> Self {
+        Self { gains }
+    }
+
+    ///Sets gain of input. Does nothing if out of range.
+    pub fn set_gain(&mut self, input: usize, gain: f32) {
+        if let Some(o) = self.gains.get_mut(input) {
+            *o = gain;
+        }
+    }
+}
+
+impl<T> Node<T> for Mixer
+where
+    T: Frame,
+{
+    fn inputs(&self) -> usize {
+        self.gains.len()
+    }
+
+    fn outputs(&self) -> usize {
+        1
+    }
+
+    fn render(&mut self, inputs: &[Block<T>], outputs: &mut [Block<T>]) {
+        for (i, g) in inputs.iter().zip(self.gains.iter()) {
+            mix(&mut outputs[0], i, *g);
+        }
+    }
+}
+
+///A node of a compressor detecting level from its second input.
+#[derive(Debug)]
+pub struct SidechainNode(pub Compressor);
+
+impl<T> Node<T> for SidechainNode
+where
+    T: Frame,
+{
+    fn inputs(&self) -> usize {
+        2
+    }
+
+    fn outputs(&self) -> usize {
+        1
+    }
+
+    fn render(&mut self, inputs: &[Block<T>], outputs: &mut [Block<T>]) {
+        outputs[0].copy_from_slice(&inputs[0]);
+        self.0.process_sidechain(&mut outputs[0], &inputs[1]);
+    }
+
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+}
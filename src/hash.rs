@@ -0,0 +1,153 @@
+use crate::error::*;
+use crate::format::*;
+use crate::sample::*;
+use crate::swap::*;
+
+//64-bit FNV-1a, stable across platforms and versions.
+struct Fnv(u64);
+
+impl Fnv {
+    fn new() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 ^= *b as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    //writes format and data as little-endian, swapping each scalar of width unless already so.
+    fn write_data(
+        &mut self,
+        kind: u8,
+        channel_size: u16,
+        byte_size: usize,
+        big_endian: bool,
+        data: &[u8],
+    ) {
+        self.write(&[kind]);
+        self.write(&channel_size.to_le_bytes());
+        self.write(&(byte_size as u64).to_le_bytes());
+        self.write(&(data.len() as u64).to_le_bytes());
+        if !big_endian {
+            return self.write(data);
+        }
+        let width = (byte_size / channel_size.max(1) as usize).max(1);
+        let mut buf = [0u8; 4096];
+        let n = buf.len() / width * width;
+        for o in data.chunks(n) {
+            let b = &mut buf[..o.len()];
+            b.copy_from_slice(o);
+            swap_bytes(b, width);
+            self.write(b);
+        }
+    }
+}
+
+//table of CRC-32 of the reflected IEEE polynomial.
+const CRC_TABLE: [u32; 256] = {
+    let mut t = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut c = i as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 != 0 {
+                0xedb88320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            k += 1;
+        }
+        t[i] = c;
+        i += 1;
+    }
+    t
+};
+
+///Incremental CRC-32 (IEEE) as used by zip and PNG.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Crc32(u32);
+
+impl Crc32 {
+    pub fn new() -> Self {
+        Self(!0)
+    }
+
+    pub fn update(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 = CRC_TABLE[((self.0 ^ *b as u32) & 0xff) as usize] ^ (self.0 >> 8);
+        }
+    }
+
+    pub fn finish(self) -> u32 {
+        !self.0
+    }
+}
+
+block_impl! {
+    impl<T> Block<T>
+    where
+        T: Channels,
+    {
+        ///Returns a stable 64-bit FNV-1a digest of sample kind, channel size, byte size and samples in little-endian byte order, equal to that of ByteBlock of the same content read as the same scalar.
+        pub fn content_hash(&self) -> u64 {
+            let mut h = Fnv::new();
+            h.write_data(
+                T::Scalar::KIND,
+                T::CHANNEL_SIZE,
+                T::BYTE_SIZE,
+                cfg!(target_endian = "big"),
+                self.bytes_slice(),
+            );
+            h.0
+        }
+    }
+}
+
+impl<T> Whole<T>
+where
+    T: Channels,
+{
+    ///Returns a stable 64-bit FNV-1a digest of sample rate and the content hash of frames. Markers are not included.
+    pub fn content_hash(&self) -> u64 {
+        let mut h = Fnv::new();
+        h.write(&self.sample_rate().get().to_le_bytes());
+        h.write(&Block::content_hash(self).to_le_bytes());
+        h.0
+    }
+}
+
+impl ByteBlock {
+    ///Returns CRC-32 (IEEE) of data bytes as stored.
+    pub fn crc32(&self) -> u32 {
+        let mut c = Crc32::new();
+        c.update(self);
+        c.finish()
+    }
+
+    ///Checks data bytes against expected CRC-32, failing with Error::ChecksumMismatch.
+    pub fn verify(&self, expected: u32) -> Result<(), Error> {
+        let found = self.crc32();
+        if found == expected {
+            Ok(())
+        } else {
+            Err(Error::ChecksumMismatch { expected, found })
+        }
+    }
+
+    ///Returns a stable 64-bit FNV-1a digest of the kind of S, channel size, byte size and data normalized to little-endian, equal to that of Block of the same content whose scalar is S.
+    pub fn content_hash<S: Scalar>(&self) -> u64 {
+        let mut h = Fnv::new();
+        h.write_data(
+            S::KIND,
+            self.channel_size(),
+            self.byte_size(),
+            self.is_big_endian(),
+            self,
+        );
+        h.0
+    }
+}
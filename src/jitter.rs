@@ -0,0 +1,181 @@
+use crate::format::*;
+use std::collections::BTreeMap;
+
+///Concealment of a late or lost packet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Concealment {
+    ///Zero bytes.
+    Silence,
+    ///The last played packet.
+    Repeat,
+}
+
+///Counters of a jitter buffer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct JitterStats {
+    ///Packets accepted.
+    pub received: usize,
+    ///Packets arriving after their playout, dropped.
+    pub late: usize,
+    ///Packets already buffered, dropped.
+    pub duplicates: usize,
+    ///Packets dropped by overflow.
+    pub overflow: usize,
+    ///Packets missing at playout and concealed.
+    pub lost: usize,
+}
+
+///A jitter buffer reordering sequence-numbered packets and emitting blocks of fixed frames.
+///
+///Sequence numbers are 16 bits and wrap as in RTP.
+pub struct JitterBuffer {
+    channel_size: u16,
+    byte_size: usize,
+    big_endian: bool,
+    frames: usize,
+    depth: usize,
+    capacity: usize,
+    concealment: Concealment,
+    packets: BTreeMap<u64, ByteBlock>,
+    next: Option<u64>,
+    playing: bool,
+    last: Vec<u8>,
+    stats: JitterStats,
+}
+
+impl std::fmt::Debug for JitterBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("JitterBuffer");
+        f.field("channel_size", &self.channel_size)
+            .field("byte_size", &self.byte_size)
+            .field("frames", &self.frames)
+            .field("depth", &self.depth)
+            .field("buffered", &self.packets.len())
+            .field("stats", &self.stats)
+            .finish()
+    }
+}
+
+impl JitterBuffer {
+    ///Constructs a new JitterBuffer of packets of frames in the byte format, which starts playout once depth packets are buffered and holds at most capacity.
+    pub fn new(
+        channel_size: u16,
+        byte_size: usize,
+        big_endian: bool,
+        frames: usize,
+        depth: usize,
+        capacity: usize,
+    ) -> Self {
+        let depth = depth.max(1);
+        Self {
+            channel_size,
+            byte_size,
+            big_endian,
+            frames,
+            depth,
+            capacity: capacity.max(depth),
+            concealment: Concealment::Silence,
+            packets: BTreeMap::new(),
+            next: None,
+            playing: false,
+            last: vec![0; frames * byte_size],
+            stats: JitterStats::default(),
+        }
+    }
+
+    ///Sets concealment, Silence by default.
+    pub fn set_concealment(&mut self, concealment: Concealment) {
+        self.concealment = concealment;
+    }
+
+    ///Returns counters.
+    pub fn stats(&self) -> JitterStats {
+        self.stats
+    }
+
+    ///Returns number of buffered packets.
+    pub fn len(&self) -> usize {
+        self.packets.len()
+    }
+
+    ///Returns true if no packet is buffered.
+    pub fn is_empty(&self) -> bool {
+        self.packets.is_empty()
+    }
+
+    ///Clears packets and restarts buffering, keeping counters.
+    pub fn reset(&mut self) {
+        self.packets.clear();
+        self.next = None;
+        self.playing = false;
+        self.last.fill(0);
+    }
+
+    //extends a 16 bit sequence number to the one nearest reference.
+    fn extend(seq: u16, reference: u64) -> u64 {
+        let d = seq.wrapping_sub(reference as u16) as i16 as i64;
+        (reference as i64 + d).max(0) as u64
+    }
+
+    ///Accepts a packet. Packets of another size are zero padded or truncated to frames.
+    pub fn push(&mut self, seq: u16, mut packet: ByteBlock) {
+        //offset keeps extended numbers positive after wrapping backwards.
+        let reference = self
+            .next
+            .or_else(|| self.packets.keys().next().copied())
+            .unwrap_or(1 << 32);
+        let seq = Self::extend(seq, reference);
+        if self.next.is_some_and(|n| seq < n) {
+            self.stats.late += 1;
+            return;
+        }
+        if self.packets.contains_key(&seq) {
+            self.stats.duplicates += 1;
+            return;
+        }
+        packet.resize(self.frames * self.byte_size, 0);
+        self.packets.insert(seq, packet);
+        self.stats.received += 1;
+        while self.packets.len() > self.capacity {
+            if let Some((k, _)) = self.packets.pop_first() {
+                self.stats.overflow += 1;
+                if self.next.is_some_and(|n| n <= k) {
+                    self.next = Some(k + 1);
+                }
+            }
+        }
+    }
+
+    ///Returns the next block of frames, silence while buffering.
+    pub fn pop(&mut self) -> ByteBlock {
+        if !self.playing {
+            if self.packets.len() < self.depth {
+                return self.block(vec![0; self.frames * self.byte_size]);
+            }
+            self.playing = true;
+        }
+        let next = self
+            .next
+            .or_else(|| self.packets.keys().next().copied())
+            .unwrap_or_default();
+        self.next = Some(next + 1);
+        match self.packets.remove(&next) {
+            Some(o) => {
+                self.last.copy_from_slice(&o);
+                o
+            }
+            None => {
+                self.stats.lost += 1;
+                let data = match self.concealment {
+                    Concealment::Silence => vec![0; self.frames * self.byte_size],
+                    Concealment::Repeat => self.last.clone(),
+                };
+                self.block(data)
+            }
+        }
+    }
+
+    fn block(&self, data: Vec<u8>) -> ByteBlock {
+        ByteBlock::new(self.channel_size, self.byte_size, self.big_endian, data)
+    }
+}
@@ -1,8 +1,124 @@
 //!A library for basic sound sample format.
 //!
 
+#![cfg_attr(feature = "allocator-api", feature(allocator_api))]
+
+#[cfg(feature = "async")]
+pub mod aio;
+mod align;
+mod aligned;
+mod analysis;
+mod batch;
+pub mod biquad;
+mod cancel;
+mod chunked;
+mod compare;
+mod convert;
+mod crossover;
+mod cursor;
+mod db;
+mod delay;
+mod denoise;
+mod dither;
+mod dsd;
+mod dtmf;
+mod dynamics;
+mod dynblock;
+mod edit;
+mod effect;
+mod envelope;
+mod eq;
+mod error;
+mod fft;
+pub mod fir;
 mod format;
+mod framing;
+pub mod gen;
+#[cfg(feature = "gpu")]
+mod gpu;
+pub mod graph;
+mod hash;
+mod jitter;
+mod lofi;
+mod loops;
+pub mod loudness;
+mod meter;
+#[cfg(feature = "memmap")]
+pub mod mmap;
+mod modulation;
+mod onset;
+mod overview;
+mod pack;
+#[cfg(feature = "rayon")]
+mod par;
+pub mod pipeline;
+mod pitch;
+mod resample;
+mod reverb;
+#[cfg(feature = "assert-no-alloc")]
+pub mod rt;
+pub mod rtp;
 mod sample;
+mod shared;
+#[cfg(feature = "rustfft")]
+pub mod spectrum;
+mod spill;
+pub mod spsc;
+pub mod stft;
+pub mod stream;
+mod stretch;
+mod swap;
+mod timeline;
+mod transcode;
+mod units;
+mod util;
+pub mod wav;
+mod window;
 
+pub use align::*;
+pub use aligned::*;
+pub use analysis::*;
+pub use batch::*;
+pub use cancel::*;
+pub use chunked::*;
+pub use compare::*;
+pub use convert::*;
+pub use crossover::*;
+pub use cursor::*;
+pub use db::*;
+pub use delay::*;
+pub use denoise::*;
+pub use dither::*;
+pub use dsd::*;
+pub use dtmf::*;
+pub use dynamics::*;
+pub use dynblock::*;
+pub use edit::*;
+pub use effect::*;
+pub use envelope::*;
+pub use eq::*;
+pub use error::*;
+pub use fft::Complex;
 pub use format::*;
+pub use framing::*;
+pub use jitter::*;
+pub use lofi::*;
+pub use loops::*;
+pub use meter::*;
+pub use modulation::*;
+pub use onset::*;
+pub use overview::*;
+pub use pack::*;
+#[cfg(feature = "rayon")]
+pub use par::*;
+pub use pitch::*;
+pub use resample::{resample, resample_with};
+pub use reverb::*;
 pub use sample::*;
+pub use shared::*;
+pub use spill::*;
+pub use stretch::*;
+pub use timeline::*;
+pub use transcode::*;
+pub use units::*;
+pub use window::*;
@@ -0,0 +1,53 @@
+use crate::format::*;
+use crate::sample::*;
+use crate::util::*;
+
+///A lo-fi effect of bit depth quantization and sample-and-hold decimation.
+#[derive(Clone, Debug)]
+pub struct BitCrusher {
+    bits: u32,
+    factor: f32,
+    phase: f32,
+    held: Vec<f32>,
+}
+
+impl BitCrusher {
+    ///Constructs a new BitCrusher. Factor is the decimation ratio, 1 keeps every frame.
+    pub fn new(bits: u32, factor: f32) -> Self {
+        Self {
+            bits: bits.clamp(1, 32),
+            factor: factor.max(1.0),
+            phase: 0.0,
+            held: Vec::new(),
+        }
+    }
+
+    ///Constructs a new BitCrusher decimating from sample rate to target rate.
+    pub fn with_rate(sample_rate: u32, bits: u32, target_rate: f32) -> Self {
+        Self::new(bits, sample_rate as f32 / target_rate)
+    }
+
+    ///Clears the internal state.
+    pub fn reset(&mut self) {
+        self.phase = 0.0;
+        self.held.clear();
+    }
+
+    ///Processes block in place.
+    pub fn process<T: Frame>(&mut self, o: &mut Block<T>) {
+        let c = T::CHANNEL_SIZE as usize;
+        for i in o.iter_mut() {
+            if self.held.len() != c || self.phase < 1.0 {
+                self.held.clear();
+                for n in 0..c {
+                    self.held.push(quantize(i.channel(n), self.bits));
+                }
+                self.phase += self.factor;
+            }
+            for (n, v) in self.held.iter().enumerate() {
+                i.set_channel(n, *v);
+            }
+            self.phase -= 1.0;
+        }
+    }
+}
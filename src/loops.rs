@@ -0,0 +1,66 @@
+use crate::format::*;
+use crate::sample::*;
+use std::ops::Range;
+
+///Returns the frames of loop range, clamped, as a loop whose end is crossfaded linearly into the frames leading up to its start, so it repeats without a click. Crossfade frames are clamped to the frames available before the start and to the loop.
+pub fn make_loop<T: Frame>(o: &Whole<T>, loop_range: Range<usize>, crossfade: usize) -> Whole<T> {
+    let end = loop_range.end.min(o.len());
+    let start = loop_range.start.min(end);
+    let n = end - start;
+    let c = crossfade.min(start).min(n);
+    let mut v = Block::from(o[start..end].to_vec());
+    for i in 0..c {
+        let b = (i as f32 + 1.0) / (c as f32 + 1.0);
+        let a = 1.0 - b;
+        let x = &mut v[n - c + i];
+        let y = &o[start - c + i];
+        for k in 0..T::CHANNEL_SIZE as usize {
+            x.set_channel(k, x.channel(k) * a + y.channel(k) * b);
+        }
+    }
+    Whole::from_block(o.sample_rate(), v)
+}
+
+///Continuity of a loop where its last frame joins its first, the maximum of all channels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LoopJoin {
+    ///Absolute step of level across the join.
+    pub level: f32,
+    ///Absolute change of slope across the join.
+    pub slope: f32,
+    ///Maximum absolute step of level between frames inside the loop, for reference.
+    pub body_level: f32,
+}
+
+impl LoopJoin {
+    ///Returns true if the join steps no more than tolerance times the largest step inside the loop, and the slope changes no more than the largest step.
+    pub fn is_seamless(&self, tolerance: f32) -> bool {
+        self.level <= self.body_level * tolerance && self.slope <= self.body_level
+    }
+}
+
+///Measures continuity of level and slope where the last frame of loop joins its first.
+pub fn loop_join<T: Frame>(o: &[T]) -> LoopJoin {
+    let mut j = LoopJoin {
+        level: 0.0,
+        slope: 0.0,
+        body_level: 0.0,
+    };
+    let n = o.len();
+    if n < 2 {
+        return j;
+    }
+    for k in 0..T::CHANNEL_SIZE as usize {
+        let at = |i: usize| o[i].channel(k);
+        let step = at(0) - at(n - 1);
+        j.level = j.level.max(step.abs());
+        if n >= 3 {
+            let (before, after) = (at(n - 1) - at(n - 2), at(1) - at(0));
+            j.slope = j.slope.max((step - before).abs().max((after - step).abs()));
+        }
+        for i in 1..n {
+            j.body_level = j.body_level.max((at(i) - at(i - 1)).abs());
+        }
+    }
+    j
+}
@@ -0,0 +1,293 @@
+//!Loudness measurement of ITU-R BS.1770 and EBU R128.
+
+use crate::biquad::*;
+use crate::cancel::*;
+use crate::db::*;
+use crate::format::*;
+use crate::sample::*;
+use crate::util::*;
+use std::f64::consts::PI;
+
+const ABSOLUTE_GATE: f64 = -70.0;
+const RELATIVE_GATE: f64 = -10.0;
+
+fn shelf(sample_rate: u32) -> Coefficients {
+    let (f0, g, q) = (1681.974450955533, 3.999843853973347, 0.7071752369554196);
+    let k = (PI * f0 / sample_rate as f64).tan();
+    let vh = 10f64.powf(g / 20.0);
+    let vb = vh.powf(0.4996667741545416);
+    let a0 = 1.0 + k / q + k * k;
+    Coefficients {
+        b0: (vh + vb * k / q + k * k) / a0,
+        b1: 2.0 * (k * k - vh) / a0,
+        b2: (vh - vb * k / q + k * k) / a0,
+        a1: 2.0 * (k * k - 1.0) / a0,
+        a2: (1.0 - k / q + k * k) / a0,
+    }
+}
+
+fn high_pass(sample_rate: u32) -> Coefficients {
+    let (f0, q) = (38.13547087602444, 0.5003270373238773);
+    let k = (PI * f0 / sample_rate as f64).tan();
+    let a0 = 1.0 + k / q + k * k;
+    Coefficients {
+        b0: 1.0,
+        b1: -2.0,
+        b2: 1.0,
+        a1: 2.0 * (k * k - 1.0) / a0,
+        a2: (1.0 - k / q + k * k) / a0,
+    }
+}
+
+///K-weighting pre-filter of BS.1770, a high shelf followed by a high-pass.
+#[derive(Clone, Debug)]
+pub struct KWeighting {
+    filters: [Biquad; 2],
+}
+
+impl KWeighting {
+    ///Constructs a new KWeighting.
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            filters: [
+                Biquad::new(shelf(sample_rate)),
+                Biquad::new(high_pass(sample_rate)),
+            ],
+        }
+    }
+
+    ///Returns coefficients of the shelf and the high-pass.
+    pub fn coefficients(&self) -> [Coefficients; 2] {
+        [
+            *self.filters[0].coefficients(),
+            *self.filters[1].coefficients(),
+        ]
+    }
+
+    ///Clears the internal state.
+    pub fn reset(&mut self) {
+        for f in self.filters.iter_mut() {
+            f.reset();
+        }
+    }
+
+    ///Processes one sample of channel n.
+    pub fn tick(&mut self, n: usize, x: f32) -> f32 {
+        let x = self.filters[0].tick(n, x);
+        self.filters[1].tick(n, x)
+    }
+
+    ///Processes block in place.
+    pub fn process<T: Frame>(&mut self, o: &mut Block<T>) {
+        for i in o.iter_mut() {
+            for n in 0..T::CHANNEL_SIZE as usize {
+                i.set_channel(n, self.tick(n, i.channel(n)));
+            }
+        }
+    }
+}
+
+///Returns channel weights of BS.1770. Channels are in order L, R, C, LFE, Ls, Rs for 5.1, and L, R, Ls, Rs for 4.
+pub fn channel_weights(channel_size: u16) -> Vec<f64> {
+    match channel_size {
+        4 => vec![1.0, 1.0, 1.41, 1.41],
+        5 => vec![1.0, 1.0, 1.0, 1.41, 1.41],
+        6 => vec![1.0, 1.0, 1.0, 0.0, 1.41, 1.41],
+        n => vec![1.0; n as usize],
+    }
+}
+
+fn to_lufs(z: f64) -> f32 {
+    if z > 0.0 {
+        (-0.691 + 10.0 * z.log10()) as f32
+    } else {
+        f32::NEG_INFINITY
+    }
+}
+
+///A loudness meter accumulating successive blocks.
+#[derive(Clone, Debug)]
+pub struct Meter {
+    sample_rate: u32,
+    weights: Vec<f64>,
+    filter: KWeighting,
+    step: usize,
+    sum: f64,
+    count: usize,
+    energies: Vec<f64>,
+}
+
+impl Meter {
+    ///Constructs a new Meter of channel size, using the weights of BS.1770.
+    pub fn new(sample_rate: u32, channel_size: u16) -> Self {
+        Self::with_weights(sample_rate, channel_weights(channel_size))
+    }
+
+    ///Constructs a new Meter of channel weights.
+    pub fn with_weights(sample_rate: u32, weights: Vec<f64>) -> Self {
+        Self {
+            sample_rate,
+            weights,
+            filter: KWeighting::new(sample_rate),
+            step: (sample_rate as usize / 10).max(1),
+            sum: 0.0,
+            count: 0,
+            energies: Vec::new(),
+        }
+    }
+
+    ///Returns sample rate.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    ///Clears the internal state.
+    pub fn reset(&mut self) {
+        self.filter.reset();
+        self.sum = 0.0;
+        self.count = 0;
+        self.energies.clear();
+    }
+
+    ///Measures frames.
+    pub fn process<T: Channels>(&mut self, o: &[T]) {
+        for i in o.iter() {
+            let mut e = 0.0;
+            for (n, g) in self
+                .weights
+                .iter()
+                .enumerate()
+                .take(T::CHANNEL_SIZE as usize)
+            {
+                let x = self.filter.tick(n, i.get_channel(n).to_unit() as f32);
+                e += g * (x as f64) * (x as f64);
+            }
+            self.sum += e;
+            self.count += 1;
+            if self.count == self.step {
+                self.energies.push(self.sum / self.step as f64);
+                self.sum = 0.0;
+                self.count = 0;
+            }
+        }
+    }
+
+    fn window(&self, n: usize) -> f32 {
+        if self.energies.len() < n {
+            return f32::NEG_INFINITY;
+        }
+        let s: f64 = self.energies[self.energies.len() - n..].iter().sum();
+        to_lufs(s / n as f64)
+    }
+
+    ///Returns momentary loudness of the last 400 ms in LUFS.
+    pub fn momentary(&self) -> f32 {
+        self.window(4)
+    }
+
+    ///Returns short-term loudness of the last 3 s in LUFS.
+    pub fn short_term(&self) -> f32 {
+        self.window(30)
+    }
+
+    ///Returns gated integrated loudness in LUFS.
+    pub fn integrated(&self) -> f32 {
+        let blocks: Vec<f64> = self
+            .energies
+            .windows(4)
+            .map(|o| o.iter().sum::<f64>() / 4.0)
+            .filter(|o| to_lufs(*o) as f64 > ABSOLUTE_GATE)
+            .collect();
+        if blocks.is_empty() {
+            return f32::NEG_INFINITY;
+        }
+        let mean = blocks.iter().sum::<f64>() / blocks.len() as f64;
+        let gate = to_lufs(mean) as f64 + RELATIVE_GATE;
+        let (s, n) = blocks
+            .iter()
+            .filter(|o| to_lufs(**o) as f64 > gate)
+            .fold((0.0, 0), |(s, n), o| (s + o, n + 1));
+        if n == 0 {
+            return f32::NEG_INFINITY;
+        }
+        to_lufs(s / n as f64)
+    }
+}
+
+///Returns gated integrated loudness of whole in LUFS.
+pub fn integrated<T: Channels>(o: &Whole<T>) -> f32 {
+    let mut m = Meter::new(o.sample_rate().get(), T::CHANNEL_SIZE);
+    m.process(o);
+    m.integrated()
+}
+
+///Returns gated integrated loudness like `integrated`, reporting frames scanned and total to options after each block and returning Err(Cancelled) once its cancellation is observed between blocks.
+pub fn integrated_with<T: Channels>(
+    o: &Whole<T>,
+    options: &mut RunOptions,
+) -> Result<f32, Cancelled> {
+    let mut m = Meter::new(o.sample_rate().get(), T::CHANNEL_SIZE);
+    let mut done = 0;
+    for i in o.chunks(PROGRESS_FRAMES) {
+        options.check()?;
+        m.process(i);
+        done += i.len();
+        options.report(done, o.len());
+    }
+    Ok(m.integrated())
+}
+
+///Reference loudness of ReplayGain 2.0 in LUFS.
+pub const REPLAY_GAIN_REFERENCE: f32 = -18.0;
+
+///ReplayGain 2.0 values of a track.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ReplayGain {
+    ///Gain in dB.
+    pub gain: f32,
+    ///Sample peak normalized to full scale 1.0.
+    pub peak: f32,
+}
+
+impl ReplayGain {
+    ///Returns gain as Db.
+    pub fn gain_db(&self) -> Db {
+        Db(self.gain)
+    }
+
+    ///Returns sample peak in dBFS.
+    pub fn peak_dbfs(&self) -> Db {
+        Db::from_gain(self.peak)
+    }
+
+    ///Returns value of REPLAYGAIN_TRACK_GAIN tag.
+    pub fn gain_tag(&self) -> String {
+        format!("{:.2} dB", self.gain)
+    }
+
+    ///Returns value of REPLAYGAIN_TRACK_PEAK tag.
+    pub fn peak_tag(&self) -> String {
+        format!("{:.6}", self.peak)
+    }
+
+    ///Returns track tags of names and values.
+    pub fn tags(&self) -> [(&'static str, String); 2] {
+        [
+            ("REPLAYGAIN_TRACK_GAIN", self.gain_tag()),
+            ("REPLAYGAIN_TRACK_PEAK", self.peak_tag()),
+        ]
+    }
+}
+
+///Returns ReplayGain 2.0 of whole. Gain is zero if whole is silent.
+pub fn replay_gain<T: Channels>(o: &Whole<T>) -> ReplayGain {
+    let l = integrated(o);
+    ReplayGain {
+        gain: if l.is_finite() {
+            REPLAY_GAIN_REFERENCE - l
+        } else {
+            0.0
+        },
+        peak: o.peak().into_iter().fold(0.0, f32::max),
+    }
+}
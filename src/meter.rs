@@ -0,0 +1,157 @@
+use crate::db::*;
+use crate::sample::*;
+
+///Standard ballistics of a level meter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ballistics {
+    ///VU meter of IEC 60268-17, rectified average rising to 99% of a steady tone in 300 ms, read in VU.
+    Vu,
+    ///Quasi-peak meter of IEC 60268-10 Type I (DIN), 5 ms integration and 20 dB return in 1.5 s, read in dB.
+    PpmType1,
+    ///Quasi-peak meter of IEC 60268-10 Type II (BBC and EBU), 10 ms integration and 24 dB return in 2.8 s, read in marks 1 to 7.
+    PpmType2,
+}
+
+impl Ballistics {
+    ///Returns the level read as 0 VU, 0 dB or mark 4, in dBFS. -18 dBFS for Vu and PpmType2, -9 dBFS for PpmType1.
+    pub fn default_reference(self) -> Db {
+        match self {
+            Self::Vu | Self::PpmType2 => Db(-18.0),
+            Self::PpmType1 => Db(-9.0),
+        }
+    }
+}
+
+//ratio of integration time to the attack time constant, so a tone burst of the integration time reads 2 dB below a steady tone when charging on peaks only.
+const BURST_2DB: f64 = 3.918;
+
+//time constant in seconds of each of the two poles rising to 99% in 300 ms.
+const VU_TIME: f64 = 0.3 / 6.638;
+
+//gain reading the rectified average of a sine as its RMS.
+const VU_SCALE: f32 = std::f32::consts::PI / (2.0 * std::f32::consts::SQRT_2);
+
+///A level meter of standard ballistics, fed block by block and read per channel on the display scale of ballistics.
+#[derive(Clone, Debug)]
+pub struct LevelMeter {
+    ballistics: Ballistics,
+    sample_rate: u32,
+    reference: Db,
+    attack: f32,
+    release: f32,
+    //states of each channel, the second used only by the second pole of Vu.
+    state: Vec<[f32; 2]>,
+    readings: Vec<f32>,
+}
+
+impl LevelMeter {
+    ///Constructs a new LevelMeter of channel size.
+    pub fn new(ballistics: Ballistics, sample_rate: u32, channel_size: u16) -> Self {
+        let fs = sample_rate.max(1) as f64;
+        let pole = |t: f64| (1.0 - (-1.0 / (t * fs)).exp()) as f32;
+        //release of db in t seconds, as a gain per frame.
+        let fall = |db: f64, t: f64| 10f64.powf(-db / 20.0 / (t * fs)) as f32;
+        let (attack, release) = match ballistics {
+            Ballistics::Vu => (pole(VU_TIME), 0.0),
+            Ballistics::PpmType1 => (pole(0.005 / BURST_2DB), fall(20.0, 1.5)),
+            Ballistics::PpmType2 => (pole(0.010 / BURST_2DB), fall(24.0, 2.8)),
+        };
+        let mut o = Self {
+            ballistics,
+            sample_rate,
+            reference: ballistics.default_reference(),
+            attack,
+            release,
+            state: vec![[0.0; 2]; channel_size as usize],
+            readings: Vec::new(),
+        };
+        o.update();
+        o
+    }
+
+    ///Returns ballistics.
+    pub fn ballistics(&self) -> Ballistics {
+        self.ballistics
+    }
+
+    ///Returns sample rate.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    ///Returns the level read as 0 VU, 0 dB or mark 4, in dBFS.
+    pub fn reference(&self) -> Db {
+        self.reference
+    }
+
+    ///Sets the level read as 0 VU, 0 dB or mark 4, in dBFS. `Ballistics::default_reference` by default.
+    pub fn set_reference(&mut self, reference: Db) {
+        self.reference = reference;
+        self.update();
+    }
+
+    ///Clears the internal state, reading silence.
+    pub fn reset(&mut self) {
+        for o in &mut self.state {
+            *o = [0.0; 2];
+        }
+        self.update();
+    }
+
+    ///Returns the level of channel n in dBFS, negative infinity for silence.
+    pub fn level(&self, n: usize) -> Db {
+        let s = &self.state[n];
+        match self.ballistics {
+            Ballistics::Vu => Db::from_gain(s[1] * VU_SCALE),
+            _ => Db::from_gain(s[0]),
+        }
+    }
+
+    ///Returns readings of each channel of the last block.
+    pub fn readings(&self) -> &[f32] {
+        &self.readings
+    }
+
+    //computes readings from levels. Type II reads 4 dB a mark above mark 2 and 6 dB from mark 1 to 2.
+    fn update(&mut self) {
+        self.readings.clear();
+        for n in 0..self.state.len() {
+            let db = (self.level(n) - self.reference).0;
+            self.readings.push(match self.ballistics {
+                Ballistics::Vu | Ballistics::PpmType1 => db,
+                Ballistics::PpmType2 if db >= -8.0 => 4.0 + db / 4.0,
+                Ballistics::PpmType2 => (2.0 + (db + 8.0) / 6.0).max(0.0),
+            });
+        }
+    }
+
+    ///Meters frames and returns readings of each channel on the display scale, VU for Vu, dB for PpmType1 and marks for PpmType2.
+    pub fn process<T: Frame>(&mut self, o: &[T]) -> &[f32] {
+        let (a, r) = (self.attack, self.release);
+        for i in o.iter() {
+            for (n, s) in self
+                .state
+                .iter_mut()
+                .enumerate()
+                .take(T::CHANNEL_SIZE as usize)
+            {
+                let x = i.channel(n).abs();
+                match self.ballistics {
+                    Ballistics::Vu => {
+                        s[0] += a * (x - s[0]);
+                        s[1] += a * (s[0] - s[1]);
+                    }
+                    _ => {
+                        if x > s[0] {
+                            s[0] += a * (x - s[0]);
+                        } else {
+                            s[0] *= r;
+                        }
+                    }
+                }
+            }
+        }
+        self.update();
+        &self.readings
+    }
+}
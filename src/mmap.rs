@@ -0,0 +1,148 @@
+//!Read-only byte blocks backed by memory-mapped files.
+
+use crate::error::*;
+use crate::format::*;
+use crate::units::*;
+use crate::wav::read_header;
+use memmap2::Mmap;
+use std::fs::File;
+use std::io::Cursor;
+use std::ops::{Deref, Range};
+use std::path::Path;
+use std::sync::Arc;
+
+///A read-only region of a memory-mapped file holding samples, sliced without copying.
+///
+///The file must not be modified or truncated while mapped, which is undefined behavior.
+#[derive(Clone)]
+pub struct MappedBlock {
+    map: Arc<Mmap>,
+    range: Range<usize>,
+    sample_rate: u32,
+    channel_size: u16,
+    byte_size: usize,
+    big_endian: bool,
+}
+
+impl std::fmt::Debug for MappedBlock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("MappedBlock");
+        f.field("range", &self.range)
+            .field("sample_rate", &self.sample_rate)
+            .field("channel_size", &self.channel_size)
+            .field("byte_size", &self.byte_size)
+            .field("big_endian", &self.big_endian)
+            .finish()
+    }
+}
+
+fn map(path: &Path) -> Result<Arc<Mmap>, Error> {
+    let file = File::open(path)?;
+    //read-only, the caller keeps the file unchanged while mapped.
+    Ok(Arc::new(unsafe { Mmap::map(&file)? }))
+}
+
+impl MappedBlock {
+    ///Maps raw samples of the byte format from offset to the end of file, or len bytes. Sample rate is 0.
+    pub fn open(
+        path: impl AsRef<Path>,
+        offset: usize,
+        len: Option<usize>,
+        channel_size: u16,
+        byte_size: usize,
+        big_endian: bool,
+    ) -> Result<Self, Error> {
+        let map = map(path.as_ref())?;
+        let end = match len {
+            Some(n) => offset.checked_add(n).filter(|e| *e <= map.len()),
+            None => (offset <= map.len()).then_some(map.len()),
+        }
+        .ok_or(Error::InvalidData("region beyond end of file"))?;
+        Ok(Self {
+            map,
+            range: offset..end,
+            sample_rate: 0,
+            channel_size,
+            byte_size,
+            big_endian,
+        })
+    }
+
+    ///Maps the data chunk of a PCM or IEEE float WAV file.
+    pub fn open_wav(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let map = map(path.as_ref())?;
+        let mut r = Cursor::new(&map[..]);
+        let (spec, size) = read_header(&mut r)?;
+        let body = r.position() as usize;
+        let end = size.map_or(map.len(), |n| {
+            body.saturating_add(usize::try_from(n).unwrap_or(usize::MAX))
+                .min(map.len())
+        });
+        Ok(Self {
+            map,
+            range: body..end,
+            sample_rate: spec.sample_rate,
+            channel_size: spec.channel_size,
+            byte_size: spec.byte_size(),
+            big_endian: false,
+        })
+    }
+
+    ///Returns sample rate, 0 if unknown.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    ///Returns channel size.
+    pub fn channel_size(&self) -> u16 {
+        self.channel_size
+    }
+
+    ///Returns byte size of a frame.
+    pub fn byte_size(&self) -> usize {
+        self.byte_size
+    }
+
+    ///Returns true if data is in big-endian byte order.
+    pub fn is_big_endian(&self) -> bool {
+        self.big_endian
+    }
+
+    ///Returns number of whole frames.
+    pub fn frames(&self) -> Frames {
+        Frames(self.range.len().checked_div(self.byte_size).unwrap_or(0))
+    }
+
+    ///Returns a view of frames in range, clamped to the region, sharing the mapping.
+    pub fn slice(&self, frames: Range<Frames>) -> Self {
+        let n = self.frames().0;
+        let (a, b) = (
+            frames.start.0.min(n),
+            frames.end.0.clamp(frames.start.0.min(n), n),
+        );
+        let start = self.range.start + a * self.byte_size;
+        Self {
+            map: self.map.clone(),
+            range: start..start + (b - a) * self.byte_size,
+            ..*self
+        }
+    }
+
+    ///Copies the region into a ByteBlock.
+    pub fn to_byte_block(&self) -> ByteBlock {
+        ByteBlock::new(
+            self.channel_size,
+            self.byte_size,
+            self.big_endian,
+            self.to_vec(),
+        )
+    }
+}
+
+impl Deref for MappedBlock {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.map[self.range.clone()]
+    }
+}
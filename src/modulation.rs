@@ -0,0 +1,203 @@
+use crate::format::*;
+use crate::sample::*;
+use std::f32::consts::TAU;
+
+///Shape of low frequency oscillator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LfoShape {
+    Sine,
+    Triangle,
+    Square,
+    Saw,
+}
+
+///A low frequency oscillator.
+#[derive(Clone, Debug)]
+struct Lfo {
+    shape: LfoShape,
+    step: f32,
+    phase: f32,
+}
+
+impl Lfo {
+    fn new(sample_rate: u32, rate: f32, shape: LfoShape) -> Self {
+        Self {
+            shape,
+            step: rate / sample_rate as f32,
+            phase: 0.0,
+        }
+    }
+
+    //returns value from -1 to 1 at the phase offset.
+    fn value(&self, offset: f32) -> f32 {
+        let p = (self.phase + offset).fract();
+        match self.shape {
+            LfoShape::Sine => (TAU * p).sin(),
+            LfoShape::Triangle => 1.0 - 4.0 * ((p + 0.25).fract() - 0.5).abs(),
+            LfoShape::Square => {
+                if p < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            LfoShape::Saw => 2.0 * p - 1.0,
+        }
+    }
+
+    fn advance(&mut self) {
+        self.phase = (self.phase + self.step).fract();
+    }
+}
+
+///A modulated delay line for chorus and flanger effects.
+#[derive(Clone, Debug)]
+pub struct ModulatedDelay {
+    base: f32,
+    depth: f32,
+    feedback: f32,
+    mix: f32,
+    spread: f32,
+    lfo: Lfo,
+    buffer: Vec<Vec<f32>>,
+    pos: usize,
+}
+
+impl ModulatedDelay {
+    ///Constructs a new ModulatedDelay. Delay and depth are in milliseconds, rate is in Hz, mix is the wet proportion from 0 to 1.
+    pub fn new(
+        sample_rate: u32,
+        delay: f32,
+        depth: f32,
+        rate: f32,
+        feedback: f32,
+        mix: f32,
+    ) -> Self {
+        let k = sample_rate as f32 * 0.001;
+        Self {
+            base: delay.max(0.0) * k,
+            depth: depth.abs() * k,
+            feedback,
+            mix: mix.clamp(0.0, 1.0),
+            spread: 0.25,
+            lfo: Lfo::new(sample_rate, rate, LfoShape::Sine),
+            buffer: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    ///Constructs a chorus of 20 ms base delay.
+    pub fn chorus(sample_rate: u32, rate: f32, depth: f32, feedback: f32, mix: f32) -> Self {
+        Self::new(sample_rate, 20.0, depth, rate, feedback, mix)
+    }
+
+    ///Constructs a flanger sweeping delay from zero to twice the depth.
+    pub fn flanger(sample_rate: u32, rate: f32, depth: f32, feedback: f32, mix: f32) -> Self {
+        Self::new(sample_rate, depth, depth, rate, feedback, mix)
+    }
+
+    ///Sets shape of modulation.
+    pub fn set_shape(&mut self, shape: LfoShape) {
+        self.lfo.shape = shape;
+    }
+
+    ///Sets phase spread in cycles across all channels, which is 0.25 by default.
+    pub fn set_spread(&mut self, spread: f32) {
+        self.spread = spread;
+    }
+
+    ///Clears the internal state.
+    pub fn reset(&mut self) {
+        for b in self.buffer.iter_mut() {
+            b.fill(0.0);
+        }
+        self.pos = 0;
+        self.lfo.phase = 0.0;
+    }
+
+    ///Processes block in place.
+    pub fn process<T: Frame>(&mut self, o: &mut Block<T>) {
+        let c = T::CHANNEL_SIZE as usize;
+        let len = (self.base + self.depth).ceil() as usize + 2;
+        if self.buffer.len() != c {
+            self.buffer = vec![vec![0.0; len]; c];
+            self.pos = 0;
+        }
+        for i in o.iter_mut() {
+            for (n, b) in self.buffer.iter_mut().enumerate() {
+                let d = (self.base
+                    + self.depth * self.lfo.value(self.spread * n as f32 / c as f32))
+                .max(1.0);
+                let r = (self.pos + len) as f32 - d;
+                let k = r.floor();
+                let f = r - k;
+                let k = k as usize;
+                let y = b[k % len] * (1.0 - f) + b[(k + 1) % len] * f;
+                let x = i.channel(n);
+                b[self.pos] = x + y * self.feedback;
+                i.set_channel(n, x * (1.0 - self.mix) + y * self.mix);
+            }
+            self.pos = (self.pos + 1) % len;
+            self.lfo.advance();
+        }
+    }
+}
+
+///An amplitude modulation effect.
+#[derive(Clone, Debug)]
+pub struct Tremolo {
+    depth: f32,
+    lfo: Lfo,
+}
+
+impl Tremolo {
+    ///Constructs a new Tremolo. Rate is in Hz, depth is from 0 to 1.
+    pub fn new(sample_rate: u32, rate: f32, depth: f32, shape: LfoShape) -> Self {
+        Self {
+            depth: depth.clamp(0.0, 1.0),
+            lfo: Lfo::new(sample_rate, rate, shape),
+        }
+    }
+
+    ///Clears the internal state.
+    pub fn reset(&mut self) {
+        self.lfo.phase = 0.0;
+    }
+
+    ///Processes block in place.
+    pub fn process<T: Frame>(&mut self, o: &mut Block<T>) {
+        for i in o.iter_mut() {
+            i.scale(1.0 - self.depth * (1.0 - self.lfo.value(0.0)) / 2.0);
+            self.lfo.advance();
+        }
+    }
+}
+
+///A pitch modulation effect of modulated fractional delay.
+#[derive(Clone, Debug)]
+pub struct Vibrato(ModulatedDelay);
+
+impl Vibrato {
+    ///Constructs a new Vibrato. Rate is in Hz, depth is in milliseconds of delay swing.
+    pub fn new(sample_rate: u32, rate: f32, depth: f32, shape: LfoShape) -> Self {
+        let mut o = ModulatedDelay::new(sample_rate, depth, depth, rate, 0.0, 1.0);
+        o.set_shape(shape);
+        o.set_spread(0.0);
+        Self(o)
+    }
+
+    ///Returns latency in frames.
+    pub fn latency(&self) -> usize {
+        self.0.base.round() as usize
+    }
+
+    ///Clears the internal state.
+    pub fn reset(&mut self) {
+        self.0.reset();
+    }
+
+    ///Processes block in place.
+    pub fn process<T: Frame>(&mut self, o: &mut Block<T>) {
+        self.0.process(o);
+    }
+}
@@ -0,0 +1,106 @@
+use crate::format::*;
+use crate::sample::*;
+use crate::stft::*;
+use crate::window::*;
+
+///Detection function of onset.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OnsetMethod {
+    ///Sum of positive magnitude differences between spectra.
+    SpectralFlux,
+    ///Positive difference of energy between frames.
+    Energy,
+}
+
+///An onset detector of transients.
+#[derive(Clone, Debug)]
+pub struct OnsetDetector {
+    method: OnsetMethod,
+    fft_size: usize,
+    hop: usize,
+    delta: f32,
+    min_gap: usize,
+}
+
+impl OnsetDetector {
+    ///Constructs a new OnsetDetector of 1024 FFT size and 512 hop.
+    pub fn new(method: OnsetMethod) -> Self {
+        Self {
+            method,
+            fft_size: 1024,
+            hop: 512,
+            delta: 0.07,
+            min_gap: 0,
+        }
+    }
+
+    ///Sets FFT size and hop in frames.
+    pub fn set_frame(&mut self, fft_size: usize, hop: usize) {
+        self.fft_size = fft_size;
+        self.hop = hop.max(1);
+    }
+
+    ///Sets threshold above the local mean of normalized detection function, 0.07 by default.
+    pub fn set_delta(&mut self, delta: f32) {
+        self.delta = delta;
+    }
+
+    ///Sets minimum gap between onsets in frames.
+    pub fn set_min_gap(&mut self, min_gap: usize) {
+        self.min_gap = min_gap;
+    }
+
+    ///Returns normalized onset detection function of each hop, mixing all channels.
+    pub fn detection_function<T: Frame>(&self, o: &Whole<T>) -> Vec<f32> {
+        let c = T::CHANNEL_SIZE as usize;
+        let x: Vec<f32> = o
+            .iter()
+            .map(|i| (0..c).map(|n| i.channel(n)).sum::<f32>() / c as f32)
+            .collect();
+        let s = Stft::from_slice(o.sample_rate(), &x, self.fft_size, self.hop, Window::Hann);
+        let m = s.magnitude();
+        let mut v = vec![0.0f32; m.len()];
+        for t in 1..m.len() {
+            v[t] = match self.method {
+                OnsetMethod::SpectralFlux => m[t]
+                    .iter()
+                    .zip(m[t - 1].iter())
+                    .map(|(a, b)| (a - b).max(0.0))
+                    .sum(),
+                OnsetMethod::Energy => {
+                    let e = |f: &Vec<f32>| f.iter().map(|o| o * o).sum::<f32>();
+                    (e(&m[t]) - e(&m[t - 1])).max(0.0)
+                }
+            };
+        }
+        let max = v.iter().fold(0.0f32, |a, b| a.max(*b));
+        if max > 0.0 {
+            for i in v.iter_mut() {
+                *i /= max;
+            }
+        }
+        v
+    }
+
+    ///Returns frame positions of onsets.
+    pub fn detect<T: Frame>(&self, o: &Whole<T>) -> Vec<usize> {
+        let f = self.detection_function(o);
+        let w = 3;
+        let mut v: Vec<usize> = Vec::new();
+        for t in 1..f.len().saturating_sub(1) {
+            if f[t] <= f[t - 1] || f[t] < f[t + 1] {
+                continue;
+            }
+            let r = &f[t.saturating_sub(w)..(t + w + 1).min(f.len())];
+            let mean = r.iter().sum::<f32>() / r.len() as f32;
+            if f[t] <= mean + self.delta {
+                continue;
+            }
+            let p = t * self.hop;
+            if v.last().is_none_or(|o| p - o >= self.min_gap) {
+                v.push(p);
+            }
+        }
+        v
+    }
+}
@@ -0,0 +1,113 @@
+use crate::format::*;
+use crate::sample::*;
+use crate::units::*;
+
+///Summary of the frames of a bin of a channel.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OverviewBin {
+    pub min: f32,
+    pub max: f32,
+    ///RMS if enabled.
+    pub rms: Option<f32>,
+}
+
+///Waveform overview of min and max per bin of frames of each channel, updated incrementally as frames are pushed. The last bin may be partial.
+#[derive(Clone, Debug)]
+pub struct Overview {
+    frames_per_bin: usize,
+    rms: bool,
+    bins: Vec<Vec<OverviewBin>>,
+    sums: Vec<f64>,
+    fill: usize,
+    frames: usize,
+}
+
+impl Overview {
+    ///Constructs a new, empty Overview of channels and frames per bin.
+    pub fn new(channel_size: u16, frames_per_bin: usize) -> Self {
+        Self {
+            frames_per_bin: frames_per_bin.max(1),
+            rms: false,
+            bins: vec![Vec::new(); channel_size as usize],
+            sums: vec![0.0; channel_size as usize],
+            fill: 0,
+            frames: 0,
+        }
+    }
+
+    ///Sets whether bins measure RMS, false by default. Takes effect from the next bin.
+    pub fn set_rms(&mut self, rms: bool) {
+        self.rms = rms;
+    }
+
+    ///Returns frames per bin.
+    pub fn frames_per_bin(&self) -> usize {
+        self.frames_per_bin
+    }
+
+    ///Returns number of frames pushed.
+    pub fn frames(&self) -> Frames {
+        Frames(self.frames)
+    }
+
+    ///Returns bins of channel n.
+    pub fn bins(&self, n: usize) -> &[OverviewBin] {
+        &self.bins[n]
+    }
+
+    //updates RMS of the last bins.
+    fn update_rms(&mut self) {
+        if self.fill == 0 {
+            return;
+        }
+        for (b, s) in self.bins.iter_mut().zip(&self.sums) {
+            if let Some(r) = b.last_mut().and_then(|o| o.rms.as_mut()) {
+                *r = (s / self.fill as f64).sqrt() as f32;
+            }
+        }
+    }
+
+    ///Adds frames, extending the last bin and appending new ones.
+    pub fn push<T: Frame>(&mut self, o: &[T]) {
+        let channels = self.bins.len().min(T::CHANNEL_SIZE as usize);
+        for f in o {
+            if self.fill == self.frames_per_bin || self.frames == 0 {
+                self.update_rms();
+                for (n, b) in self.bins.iter_mut().enumerate() {
+                    let v = if n < channels { f.channel(n) } else { 0.0 };
+                    b.push(OverviewBin {
+                        min: v,
+                        max: v,
+                        rms: self.rms.then_some(0.0),
+                    });
+                }
+                self.sums.fill(0.0);
+                self.fill = 0;
+            }
+            for (n, b) in self.bins.iter_mut().enumerate().take(channels) {
+                let v = f.channel(n);
+                let last = b.last_mut().expect("bin");
+                last.min = last.min.min(v);
+                last.max = last.max.max(v);
+                self.sums[n] += v as f64 * v as f64;
+            }
+            self.fill += 1;
+            self.frames += 1;
+        }
+        self.update_rms();
+    }
+}
+
+block_impl! {
+    impl<T> Block<T>
+    where
+        T: Frame,
+    {
+        ///Returns min and max overview of frames per bin.
+        pub fn overview(&self, frames_per_bin: usize) -> Overview {
+            let mut o = Overview::new(T::CHANNEL_SIZE, frames_per_bin);
+            o.push(self);
+            o
+        }
+    }
+}
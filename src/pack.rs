@@ -0,0 +1,260 @@
+use crate::error::*;
+use crate::format::*;
+
+///Position of a sample of fewer bits within i32.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Justification {
+    ///The sample takes the most significant bits, as 24 bit audio in 32 bit words.
+    Left,
+    ///The sample takes the least significant bits, sign extended.
+    #[default]
+    Right,
+}
+
+fn check_bits(bits: u32) -> Result<(), Error> {
+    if (1..=32).contains(&bits) {
+        Ok(())
+    } else {
+        Err(Error::Unsupported("bit width outside 1 to 32"))
+    }
+}
+
+block_impl! {
+    impl Block<i32> {
+        ///Packs samples of bits, from 1 to 32, back to back across byte boundaries. Big-endian packing puts the most significant bit first, little-endian the least significant bit first. The last byte is padded with zero bits.
+        pub fn pack_bits(
+            &self,
+            bits: u32,
+            justification: Justification,
+            big_endian: bool,
+        ) -> Result<Vec<u8>, Error> {
+            check_bits(bits)?;
+            let mask = u64::MAX >> (64 - bits);
+            let mut v = Vec::with_capacity((self.len() * bits as usize).div_ceil(8));
+            let (mut acc, mut n) = (0u64, 0u32);
+            for s in self.iter() {
+                let x = match justification {
+                    Justification::Left => (*s as u32 >> (32 - bits)) as u64,
+                    Justification::Right => *s as u32 as u64 & mask,
+                };
+                if big_endian {
+                    acc = (acc << bits) | x;
+                    n += bits;
+                    while n >= 8 {
+                        n -= 8;
+                        v.push((acc >> n) as u8);
+                    }
+                    acc &= (1 << n) - 1;
+                } else {
+                    acc |= x << n;
+                    n += bits;
+                    while n >= 8 {
+                        v.push(acc as u8);
+                        acc >>= 8;
+                        n -= 8;
+                    }
+                }
+            }
+            if n > 0 {
+                v.push(if big_endian {
+                    (acc << (8 - n)) as u8
+                } else {
+                    acc as u8
+                });
+            }
+            Ok(v)
+        }
+    }
+}
+
+impl Block<i32> {
+    ///Unpacks samples of bits, from 1 to 32, packed back to back as by `pack_bits`. Padding bits shorter than a sample at the end are ignored.
+    pub fn unpack_bits(
+        data: &[u8],
+        bits: u32,
+        justification: Justification,
+        big_endian: bool,
+    ) -> Result<Self, Error> {
+        check_bits(bits)?;
+        let mask = u64::MAX >> (64 - bits);
+        let mut v = Block::new(data.len() * 8 / bits as usize);
+        let (mut acc, mut n) = (0u64, 0u32);
+        for b in data {
+            if big_endian {
+                acc = (acc << 8) | *b as u64;
+            } else {
+                acc |= (*b as u64) << n;
+            }
+            n += 8;
+            while n >= bits {
+                let x = if big_endian {
+                    n -= bits;
+                    let x = (acc >> n) & mask;
+                    acc &= (1 << n) - 1;
+                    x
+                } else {
+                    let x = acc & mask;
+                    acc >>= bits;
+                    n -= bits;
+                    x
+                };
+                let left = ((x as u32) << (32 - bits)) as i32;
+                v.push(match justification {
+                    Justification::Left => left,
+                    Justification::Right => left >> (32 - bits),
+                });
+            }
+        }
+        Ok(v)
+    }
+}
+
+impl ByteBlock {
+    ///Packs interleaved samples of channels to ByteBlock of bits, from 1 to 32, per sample. Fails unless a frame fills whole bytes.
+    pub fn from_packed_bits(
+        channel_size: u16,
+        bits: u32,
+        justification: Justification,
+        big_endian: bool,
+        o: &Block<i32>,
+    ) -> Result<Self, Error> {
+        let frame_bits = channel_size as usize * bits as usize;
+        if channel_size == 0 || !frame_bits.is_multiple_of(8) {
+            return Err(Error::LengthNotMultiple {
+                len: frame_bits,
+                multiple: 8,
+            });
+        }
+        if !o.len().is_multiple_of(channel_size as usize) {
+            return Err(Error::LengthNotMultiple {
+                len: o.len(),
+                multiple: channel_size as usize,
+            });
+        }
+        let data = o.pack_bits(bits, justification, big_endian)?;
+        Ok(Self::new(channel_size, frame_bits / 8, big_endian, data))
+    }
+
+    ///Unpacks data of bits per sample to interleaved samples, in the byte order of self, where a frame of byte size holds channels of packed samples. Fails if bits per sample are outside 1 to 32 or not whole.
+    pub fn unpack_bits(&self, justification: Justification) -> Result<Block<i32>, Error> {
+        let c = self.channel_size() as usize;
+        if c == 0 || !(self.byte_size() * 8).is_multiple_of(c) {
+            return Err(Error::LengthNotMultiple {
+                len: self.byte_size() * 8,
+                multiple: c,
+            });
+        }
+        let frames = self.len().checked_div(self.byte_size()).unwrap_or(0);
+        let mut v = Block::unpack_bits(
+            &self[..frames * self.byte_size()],
+            (self.byte_size() * 8 / c) as u32,
+            justification,
+            self.is_big_endian(),
+        )?;
+        v.truncate(frames * c);
+        Ok(v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //pseudo-random values, deterministic across runs.
+    fn noise(n: usize) -> impl Iterator<Item = u32> {
+        let mut x = 0x9E37_79B9u32;
+        (0..n).map(move |_| {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            x
+        })
+    }
+
+    //returns x reduced to what a sample of bits keeps at justification.
+    fn reduce(x: u32, bits: u32, justification: Justification) -> i32 {
+        let left = (x << (32 - bits)) as i32;
+        match justification {
+            Justification::Left => (x >> (32 - bits) << (32 - bits)) as i32,
+            Justification::Right => left >> (32 - bits),
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        for bits in 1..=32 {
+            for justification in [Justification::Left, Justification::Right] {
+                for big_endian in [false, true] {
+                    for n in 0..12 {
+                        let o = Block::from(
+                            noise(n)
+                                .map(|x| reduce(x, bits, justification))
+                                .collect::<Vec<_>>(),
+                        );
+                        let data = o.pack_bits(bits, justification, big_endian).unwrap();
+                        assert_eq!(data.len(), (n * bits as usize).div_ceil(8));
+                        let mut v =
+                            Block::unpack_bits(&data, bits, justification, big_endian).unwrap();
+                        //padding as long as a sample of few bits unpacks as zeros.
+                        assert!(v[n..].iter().all(|o| *o == 0));
+                        v.truncate(n);
+                        assert_eq!(v[..], o[..], "{bits} bits {justification:?} {big_endian}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn bit_order() {
+        let o = Block::from(vec![1, -2, 3, -4]);
+        let be = o.pack_bits(4, Justification::Right, true).unwrap();
+        let le = o.pack_bits(4, Justification::Right, false).unwrap();
+        assert_eq!(be, [0x1E, 0x3C]);
+        assert_eq!(le, [0xE1, 0xC3]);
+        let o = Block::from(vec![1, 1, 1]);
+        assert_eq!(
+            o.pack_bits(3, Justification::Right, true).unwrap(),
+            [0x24, 0x80]
+        );
+        assert_eq!(
+            o.pack_bits(3, Justification::Right, false).unwrap(),
+            [0x49, 0x00]
+        );
+    }
+
+    #[test]
+    fn invalid_bits() {
+        let o = Block::from(vec![0]);
+        for bits in [0, 33] {
+            assert!(o.pack_bits(bits, Justification::Right, true).is_err());
+            assert!(Block::unpack_bits(&[0; 8], bits, Justification::Right, true).is_err());
+        }
+    }
+
+    #[test]
+    fn byte_block_round_trip() {
+        for (channel_size, bits) in [(1, 8), (2, 4), (2, 12), (1, 24), (8, 1), (3, 16)] {
+            for big_endian in [false, true] {
+                let o = Block::from(
+                    noise(channel_size as usize * 5)
+                        .map(|x| reduce(x, bits, Justification::Right))
+                        .collect::<Vec<_>>(),
+                );
+                let b = ByteBlock::from_packed_bits(
+                    channel_size,
+                    bits,
+                    Justification::Right,
+                    big_endian,
+                    &o,
+                )
+                .unwrap();
+                assert_eq!(b.len(), o.len() * bits as usize / 8);
+                assert_eq!(b.unpack_bits(Justification::Right).unwrap()[..], o[..]);
+            }
+        }
+        let o = Block::from(vec![0; 3]);
+        assert!(ByteBlock::from_packed_bits(1, 4, Justification::Right, true, &o).is_err());
+        assert!(ByteBlock::from_packed_bits(2, 8, Justification::Right, true, &o).is_err());
+    }
+}
@@ -0,0 +1,85 @@
+use crate::format::*;
+use crate::sample::*;
+use rayon::prelude::*;
+
+//minimum length split across threads, also the length of each part.
+pub(crate) const PAR_LEN: usize = 1 << 16;
+
+///Builds N planar channels to packed block in parallel, truncating to the shortest channel.
+pub fn par_build<T, const N: usize>(channels: [&[T]; N]) -> Block<[T; N]>
+where
+    T: Type + Clone + Send + Sync,
+    [T; N]: Sample,
+{
+    let len = channels.iter().map(|o| o.len()).min().unwrap_or(0);
+    let mut v = Vec::new();
+    (0..len)
+        .into_par_iter()
+        .with_min_len(PAR_LEN)
+        .map(|i| std::array::from_fn(|n| channels[n][i].clone()))
+        .collect_into_vec(&mut v);
+    Block::from(v)
+}
+
+///Builds N planar channels to packed block in parallel, padding shorter channels with default.
+pub fn par_build_padding<T, const N: usize>(channels: [&[T]; N]) -> Block<[T; N]>
+where
+    T: Type + Clone + Default + Send + Sync,
+    [T; N]: Sample,
+{
+    let len = channels.iter().map(|o| o.len()).max().unwrap_or(0);
+    let mut v = Vec::new();
+    (0..len)
+        .into_par_iter()
+        .with_min_len(PAR_LEN)
+        .map(|i| std::array::from_fn(|n| channels[n].get(i).cloned().unwrap_or_default()))
+        .collect_into_vec(&mut v);
+    Block::from(v)
+}
+
+block_impl! {
+    impl<T> Block<T>
+    where
+        T: Channels + Sync,
+    {
+        //folds each channel of parts in parallel.
+        fn par_fold(
+            &self,
+            f: impl Fn(f64, f64) -> f64 + Sync,
+            g: impl Fn(f64, f64) -> f64 + Sync,
+        ) -> Vec<f64> {
+            let c = T::CHANNEL_SIZE as usize;
+            self.par_chunks(PAR_LEN)
+                .map(|o| {
+                    let mut v = vec![0.0f64; c];
+                    for i in o {
+                        for (n, p) in v.iter_mut().enumerate() {
+                            *p = f(*p, i.get_channel(n).to_unit());
+                        }
+                    }
+                    v
+                })
+                .reduce(
+                    || vec![0.0; c],
+                    |a, b| a.into_iter().zip(b).map(|(a, b)| g(a, b)).collect(),
+                )
+        }
+
+        ///Returns peak of each channel as peak, scanning parts in parallel.
+        pub fn par_peak(&self) -> Vec<f32> {
+            self.par_fold(|p, x| p.max(x.abs()), f64::max)
+                .into_iter()
+                .map(|o| o as f32)
+                .collect()
+        }
+
+        ///Returns RMS of each channel as rms, scanning parts in parallel.
+        pub fn par_rms(&self) -> Vec<f32> {
+            let len = self.len().max(1) as f64;
+            self.par_fold(|s, x| s + x * x, |a, b| a + b)
+                .into_iter()
+                .map(|o| (o / len).sqrt() as f32)
+                .collect()
+        }
+    }
+}
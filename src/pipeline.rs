@@ -0,0 +1,459 @@
+//!Streaming of blocks from sources through processors into sinks.
+
+use crate::effect::*;
+use crate::format::*;
+use crate::sample::*;
+use crate::units::SampleRate;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+///A producer of blocks.
+pub trait Source {
+    type Item: Sample;
+
+    ///Returns the next block, or None when exhausted.
+    fn pull(&mut self) -> Option<Block<Self::Item>>;
+}
+
+///A transformer of blocks.
+pub trait Processor {
+    type In: Sample;
+    type Out: Sample;
+
+    ///Returns the transformed block.
+    fn process(&mut self, o: Block<Self::In>) -> Block<Self::Out>;
+
+    ///Returns a remaining tail after the input is exhausted, if any.
+    fn flush(&mut self) -> Option<Block<Self::Out>> {
+        None
+    }
+}
+
+///A consumer of blocks.
+pub trait Sink {
+    type Item: Sample;
+
+    ///Consumes a block.
+    fn push(&mut self, o: Block<Self::Item>);
+
+    ///Called once after the last block.
+    fn finish(&mut self) {}
+}
+
+///A source of consecutive blocks of a whole.
+pub struct Blocks<T> {
+    data: Whole<T>,
+    frames: usize,
+    index: usize,
+    pool: Option<BlockPool<T>>,
+}
+
+impl<T> std::fmt::Debug for Blocks<T>
+where
+    T: Sample,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("Blocks");
+        f.field("data", &self.data)
+            .field("frames", &self.frames)
+            .field("index", &self.index)
+            .finish()
+    }
+}
+
+impl<T> Blocks<T>
+where
+    T: Sample,
+{
+    ///Constructs a new Blocks of frames per block, the last block may be shorter.
+    pub fn new(data: Whole<T>, frames: usize) -> Self {
+        Self {
+            data,
+            frames: frames.max(1),
+            index: 0,
+            pool: None,
+        }
+    }
+
+    ///Takes buffers of blocks from pool instead of allocating.
+    pub fn with_pool(mut self, pool: BlockPool<T>) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
+    ///Returns sample rate of the whole.
+    pub fn sample_rate(&self) -> SampleRate {
+        self.data.sample_rate()
+    }
+}
+
+impl<T> Source for Blocks<T>
+where
+    T: Sample + Clone,
+{
+    type Item = T;
+
+    fn pull(&mut self) -> Option<Block<T>> {
+        if self.index >= self.data.len() {
+            return None;
+        }
+        let end = (self.index + self.frames).min(self.data.len());
+        let o = match &self.pool {
+            Some(p) => {
+                let mut o = p.get();
+                o.extend_from_slice(&self.data[self.index..end]);
+                o
+            }
+            None => Block::from(self.data[self.index..end].to_vec()),
+        };
+        self.index = end;
+        Some(o)
+    }
+}
+
+impl<T> Sink for Whole<T>
+where
+    T: Sample,
+{
+    type Item = T;
+
+    fn push(&mut self, o: Block<T>) {
+        self.add(o);
+    }
+}
+
+impl<T> Sink for Vec<Block<T>>
+where
+    T: Sample,
+{
+    type Item = T;
+
+    fn push(&mut self, o: Block<T>) {
+        Vec::push(self, o);
+    }
+}
+
+///A pool recycling buffers of blocks of frames, shared by its clones.
+///
+///Blocks are checked out with get and returned with put, or by using the pool as the final sink.
+pub struct BlockPool<T> {
+    blocks: Arc<Mutex<Vec<Block<T>>>>,
+    frames: usize,
+    limit: usize,
+}
+
+impl<T> Clone for BlockPool<T> {
+    fn clone(&self) -> Self {
+        Self {
+            blocks: self.blocks.clone(),
+            frames: self.frames,
+            limit: self.limit,
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for BlockPool<T>
+where
+    T: Sample,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("BlockPool");
+        f.field("frames", &self.frames)
+            .field("limit", &self.limit)
+            .field("available", &self.available())
+            .finish()
+    }
+}
+
+impl<T> BlockPool<T>
+where
+    T: Sample,
+{
+    ///Constructs a new BlockPool preallocating count blocks of frames. The pool keeps at most count returned blocks.
+    pub fn new(count: usize, frames: usize) -> Self {
+        let mut v = Vec::with_capacity(count);
+        v.extend((0..count).map(|_| Block::new(frames)));
+        Self {
+            blocks: Arc::new(Mutex::new(v)),
+            frames,
+            limit: count,
+        }
+    }
+
+    ///Returns frames of each buffer.
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    ///Returns number of buffers ready to be checked out.
+    pub fn available(&self) -> usize {
+        self.lock().len()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Block<T>>> {
+        self.blocks.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    ///Checks out an empty block of capacity frames, allocating only when the pool is empty.
+    pub fn get(&self) -> Block<T> {
+        self.lock().pop().unwrap_or_else(|| Block::new(self.frames))
+    }
+
+    ///Returns a block to the pool, dropping it if the pool is full or the block is smaller than frames.
+    pub fn put(&self, mut o: Block<T>) {
+        if o.capacity() < self.frames {
+            return;
+        }
+        let mut v = self.lock();
+        if v.len() < self.limit {
+            o.clear();
+            v.push(o);
+        }
+    }
+}
+
+impl<T> Sink for BlockPool<T>
+where
+    T: Sample,
+{
+    type Item = T;
+
+    fn push(&mut self, o: Block<T>) {
+        self.put(o);
+    }
+}
+
+///A processor of a closure transforming blocks.
+pub struct Map<F, I, O> {
+    f: F,
+    marker: PhantomData<fn(I) -> O>,
+}
+
+///Returns a processor of a closure transforming blocks.
+pub fn map<F, I, O>(f: F) -> Map<F, I, O>
+where
+    F: FnMut(Block<I>) -> Block<O>,
+    I: Sample,
+    O: Sample,
+{
+    Map {
+        f,
+        marker: PhantomData,
+    }
+}
+
+impl<F, I, O> Processor for Map<F, I, O>
+where
+    F: FnMut(Block<I>) -> Block<O>,
+    I: Sample,
+    O: Sample,
+{
+    type In = I;
+    type Out = O;
+
+    fn process(&mut self, o: Block<I>) -> Block<O> {
+        (self.f)(o)
+    }
+}
+
+///A processor of a closure modifying blocks in place, such as a built-in effect's process.
+pub struct InPlace<F, T> {
+    f: F,
+    marker: PhantomData<fn(T)>,
+}
+
+///Returns a processor of a closure modifying blocks in place.
+pub fn in_place<F, T>(f: F) -> InPlace<F, T>
+where
+    F: FnMut(&mut Block<T>),
+    T: Sample,
+{
+    InPlace {
+        f,
+        marker: PhantomData,
+    }
+}
+
+impl<F, T> Processor for InPlace<F, T>
+where
+    F: FnMut(&mut Block<T>),
+    T: Sample,
+{
+    type In = T;
+    type Out = T;
+
+    fn process(&mut self, mut o: Block<T>) -> Block<T> {
+        (self.f)(&mut o);
+        o
+    }
+}
+
+///A processor of an effect.
+#[derive(Debug)]
+pub struct Apply<E, T> {
+    effect: E,
+    marker: PhantomData<fn(T)>,
+}
+
+impl<E, T> Apply<E, T>
+where
+    E: Effect<T>,
+    T: Frame,
+{
+    ///Constructs a new Apply.
+    pub fn new(effect: E) -> Self {
+        Self {
+            effect,
+            marker: PhantomData,
+        }
+    }
+
+    ///Returns the inner effect.
+    pub fn into_inner(self) -> E {
+        self.effect
+    }
+}
+
+impl<E, T> Processor for Apply<E, T>
+where
+    E: Effect<T>,
+    T: Frame,
+{
+    type In = T;
+    type Out = T;
+
+    fn process(&mut self, mut o: Block<T>) -> Block<T> {
+        self.effect.process(&mut o);
+        o
+    }
+}
+
+///A source of blocks of source transformed by processor.
+#[derive(Debug)]
+pub struct Chain<S, P> {
+    source: S,
+    processor: P,
+    done: bool,
+}
+
+impl<S, P> Source for Chain<S, P>
+where
+    S: Source,
+    P: Processor<In = S::Item>,
+{
+    type Item = P::Out;
+
+    fn pull(&mut self) -> Option<Block<P::Out>> {
+        if self.done {
+            return None;
+        }
+        match self.source.pull() {
+            Some(o) => Some(self.processor.process(o)),
+            None => {
+                self.done = true;
+                self.processor.flush()
+            }
+        }
+    }
+}
+
+///A sink of blocks transformed by processor into sink, for push-based execution.
+#[derive(Debug)]
+pub struct Feed<P, K> {
+    processor: P,
+    sink: K,
+}
+
+impl<P, K> Feed<P, K>
+where
+    P: Processor,
+    K: Sink<Item = P::Out>,
+{
+    ///Constructs a new Feed.
+    pub fn new(processor: P, sink: K) -> Self {
+        Self { processor, sink }
+    }
+
+    ///Returns the inner sink.
+    pub fn into_sink(self) -> K {
+        self.sink
+    }
+}
+
+impl<P, K> Sink for Feed<P, K>
+where
+    P: Processor,
+    K: Sink<Item = P::Out>,
+{
+    type Item = P::In;
+
+    fn push(&mut self, o: Block<P::In>) {
+        let o = self.processor.process(o);
+        self.sink.push(o);
+    }
+
+    fn finish(&mut self) {
+        if let Some(o) = self.processor.flush() {
+            self.sink.push(o);
+        }
+        self.sink.finish();
+    }
+}
+
+///A pull-based chain of a source and processors.
+#[derive(Debug)]
+pub struct Pipeline<S> {
+    source: S,
+}
+
+impl<S> Pipeline<S>
+where
+    S: Source,
+{
+    ///Constructs a new Pipeline of source.
+    pub fn new(source: S) -> Self {
+        Self { source }
+    }
+
+    ///Appends a processor.
+    pub fn then<P>(self, processor: P) -> Pipeline<Chain<S, P>>
+    where
+        P: Processor<In = S::Item>,
+    {
+        Pipeline::new(Chain {
+            source: self.source,
+            processor,
+            done: false,
+        })
+    }
+
+    ///Pulls all blocks into sink and finishes it. Returns the number of frames delivered.
+    pub fn run<K>(mut self, sink: &mut K) -> usize
+    where
+        K: Sink<Item = S::Item>,
+    {
+        let mut n = 0;
+        while let Some(o) = self.source.pull() {
+            n += o.len();
+            sink.push(o);
+        }
+        sink.finish();
+        n
+    }
+
+    ///Returns the chained source.
+    pub fn into_source(self) -> S {
+        self.source
+    }
+}
+
+impl<S> Source for Pipeline<S>
+where
+    S: Source,
+{
+    type Item = S::Item;
+
+    fn pull(&mut self) -> Option<Block<S::Item>> {
+        self.source.pull()
+    }
+}
@@ -0,0 +1,127 @@
+use crate::error::*;
+use crate::format::*;
+use crate::sample::*;
+
+///Pitch estimate of an analysis frame.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PitchEstimate {
+    ///Start frame of the analysis window.
+    pub frame: usize,
+    ///Fundamental frequency in Hz, None if unvoiced.
+    pub frequency: Option<f32>,
+    ///Confidence from 0 to 1.
+    pub confidence: f32,
+}
+
+///A YIN pitch detector of mono signal.
+#[derive(Clone, Debug)]
+pub struct PitchDetector {
+    sample_rate: u32,
+    min_lag: usize,
+    max_lag: usize,
+    hop: usize,
+    threshold: f32,
+}
+
+impl PitchDetector {
+    ///Constructs a new PitchDetector searching from min_freq to max_freq in Hz. Hop is in frames. Fails with Error::InvalidArgument unless 0 < min_freq < max_freq <= sample_rate / 2.
+    pub fn new(sample_rate: u32, min_freq: f32, max_freq: f32, hop: usize) -> Result<Self, Error> {
+        let sr = sample_rate as f32;
+        //also rejects NaN.
+        if !(min_freq > 0.0 && min_freq < max_freq && max_freq <= sr / 2.0) {
+            return Err(Error::InvalidArgument(
+                "frequency range not within 0 to Nyquist",
+            ));
+        }
+        let min_lag = ((sr / max_freq).floor() as usize).max(2);
+        Ok(Self {
+            sample_rate,
+            min_lag,
+            max_lag: ((sr / min_freq).ceil() as usize).max(min_lag + 1),
+            hop: hop.max(1),
+            threshold: 0.15,
+        })
+    }
+
+    ///Sets threshold of the cumulative mean normalized difference, 0.15 by default.
+    pub fn set_threshold(&mut self, threshold: f32) {
+        self.threshold = threshold;
+    }
+
+    ///Returns analysis window size in frames.
+    pub fn window(&self) -> usize {
+        2 * self.max_lag + 2
+    }
+
+    fn estimate(&self, x: &[f32], frame: usize) -> PitchEstimate {
+        let w = self.max_lag;
+        let mut d = vec![0.0f32; self.max_lag + 2];
+        for (tau, v) in d.iter_mut().enumerate().skip(1) {
+            let mut s = 0.0;
+            for j in 0..w {
+                let e = x[j] - x[j + tau];
+                s += e * e;
+            }
+            *v = s;
+        }
+        let mut sum = 0.0;
+        d[0] = 1.0;
+        for (tau, v) in d.iter_mut().enumerate().skip(1) {
+            sum += *v;
+            *v = if sum > 0.0 {
+                *v * tau as f32 / sum
+            } else {
+                1.0
+            };
+        }
+        let mut best = None;
+        let mut tau = self.min_lag;
+        while tau <= self.max_lag {
+            if d[tau] < self.threshold {
+                while tau < self.max_lag && d[tau + 1] < d[tau] {
+                    tau += 1;
+                }
+                best = Some(tau);
+                break;
+            }
+            tau += 1;
+        }
+        let voiced = best.is_some();
+        let tau = best.unwrap_or_else(|| {
+            (self.min_lag..=self.max_lag)
+                .min_by(|a, b| d[*a].total_cmp(&d[*b]))
+                .unwrap_or(self.min_lag)
+        });
+        let (a, b, c) = (d[tau - 1], d[tau], d[tau + 1]);
+        let den = a - 2.0 * b + c;
+        let shift = if den.abs() > f32::EPSILON {
+            (0.5 * (a - c) / den).clamp(-0.5, 0.5)
+        } else {
+            0.0
+        };
+        PitchEstimate {
+            frame,
+            frequency: voiced.then(|| self.sample_rate as f32 / (tau as f32 + shift)),
+            confidence: (1.0 - b).clamp(0.0, 1.0),
+        }
+    }
+
+    ///Returns pitch estimates of windows every hop frames.
+    pub fn detect(&self, o: &Block<f32>) -> Vec<PitchEstimate> {
+        let n = self.window();
+        let mut v = Vec::new();
+        let mut s = 0;
+        while s + n <= o.len() {
+            v.push(self.estimate(&o[s..s + n], s));
+            s += self.hop;
+        }
+        v
+    }
+
+    ///Returns pitch estimates of channel n of block.
+    pub fn detect_channel<T: Frame>(&self, o: &Block<T>, n: usize) -> Vec<PitchEstimate> {
+        self.detect(&Block::from(
+            o.iter().map(|i| i.channel(n)).collect::<Vec<f32>>(),
+        ))
+    }
+}
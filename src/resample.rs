@@ -0,0 +1,162 @@
+use crate::cancel::*;
+use crate::error::*;
+use crate::format::*;
+use crate::sample::*;
+use crate::units::*;
+use crate::util::*;
+use std::f64::consts::PI;
+
+//zero crossings of the sinc on each side.
+const ZEROS: f64 = 16.0;
+
+//below this many output samples the GPU is not worth its setup.
+#[cfg(feature = "gpu")]
+const GPU_MIN: usize = 1 << 18;
+
+///Interpolation kernel from one sample rate to another.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Kernel {
+    ///Input step per output frame is l / m.
+    pub l: u64,
+    pub m: u64,
+    ///Cutoff relative to the input Nyquist frequency.
+    pub cutoff: f64,
+    ///Half width of the window in input frames.
+    pub half: f64,
+    ///Taps on each side.
+    pub span: i64,
+}
+
+impl Kernel {
+    pub fn new(from: SampleRate, to: SampleRate) -> Self {
+        let (l, m) = from.ratio_to(to);
+        let cutoff = (to.get() as f64 / from.get() as f64).min(1.0);
+        let half = ZEROS / cutoff;
+        Self {
+            l: l as u64,
+            m: m as u64,
+            cutoff,
+            half,
+            span: half.ceil() as i64,
+        }
+    }
+
+    ///Returns output frames of input frames.
+    pub fn frames(&self, n: usize) -> usize {
+        (n as u64 * self.m).div_ceil(self.l) as usize
+    }
+
+    fn tap(&self, d: f64) -> f64 {
+        if d.abs() >= self.half {
+            return 0.0;
+        }
+        let a = self.cutoff * d;
+        let s = if a.abs() > 1e-9 {
+            (PI * a).sin() / (PI * a)
+        } else {
+            1.0
+        };
+        self.cutoff * s * (0.5 + 0.5 * (PI * d / self.half).cos())
+    }
+
+    ///Interpolates output frames of a channel, starting at output frame start.
+    pub fn run(&self, x: &[f32], y: &mut [f32], start: usize) {
+        for (i, o) in (start..).zip(y.iter_mut()) {
+            let (q, r) = (i as u64 / self.m, i as u64 % self.m);
+            let base = (q * self.l + r * self.l / self.m) as i64;
+            let frac = ((r * self.l) % self.m) as f64 / self.m as f64;
+            let a = (base - self.span + 1).max(0);
+            let b = (base + self.span).min(x.len() as i64 - 1);
+            let mut acc = 0.0;
+            for j in a..=b {
+                acc += x[j as usize] as f64 * self.tap(frac - (j - base) as f64);
+            }
+            *o = acc as f32;
+        }
+    }
+}
+
+///Returns planar channels of frames.
+pub(crate) fn planar<T: Frame>(o: &[T]) -> Vec<Vec<f32>> {
+    (0..T::CHANNEL_SIZE as usize)
+        .map(|n| o.iter().map(|i| i.channel(n)).collect())
+        .collect()
+}
+
+///Resamples planar channels, on the GPU when available and worthwhile, reporting output frames done and total to options and checking its cancellation before each block.
+pub(crate) fn resample_planar(
+    x: &[Vec<f32>],
+    from: SampleRate,
+    to: SampleRate,
+    options: &mut RunOptions,
+) -> Result<Vec<Vec<f32>>, Error> {
+    let k = Kernel::new(from, to);
+    let n = k.frames(x.first().map_or(0, |o| o.len()));
+    #[cfg(feature = "gpu")]
+    if n * x.len() >= GPU_MIN {
+        options.check()?;
+        if let Some(v) = crate::gpu::resample(x, &k, n) {
+            options.report(n, n);
+            return Ok(v);
+        }
+    }
+    let mut y = vec![vec![0.0; n]; x.len()];
+    for start in (0..n).step_by(PROGRESS_FRAMES) {
+        options.check()?;
+        let end = (start + PROGRESS_FRAMES).min(n);
+        for (x, y) in x.iter().zip(y.iter_mut()) {
+            k.run(x, &mut y[start..end], start);
+        }
+        options.report(end, n);
+    }
+    Ok(y)
+}
+
+///Returns whole resampled to sample rate by windowed sinc interpolation, band-limited when downsampling.
+pub fn resample<T: Frame>(o: &Whole<T>, sample_rate: SampleRate) -> Result<Whole<T>, Error> {
+    resample_with(o, sample_rate, &mut RunOptions::new())
+}
+
+///Resamples like `resample`, reporting output frames done and total to options after each block and failing with Error::Cancelled once its cancellation is observed between blocks.
+pub fn resample_with<T: Frame>(
+    o: &Whole<T>,
+    sample_rate: SampleRate,
+    options: &mut RunOptions,
+) -> Result<Whole<T>, Error> {
+    options.check()?;
+    if o.sample_rate() == sample_rate {
+        options.report(o.len(), o.len());
+        return Ok(Whole::from_block(sample_rate, Block::from(o.to_vec())));
+    }
+    let y = resample_planar(&planar(o), o.sample_rate(), sample_rate, options)?;
+    let n = y.first().map_or(0, |c| c.len());
+    let mut v = Block::new(n);
+    for i in 0..n {
+        let mut f = T::default();
+        for (c, s) in y.iter().enumerate() {
+            f.set_channel(c, s[i]);
+        }
+        v.push(f);
+    }
+    Ok(Whole::from_block(sample_rate, v))
+}
+
+impl<T> Whole<T>
+where
+    T: Frame,
+{
+    ///Returns self played faster by factor like tape, scaling both duration and pitch, rendered through the resampler. The scaled sample rate is rounded to an integer. Fails if factor is not positive and finite, or with Error::InvalidSampleRate if the scaled rate is out of range.
+    pub fn change_speed(&self, factor: f32) -> Result<Whole<T>, Error> {
+        if !(factor > 0.0 && factor.is_finite()) {
+            return Err(Error::InvalidArgument("factor not positive"));
+        }
+        let sr = self.sample_rate();
+        let from = SampleRate::new((sr.get() as f64 * factor as f64).round() as u32)?;
+        let mut o = resample(&Whole::from_block(from, Block::from(self.to_vec())), sr)?;
+        *o.markers_mut() = self.markers().to_vec();
+        for m in o.markers_mut() {
+            m.frame = (m.frame as f64 * sr.get() as f64 / from.get() as f64).round() as usize;
+        }
+        Ok(o)
+    }
+}
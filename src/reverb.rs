@@ -0,0 +1,141 @@
+use crate::format::*;
+
+const COMBS: [usize; 8] = [1116, 1188, 1277, 1356, 1422, 1491, 1557, 1617];
+const ALLPASSES: [usize; 4] = [556, 441, 341, 225];
+const SPREAD: usize = 23;
+//tunings above are of 44100 Hz.
+const TUNING_RATE: f32 = 44100.0;
+
+#[derive(Clone, Debug)]
+struct Comb {
+    buffer: Vec<f32>,
+    pos: usize,
+    store: f32,
+}
+
+impl Comb {
+    fn new(n: usize) -> Self {
+        Self {
+            buffer: vec![0.0; n.max(1)],
+            pos: 0,
+            store: 0.0,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.buffer.fill(0.0);
+        self.pos = 0;
+        self.store = 0.0;
+    }
+
+    fn tick(&mut self, x: f32, feedback: f32, damp: f32) -> f32 {
+        let y = self.buffer[self.pos];
+        self.store = y * (1.0 - damp) + self.store * damp;
+        self.buffer[self.pos] = x + self.store * feedback;
+        self.pos = (self.pos + 1) % self.buffer.len();
+        y
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Allpass {
+    buffer: Vec<f32>,
+    pos: usize,
+}
+
+impl Allpass {
+    fn new(n: usize) -> Self {
+        Self {
+            buffer: vec![0.0; n.max(1)],
+            pos: 0,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.buffer.fill(0.0);
+        self.pos = 0;
+    }
+
+    fn tick(&mut self, x: f32) -> f32 {
+        let b = self.buffer[self.pos];
+        self.buffer[self.pos] = x + b * 0.5;
+        self.pos = (self.pos + 1) % self.buffer.len();
+        b - x
+    }
+}
+
+///A Schroeder/Freeverb style stereo reverb.
+#[derive(Clone, Debug)]
+pub struct Reverb {
+    room_size: f32,
+    damping: f32,
+    width: f32,
+    mix: f32,
+    combs: [Vec<Comb>; 2],
+    allpasses: [Vec<Allpass>; 2],
+}
+
+impl Reverb {
+    ///Constructs a new Reverb. Room size, damping, width and mix (wet proportion) are from 0 to 1.
+    pub fn new(sample_rate: u32, room_size: f32, damping: f32, width: f32, mix: f32) -> Self {
+        let scale = |n: usize| (n as f32 * sample_rate as f32 / TUNING_RATE) as usize;
+        let combs = [0, SPREAD].map(|s| COMBS.iter().map(|n| Comb::new(scale(n + s))).collect());
+        let allpasses = [0, SPREAD].map(|s| {
+            ALLPASSES
+                .iter()
+                .map(|n| Allpass::new(scale(n + s)))
+                .collect()
+        });
+        Self {
+            room_size: room_size.clamp(0.0, 1.0),
+            damping: damping.clamp(0.0, 1.0),
+            width: width.clamp(0.0, 1.0),
+            mix: mix.clamp(0.0, 1.0),
+            combs,
+            allpasses,
+        }
+    }
+
+    ///Clears the internal state.
+    pub fn reset(&mut self) {
+        for f in self.combs.iter_mut().flatten() {
+            f.reset();
+        }
+        for f in self.allpasses.iter_mut().flatten() {
+            f.reset();
+        }
+    }
+
+    ///Processes block in place.
+    pub fn process(&mut self, o: &mut Block<[f32; 2]>) {
+        let wet = 3.0 * self.mix;
+        let wet1 = wet * (self.width / 2.0 + 0.5);
+        let wet2 = wet * ((1.0 - self.width) / 2.0);
+        let dry = 1.0 - self.mix;
+        let feedback = 0.7 + 0.28 * self.room_size;
+        let damp = 0.4 * self.damping;
+        for i in o.iter_mut() {
+            let x = (i[0] + i[1]) * 0.015;
+            let mut y = [0.0f32; 2];
+            for (c, v) in y.iter_mut().enumerate() {
+                for f in self.combs[c].iter_mut() {
+                    *v += f.tick(x, feedback, damp);
+                }
+                for f in self.allpasses[c].iter_mut() {
+                    *v = f.tick(*v);
+                }
+            }
+            *i = [
+                y[0] * wet1 + y[1] * wet2 + i[0] * dry,
+                y[1] * wet1 + y[0] * wet2 + i[1] * dry,
+            ];
+        }
+    }
+
+    ///Resets, appends tail frames of silence for the decay, and processes whole in place.
+    pub fn process_whole(&mut self, o: &mut Whole<[f32; 2]>, tail: usize) {
+        self.reset();
+        o.extend(std::iter::repeat_n([0.0; 2], tail));
+        self.process(o);
+    }
+}
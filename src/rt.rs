@@ -0,0 +1,60 @@
+//!Enforcement of allocation-free real-time sections.
+//!
+//!Install [`CountingAllocator`] as the global allocator, then wrap real-time code in [`assert_no_alloc`].
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+thread_local! {
+    static GUARDED: Cell<bool> = const { Cell::new(false) };
+    static COUNT: Cell<usize> = const { Cell::new(0) };
+}
+
+fn record() {
+    if GUARDED.with(|o| o.get()) {
+        COUNT.with(|o| o.set(o.get() + 1));
+    }
+}
+
+///A global allocator over System counting allocations made inside assert_no_alloc on each thread.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record();
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        record();
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record();
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        record();
+        System.dealloc(ptr, layout)
+    }
+}
+
+///Runs f and returns the number of allocations, reallocations and deallocations it made on this thread. Always 0 unless CountingAllocator is the global allocator.
+pub fn count_allocations<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    let guarded = GUARDED.with(|o| o.replace(true));
+    let before = COUNT.with(|o| o.get());
+    let r = f();
+    let n = COUNT.with(|o| o.get()) - before;
+    GUARDED.with(|o| o.set(guarded));
+    (r, n)
+}
+
+///Runs f, panicking after it returns if it allocated, reallocated or deallocated on this thread.
+pub fn assert_no_alloc<R>(f: impl FnOnce() -> R) -> R {
+    let (r, n) = count_allocations(f);
+    assert!(n == 0, "{} allocations in real-time section", n);
+    r
+}
@@ -0,0 +1,72 @@
+//!RTP audio payloads L16 (RFC 3551) and L24 (RFC 3190), interleaved in network byte order.
+
+use crate::format::*;
+use crate::sample::*;
+
+///Returns frames of a packet of ptime in milliseconds.
+pub fn frames_per_packet(sample_rate: u32, ptime: f32) -> usize {
+    (sample_rate as f64 * ptime.max(0.0) as f64 / 1000.0).round() as usize
+}
+
+fn pack<T: Channels>(o: &[T], width: usize, f: impl Fn(T::Scalar) -> [u8; 4]) -> ByteBlock {
+    let c = T::CHANNEL_SIZE as usize;
+    let mut v = Vec::with_capacity(o.len() * c * width);
+    for i in o {
+        for n in 0..c {
+            v.extend_from_slice(&f(i.get_channel(n))[..width]);
+        }
+    }
+    ByteBlock::new(T::CHANNEL_SIZE, c * width, true, v)
+}
+
+fn unpack<T: Channels + Default>(
+    payload: &[u8],
+    width: usize,
+    f: impl Fn(&[u8]) -> T::Scalar,
+) -> Block<T> {
+    let c = T::CHANNEL_SIZE as usize;
+    let mut v = Block::new(payload.len() / (c * width));
+    for i in payload.chunks_exact(c * width) {
+        let mut o = T::default();
+        for (n, s) in i.chunks_exact(width).enumerate() {
+            o.put_channel(n, f(s));
+        }
+        v.push(o);
+    }
+    v
+}
+
+///Returns an L16 payload of frames.
+pub fn pack_l16<T: Channels<Scalar = i16>>(o: &[T]) -> ByteBlock {
+    pack(o, 2, |s| {
+        let b = s.to_be_bytes();
+        [b[0], b[1], 0, 0]
+    })
+}
+
+///Returns frames of an L16 payload, ignoring a trailing partial frame.
+pub fn unpack_l16<T: Channels<Scalar = i16> + Default>(payload: &[u8]) -> Block<T> {
+    unpack(payload, 2, |s| i16::from_be_bytes([s[0], s[1]]))
+}
+
+///Returns an L24 payload of frames, keeping the upper 24 bits of each sample.
+pub fn pack_l24<T: Channels<Scalar = i32>>(o: &[T]) -> ByteBlock {
+    pack(o, 3, |s| s.to_be_bytes())
+}
+
+///Returns frames of an L24 payload as the upper 24 bits of each sample, ignoring a trailing partial frame.
+pub fn unpack_l24<T: Channels<Scalar = i32> + Default>(payload: &[u8]) -> Block<T> {
+    unpack(payload, 3, |s| i32::from_be_bytes([s[0], s[1], s[2], 0]))
+}
+
+///Splits whole into L16 payloads of ptime in milliseconds, the last may be shorter.
+pub fn packetize_l16<T: Channels<Scalar = i16>>(o: &Whole<T>, ptime: f32) -> Vec<ByteBlock> {
+    let n = frames_per_packet(o.sample_rate().get(), ptime).max(1);
+    o.chunks(n).map(pack_l16).collect()
+}
+
+///Splits whole into L24 payloads of ptime in milliseconds, the last may be shorter.
+pub fn packetize_l24<T: Channels<Scalar = i32>>(o: &Whole<T>, ptime: f32) -> Vec<ByteBlock> {
+    let n = frames_per_packet(o.sample_rate().get(), ptime).max(1);
+    o.chunks(n).map(pack_l24).collect()
+}
@@ -8,6 +8,9 @@ pub trait Type {
 
     ///Returns the memory representation of self as a byte array in little-endian byte order.
     fn le_bytes(self) -> Vec<u8>;
+
+    ///Creates a value from the start of a byte array in native byte order. Panics if it is too short.
+    fn from_ne_slice(b: &[u8]) -> Self;
 }
 
 macro_rules! type_for {
@@ -24,6 +27,12 @@ macro_rules! type_for {
             fn le_bytes(self) -> Vec<u8> {
                 Vec::from(self.to_le_bytes())
             }
+
+            fn from_ne_slice(b: &[u8]) -> Self {
+                let mut a = [0; size_of::<$t>()];
+                a.copy_from_slice(&b[..size_of::<$t>()]);
+                <$t>::from_ne_bytes(a)
+            }
         }
     };
 }
@@ -62,6 +71,9 @@ pub trait Sample {
 
     ///Copies self into a new `Vec<u8>` as a byte array in little-endian byte order.
     fn copy_to_le_bytes(&self) -> Vec<u8>;
+
+    ///Creates a sample from the start of a byte array of byte size in native byte order. Panics if it is too short.
+    fn copy_from_ne_bytes(b: &[u8]) -> Self;
 }
 
 impl<T> Sample for T
@@ -83,6 +95,10 @@ where
     fn copy_to_le_bytes(&self) -> Vec<u8> {
         self.clone().le_bytes()
     }
+
+    fn copy_from_ne_bytes(b: &[u8]) -> Self {
+        T::from_ne_slice(b)
+    }
 }
 
 macro_rules! sample_array {
@@ -96,9 +112,11 @@ macro_rules! sample_array {
             const BYTE_SIZE: usize = size_of::<T>() * $n;
 
             fn copy_to_ne_bytes(&self) -> Vec<u8> {
-                let mut o = self.clone();
-                let ptr = o.as_mut_ptr() as *mut u8;
-                unsafe { Vec::from_raw_parts(ptr, Self::BYTE_SIZE, Self::BYTE_SIZE) }
+                let mut v = Vec::with_capacity(Self::BYTE_SIZE);
+                for i in self {
+                    v.extend_from_slice(&i.clone().ne_bytes())
+                }
+                v
             }
 
             fn copy_to_be_bytes(&self) -> Vec<u8> {
@@ -116,6 +134,10 @@ macro_rules! sample_array {
                 }
                 v
             }
+
+            fn copy_from_ne_bytes(b: &[u8]) -> Self {
+                std::array::from_fn(|n| T::from_ne_slice(&b[n * size_of::<T>()..]))
+            }
         }
     };
 }
@@ -133,3 +155,243 @@ sample_array!(6);
 sample_array!(7);
 
 sample_array!(8);
+
+///A trait for Frame of f32 sample.
+pub trait Frame: Sample + Copy + Default {
+    ///Returns the value of channel n.
+    fn channel(&self, n: usize) -> f32;
+
+    ///Sets the value of channel n.
+    fn set_channel(&mut self, n: usize, v: f32);
+
+    ///Returns the maximum absolute value of all channels.
+    fn peak(&self) -> f32 {
+        let mut m = 0.0f32;
+        for n in 0..Self::CHANNEL_SIZE as usize {
+            m = m.max(self.channel(n).abs());
+        }
+        m
+    }
+
+    ///Multiplies all channels by gain.
+    fn scale(&mut self, gain: f32) {
+        for n in 0..Self::CHANNEL_SIZE as usize {
+            self.set_channel(n, self.channel(n) * gain);
+        }
+    }
+}
+
+impl Frame for f32 {
+    fn channel(&self, _: usize) -> f32 {
+        *self
+    }
+
+    fn set_channel(&mut self, _: usize, v: f32) {
+        *self = v;
+    }
+}
+
+macro_rules! frame_array {
+    ($n:expr) => {
+        impl Frame for [f32; $n] {
+            fn channel(&self, n: usize) -> f32 {
+                self[n]
+            }
+
+            fn set_channel(&mut self, n: usize, v: f32) {
+                self[n] = v;
+            }
+        }
+    };
+}
+
+frame_array!(2);
+
+frame_array!(3);
+
+frame_array!(4);
+
+frame_array!(5);
+
+frame_array!(6);
+
+frame_array!(7);
+
+frame_array!(8);
+
+///A trait for Scalar value of sample.
+pub trait Scalar: Type + Copy + Default + PartialOrd {
+    ///Value of full scale.
+    const FULL_SCALE: f64;
+
+    ///Value of silence.
+    const ZERO: f64;
+
+    ///Maximum positive value normalized to full scale 1.0.
+    const MAX_UNIT: f64;
+
+    ///Discriminant of the kind of sample, distinct for each type and stable across versions.
+    const KIND: u8;
+
+    ///Converts self to f64.
+    fn to_f64(self) -> f64;
+
+    ///Converts f64 to Self, rounding and saturating for integer.
+    fn from_f64(v: f64) -> Self;
+
+    ///Returns the bit pattern of self, distinct for every distinct memory representation, such as +0.0 and -0.0.
+    fn to_bits(self) -> u64;
+
+    ///Returns the index of self in the ordered sequence of values, so adjacent values differ by 1: the value for integers, and the bits ordered by sign for floats with both zeros at 0.
+    fn ulp_index(self) -> i128;
+
+    ///Returns value normalized to full scale 1.0.
+    fn to_unit(self) -> f64 {
+        (self.to_f64() - Self::ZERO) / Self::FULL_SCALE
+    }
+
+    ///Converts value normalized to full scale 1.0 to Self.
+    fn from_unit(v: f64) -> Self {
+        Self::from_f64(v * Self::FULL_SCALE + Self::ZERO)
+    }
+}
+
+macro_rules! scalar_float {
+    ($t:ty, $i:ty, $kind:expr) => {
+        impl Scalar for $t {
+            const FULL_SCALE: f64 = 1.0;
+
+            const ZERO: f64 = 0.0;
+
+            const MAX_UNIT: f64 = 1.0;
+
+            const KIND: u8 = $kind;
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn from_f64(v: f64) -> Self {
+                v as $t
+            }
+
+            fn to_bits(self) -> u64 {
+                <$t>::to_bits(self) as u64
+            }
+
+            fn ulp_index(self) -> i128 {
+                let i = <$t>::to_bits(self) as $i;
+                (if i < 0 { <$i>::MIN - i } else { i }) as i128
+            }
+        }
+    };
+}
+
+macro_rules! scalar_int {
+    ($t:ty, $full:expr, $zero:expr, $kind:expr) => {
+        impl Scalar for $t {
+            const FULL_SCALE: f64 = $full;
+
+            const ZERO: f64 = $zero;
+
+            const MAX_UNIT: f64 = ($full - 1.0) / $full;
+
+            const KIND: u8 = $kind;
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn from_f64(v: f64) -> Self {
+                v.round() as $t
+            }
+
+            fn to_bits(self) -> u64 {
+                self as u64
+            }
+
+            fn ulp_index(self) -> i128 {
+                self as i128
+            }
+        }
+    };
+}
+
+scalar_float!(f32, i32, 9);
+
+scalar_float!(f64, i64, 10);
+
+scalar_int!(i8, 128.0, 0.0, 1);
+
+scalar_int!(i16, 32768.0, 0.0, 2);
+
+scalar_int!(i32, 2147483648.0, 0.0, 3);
+
+scalar_int!(i64, 9223372036854775808.0, 0.0, 4);
+
+scalar_int!(u8, 128.0, 128.0, 5);
+
+scalar_int!(u16, 32768.0, 32768.0, 6);
+
+scalar_int!(u32, 2147483648.0, 2147483648.0, 7);
+
+scalar_int!(u64, 9223372036854775808.0, 9223372036854775808.0, 8);
+
+///A trait for Channels of sample.
+pub trait Channels: Sample {
+    type Scalar: Scalar;
+
+    ///Returns the value of channel n.
+    fn get_channel(&self, n: usize) -> Self::Scalar;
+
+    ///Sets the value of channel n.
+    fn put_channel(&mut self, n: usize, v: Self::Scalar);
+}
+
+impl<T> Channels for T
+where
+    T: Scalar,
+{
+    type Scalar = T;
+
+    fn get_channel(&self, _: usize) -> T {
+        *self
+    }
+
+    fn put_channel(&mut self, _: usize, v: T) {
+        *self = v;
+    }
+}
+
+macro_rules! channels_array {
+    ($n:expr) => {
+        impl<T> Channels for [T; $n]
+        where
+            T: Scalar,
+        {
+            type Scalar = T;
+
+            fn get_channel(&self, n: usize) -> T {
+                self[n]
+            }
+
+            fn put_channel(&mut self, n: usize, v: T) {
+                self[n] = v;
+            }
+        }
+    };
+}
+
+channels_array!(2);
+
+channels_array!(3);
+
+channels_array!(4);
+
+channels_array!(5);
+
+channels_array!(6);
+
+channels_array!(7);
+
+channels_array!(8);
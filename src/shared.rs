@@ -0,0 +1,387 @@
+use crate::format::*;
+use crate::sample::*;
+use crate::units::*;
+use std::ops::{Deref, Range};
+use std::sync::Arc;
+
+///An immutable block shared by clones, with zero-copy sub-views and copy-on-write mutation.
+pub struct SharedBlock<T> {
+    data: Arc<Vec<T>>,
+    range: Range<usize>,
+}
+
+impl<T> Clone for SharedBlock<T> {
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data.clone(),
+            range: self.range.clone(),
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for SharedBlock<T>
+where
+    T: Sample,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("SharedBlock");
+        f.field("channel_size", &T::CHANNEL_SIZE)
+            .field("byte_size", &T::BYTE_SIZE)
+            .field("range", &self.range)
+            .field("refs", &Arc::strong_count(&self.data))
+            .finish()
+    }
+}
+
+//returns a sub-range of range, relative and clamped.
+fn sub(range: &Range<usize>, o: Range<usize>) -> Range<usize> {
+    let end = range.start + o.end.min(range.len());
+    (range.start + o.start).min(end)..end
+}
+
+impl<T> SharedBlock<T>
+where
+    T: Sample,
+{
+    ///Constructs a new SharedBlock taking block.
+    pub fn new(mut o: Block<T>) -> Self {
+        let n = o.len();
+        Self {
+            data: Arc::new(std::mem::take(&mut *o)),
+            range: 0..n,
+        }
+    }
+
+    ///Returns a view of frames in range relative to self, clamped, sharing the data.
+    pub fn slice(&self, range: Range<usize>) -> Self {
+        Self {
+            data: self.data.clone(),
+            range: sub(&self.range, range),
+        }
+    }
+
+    ///Returns true if both view the same data.
+    pub fn ptr_eq(&self, o: &Self) -> bool {
+        Arc::ptr_eq(&self.data, &o.data)
+    }
+
+    ///Returns true if no other clone shares the data.
+    pub fn is_unique(&self) -> bool {
+        Arc::strong_count(&self.data) == 1
+    }
+
+    ///Returns mutable frames, copying the viewed frames first if the data is shared or only partly viewed.
+    pub fn make_mut(&mut self) -> &mut [T]
+    where
+        T: Clone,
+    {
+        if !self.is_unique() || self.range.len() != self.data.len() {
+            self.data = Arc::new(self.data[self.range.clone()].to_vec());
+            self.range = 0..self.data.len();
+        }
+        Arc::get_mut(&mut self.data).expect("unique data")
+    }
+
+    ///Converts self into a Block, without copying if the data is unique and fully viewed.
+    pub fn into_block(self) -> Block<T>
+    where
+        T: Clone,
+    {
+        let range = self.range;
+        match Arc::try_unwrap(self.data) {
+            Ok(v) if range.len() == v.len() => Block::from(v),
+            Ok(v) => Block::from(v[range].to_vec()),
+            Err(o) => Block::from(o[range].to_vec()),
+        }
+    }
+}
+
+impl<T> Deref for SharedBlock<T>
+where
+    T: Sample,
+{
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        &self.data[self.range.clone()]
+    }
+}
+
+impl<T> From<Block<T>> for SharedBlock<T>
+where
+    T: Sample,
+{
+    fn from(o: Block<T>) -> Self {
+        Self::new(o)
+    }
+}
+
+///An immutable whole shared by clones, with zero-copy sub-views and copy-on-write mutation.
+pub struct SharedWhole<T> {
+    sample_rate: SampleRate,
+    data: SharedBlock<T>,
+}
+
+impl<T> Clone for SharedWhole<T> {
+    fn clone(&self) -> Self {
+        Self {
+            sample_rate: self.sample_rate,
+            data: self.data.clone(),
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for SharedWhole<T>
+where
+    T: Sample,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("SharedWhole");
+        f.field("sample_rate", &self.sample_rate)
+            .field("data", &self.data)
+            .finish()
+    }
+}
+
+impl<T> SharedWhole<T>
+where
+    T: Sample,
+{
+    ///Constructs a new SharedWhole taking whole.
+    pub fn new(o: Whole<T>) -> Self {
+        Self {
+            sample_rate: o.sample_rate(),
+            data: SharedBlock::new(Block::from(o)),
+        }
+    }
+
+    ///Returns sample rate.
+    pub fn sample_rate(&self) -> SampleRate {
+        self.sample_rate
+    }
+
+    ///Returns a view of frames in range relative to self, clamped, sharing the data.
+    pub fn slice(&self, range: Range<usize>) -> Self {
+        Self {
+            sample_rate: self.sample_rate,
+            data: self.data.slice(range),
+        }
+    }
+
+    ///Returns the shared block.
+    pub fn block(&self) -> &SharedBlock<T> {
+        &self.data
+    }
+
+    ///Returns mutable frames, copying the viewed frames first if the data is shared or only partly viewed.
+    pub fn make_mut(&mut self) -> &mut [T]
+    where
+        T: Clone,
+    {
+        self.data.make_mut()
+    }
+
+    ///Converts self into a Whole, without copying if the data is unique and fully viewed.
+    pub fn into_whole(self) -> Whole<T>
+    where
+        T: Clone,
+    {
+        Whole::from_block(self.sample_rate, self.data.into_block())
+    }
+}
+
+impl<T> Deref for SharedWhole<T>
+where
+    T: Sample,
+{
+    type Target = SharedBlock<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+impl<T> From<Whole<T>> for SharedWhole<T>
+where
+    T: Sample,
+{
+    fn from(o: Whole<T>) -> Self {
+        Self::new(o)
+    }
+}
+
+///A whole edited copy-on-write as a sequence of shared pieces, so edits materialize only the affected frames and clones are cheap variants.
+pub struct CowWhole<T> {
+    sample_rate: SampleRate,
+    pieces: Vec<SharedBlock<T>>,
+    len: usize,
+}
+
+impl<T> Clone for CowWhole<T> {
+    fn clone(&self) -> Self {
+        Self {
+            sample_rate: self.sample_rate,
+            pieces: self.pieces.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for CowWhole<T>
+where
+    T: Sample,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("CowWhole");
+        f.field("sample_rate", &self.sample_rate)
+            .field("pieces", &self.pieces.len())
+            .field("data_size", &self.len)
+            .finish()
+    }
+}
+
+impl<T> CowWhole<T>
+where
+    T: Sample + Clone,
+{
+    ///Constructs a new CowWhole viewing shared whole.
+    pub fn new(o: SharedWhole<T>) -> Self {
+        let len = o.len();
+        Self {
+            sample_rate: o.sample_rate,
+            pieces: if len > 0 { vec![o.data] } else { Vec::new() },
+            len,
+        }
+    }
+
+    ///Returns sample rate.
+    pub fn sample_rate(&self) -> SampleRate {
+        self.sample_rate
+    }
+
+    ///Returns number of frames.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    ///Returns true if there is no frame.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    ///Returns number of pieces.
+    pub fn pieces(&self) -> usize {
+        self.pieces.len()
+    }
+
+    //splits pieces at frame, clamped, returning the index of the piece starting there.
+    fn split(&mut self, at: usize) -> usize {
+        let mut start = 0;
+        for i in 0..self.pieces.len() {
+            let n = self.pieces[i].len();
+            if at == start {
+                return i;
+            }
+            if at < start + n {
+                let o = &self.pieces[i];
+                let (a, b) = (o.slice(0..at - start), o.slice(at - start..n));
+                self.pieces[i] = a;
+                self.pieces.insert(i + 1, b);
+                return i + 1;
+            }
+            start += n;
+        }
+        self.pieces.len()
+    }
+
+    //returns piece indexes covering range, clamped.
+    fn span(&mut self, range: Range<usize>) -> Range<usize> {
+        let end = range.end.min(self.len);
+        let start = range.start.min(end);
+        let a = self.split(start);
+        a..self.split(end)
+    }
+
+    ///Returns a clip of frames in range, clamped, sharing the data.
+    pub fn copy(&mut self, range: Range<usize>) -> Self {
+        let r = self.span(range);
+        let pieces = self.pieces[r].to_vec();
+        Self {
+            sample_rate: self.sample_rate,
+            len: pieces.iter().map(|o| o.len()).sum(),
+            pieces,
+        }
+    }
+
+    ///Removes frames in range, clamped, returning them as a clip.
+    pub fn cut(&mut self, range: Range<usize>) -> Self {
+        let r = self.span(range);
+        let pieces: Vec<_> = self.pieces.drain(r).collect();
+        let len = pieces.iter().map(|o| o.len()).sum();
+        self.len -= len;
+        Self {
+            sample_rate: self.sample_rate,
+            pieces,
+            len,
+        }
+    }
+
+    ///Inserts a clip at frame, clamped, sharing its data.
+    pub fn paste(&mut self, at: usize, clip: &Self) {
+        let i = self.split(at.min(self.len));
+        self.pieces.splice(i..i, clip.pieces.iter().cloned());
+        self.len += clip.len;
+    }
+
+    ///Applies f to frames in range, clamped, copying only those frames.
+    pub fn modify(&mut self, range: Range<usize>, mut f: impl FnMut(&mut [T])) {
+        let r = self.span(range);
+        for o in &mut self.pieces[r] {
+            f(o.make_mut());
+        }
+    }
+
+    ///Copies frames in range, clamped, into a Block.
+    pub fn read(&self, range: Range<usize>) -> Block<T> {
+        let end = range.end.min(self.len);
+        let start = range.start.min(end);
+        let mut v = Block::new(end - start);
+        let mut at = 0;
+        for o in &self.pieces {
+            let (a, b) = (start.max(at), end.min(at + o.len()));
+            if a < b {
+                v.extend_from_slice(&o[a - at..b - at]);
+            }
+            at += o.len();
+        }
+        v
+    }
+
+    ///Renders all frames into a Whole.
+    pub fn render(&self) -> Whole<T> {
+        Whole::from_block(self.sample_rate, self.read(0..self.len))
+    }
+}
+
+impl<T> CowWhole<T>
+where
+    T: Frame,
+{
+    ///Multiplies frames in range, clamped, by gain, copying only those frames.
+    pub fn gain(&mut self, range: Range<usize>, gain: f32) {
+        self.modify(range, |o| {
+            for i in o {
+                i.scale(gain);
+            }
+        });
+    }
+}
+
+impl<T> From<SharedWhole<T>> for CowWhole<T>
+where
+    T: Sample + Clone,
+{
+    fn from(o: SharedWhole<T>) -> Self {
+        Self::new(o)
+    }
+}
@@ -0,0 +1,115 @@
+//!Spectrum analysis of FFT.
+
+use crate::db::*;
+use crate::format::*;
+use crate::sample::*;
+use crate::window::*;
+use rustfft::num_complex::Complex;
+use rustfft::FftPlanner;
+
+///One-sided spectrum of a channel.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Spectrum {
+    sample_rate: u32,
+    fft_size: usize,
+    ///Amplitude of each bin, a full scale sine is 1.0.
+    pub magnitude: Vec<f32>,
+    ///Phase of each bin in radians.
+    pub phase: Vec<f32>,
+}
+
+impl Spectrum {
+    ///Returns sample rate.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    ///Returns FFT size.
+    pub fn fft_size(&self) -> usize {
+        self.fft_size
+    }
+
+    ///Returns number of bins, `fft_size / 2 + 1`.
+    pub fn bins(&self) -> usize {
+        self.magnitude.len()
+    }
+
+    ///Returns frequency width of a bin in Hz.
+    pub fn bin_width(&self) -> f32 {
+        self.sample_rate as f32 / self.fft_size as f32
+    }
+
+    ///Returns center frequency of bin k in Hz.
+    pub fn frequency(&self, k: usize) -> f32 {
+        k as f32 * self.bin_width()
+    }
+
+    ///Returns center frequencies of all bins in Hz.
+    pub fn frequencies(&self) -> Vec<f32> {
+        (0..self.bins()).map(|k| self.frequency(k)).collect()
+    }
+
+    ///Returns magnitude in dBFS.
+    pub fn magnitude_db(&self) -> Vec<Db> {
+        self.magnitude.iter().map(|o| Db::from_gain(*o)).collect()
+    }
+}
+
+///Returns spectrum of channel n of the first fft_size frames, zero padded if shorter.
+pub fn channel<T: Channels>(
+    o: &Block<T>,
+    n: usize,
+    sample_rate: u32,
+    fft_size: usize,
+    window: Window,
+) -> Spectrum {
+    let fft_size = fft_size.max(1);
+    let w = window.coefficients(fft_size);
+    let gain: f64 = w.iter().map(|o| *o as f64).sum();
+    let mut buf: Vec<Complex<f64>> = w
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            let x = o.get(i).map_or(0.0, |f| f.get_channel(n).to_unit());
+            Complex::new(x * *c as f64, 0.0)
+        })
+        .collect();
+    FftPlanner::new()
+        .plan_fft_forward(fft_size)
+        .process(&mut buf);
+    let bins = fft_size / 2 + 1;
+    let mut magnitude = Vec::with_capacity(bins);
+    let mut phase = Vec::with_capacity(bins);
+    for (k, c) in buf.iter().take(bins).enumerate() {
+        let one_sided = if k == 0 || 2 * k == fft_size {
+            1.0
+        } else {
+            2.0
+        };
+        magnitude.push((c.norm() * one_sided / gain) as f32);
+        phase.push(c.arg() as f32);
+    }
+    Spectrum {
+        sample_rate,
+        fft_size,
+        magnitude,
+        phase,
+    }
+}
+
+///Returns spectrum of each channel of the first fft_size frames.
+pub fn block<T: Channels>(
+    o: &Block<T>,
+    sample_rate: u32,
+    fft_size: usize,
+    window: Window,
+) -> Vec<Spectrum> {
+    (0..T::CHANNEL_SIZE as usize)
+        .map(|n| channel(o, n, sample_rate, fft_size, window))
+        .collect()
+}
+
+///Returns spectrum of each channel of the first fft_size frames of whole.
+pub fn whole<T: Channels>(o: &Whole<T>, fft_size: usize, window: Window) -> Vec<Spectrum> {
+    block(o, o.sample_rate().get(), fft_size, window)
+}
@@ -0,0 +1,135 @@
+use crate::format::*;
+use crate::sample::*;
+use crate::units::*;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Result, Seek, SeekFrom, Write};
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static SPILL_ID: AtomicUsize = AtomicUsize::new(0);
+
+///A whole which keeps at most a memory budget of recent frames in memory, spilling older frames to a temporary file.
+///
+///Frames are stored in native byte order, and the file is removed on drop.
+pub struct SpillWhole<T> {
+    sample_rate: SampleRate,
+    budget: usize,
+    memory: Block<T>,
+    spilled: usize,
+    file: Option<(File, PathBuf)>,
+}
+
+impl<T> std::fmt::Debug for SpillWhole<T>
+where
+    T: Sample,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("SpillWhole");
+        f.field("sample_rate", &self.sample_rate)
+            .field("budget", &self.budget)
+            .field("memory", &self.memory.len())
+            .field("spilled", &self.spilled)
+            .finish()
+    }
+}
+
+impl<T> SpillWhole<T>
+where
+    T: Sample + Clone + Default,
+{
+    ///Constructs a new, empty SpillWhole keeping at most budget bytes of frames in memory.
+    pub fn new(sample_rate: SampleRate, budget: usize) -> Self {
+        Self {
+            sample_rate,
+            budget: (budget / T::BYTE_SIZE).max(1),
+            memory: Block::new(0),
+            spilled: 0,
+            file: None,
+        }
+    }
+
+    ///Returns sample rate.
+    pub fn sample_rate(&self) -> SampleRate {
+        self.sample_rate
+    }
+
+    ///Returns number of frames.
+    pub fn len(&self) -> usize {
+        self.spilled + self.memory.len()
+    }
+
+    ///Returns true if there is no frame.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    ///Returns number of frames on disk.
+    pub fn spilled(&self) -> usize {
+        self.spilled
+    }
+
+    fn file(&mut self) -> Result<&mut File> {
+        if self.file.is_none() {
+            let id = SPILL_ID.fetch_add(1, Ordering::Relaxed);
+            let path = std::env::temp_dir().join(format!("bssf-spill-{}-{id}", std::process::id()));
+            let file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create_new(true)
+                .open(&path)?;
+            self.file = Some((file, path));
+        }
+        Ok(&mut self.file.as_mut().unwrap().0)
+    }
+
+    ///Add elements of other into self, spilling the oldest frames in memory down to half the budget once over budget.
+    pub fn add(&mut self, mut o: Block<T>) -> Result<()> {
+        self.memory.append(&mut o);
+        if self.memory.len() > self.budget {
+            let n = self.memory.len() - self.budget / 2;
+            self.file()?;
+            let (file, _) = self.file.as_mut().unwrap();
+            file.seek(SeekFrom::Start((self.spilled * T::BYTE_SIZE) as u64))?;
+            file.write_all(&self.memory.bytes_slice()[..n * T::BYTE_SIZE])?;
+            self.memory.drain(..n);
+            self.spilled += n;
+        }
+        Ok(())
+    }
+
+    ///Copies frames in range, clamped to the length, reading spilled frames from disk.
+    pub fn read(&mut self, range: Range<usize>) -> Result<Block<T>> {
+        let end = range.end.min(self.len());
+        let start = range.start.min(end);
+        let mut v = vec![T::default(); end - start];
+        let disk = self.spilled.min(end).saturating_sub(start);
+        if disk > 0 {
+            let file = self.file()?;
+            file.seek(SeekFrom::Start((start * T::BYTE_SIZE) as u64))?;
+            let mut b = vec![0; disk * T::BYTE_SIZE];
+            file.read_exact(&mut b)?;
+            for (o, b) in v.iter_mut().zip(b.chunks_exact(T::BYTE_SIZE)) {
+                *o = T::copy_from_ne_bytes(b);
+            }
+        }
+        let a = (start + disk).saturating_sub(self.spilled);
+        v[disk..].clone_from_slice(&self.memory[a..a + (end - start - disk)]);
+        Ok(Block::from(v))
+    }
+
+    ///Reads all frames into a Whole.
+    pub fn into_whole(mut self) -> Result<Whole<T>> {
+        let data = self.read(0..self.len())?;
+        Ok(Whole::from_block(self.sample_rate, data))
+    }
+}
+
+impl<T> Drop for SpillWhole<T> {
+    fn drop(&mut self) {
+        if let Some((file, path)) = self.file.take() {
+            drop(file);
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
@@ -0,0 +1,164 @@
+//!A wait-free single-producer single-consumer queue of preallocated slots.
+
+use std::cell::UnsafeCell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+struct Shared<X> {
+    slots: Box<[UnsafeCell<X>]>,
+    //count of slots read, only written by consumer.
+    head: AtomicUsize,
+    //count of slots written, only written by producer.
+    tail: AtomicUsize,
+}
+
+//each slot is accessed by only one side at a time, as ordered by head and tail.
+unsafe impl<X: Send> Sync for Shared<X> {}
+
+impl<X> Shared<X> {
+    fn len(&self) -> usize {
+        self.tail
+            .load(Ordering::Acquire)
+            .wrapping_sub(self.head.load(Ordering::Acquire))
+    }
+
+    fn slot(&self, i: usize) -> *mut X {
+        self.slots[i % self.slots.len()].get()
+    }
+}
+
+///Constructs a queue of capacity slots, each preallocated by init, such as `|| Block::<f32>::new(512)`.
+///
+///Neither side allocates or locks after construction, so either may run on a real-time thread.
+pub fn channel<X: Send>(
+    capacity: usize,
+    mut init: impl FnMut() -> X,
+) -> (Producer<X>, Consumer<X>) {
+    let slots = (0..capacity.max(1))
+        .map(|_| UnsafeCell::new(init()))
+        .collect();
+    let shared = Arc::new(Shared {
+        slots,
+        head: AtomicUsize::new(0),
+        tail: AtomicUsize::new(0),
+    });
+    (
+        Producer {
+            shared: shared.clone(),
+        },
+        Consumer { shared },
+    )
+}
+
+///The writing side of a queue.
+pub struct Producer<X> {
+    shared: Arc<Shared<X>>,
+}
+
+impl<X> std::fmt::Debug for Producer<X> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("Producer");
+        f.field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
+
+impl<X> Producer<X> {
+    ///Returns number of slots.
+    pub fn capacity(&self) -> usize {
+        self.shared.slots.len()
+    }
+
+    ///Returns number of slots waiting to be read.
+    pub fn len(&self) -> usize {
+        self.shared.len()
+    }
+
+    ///Returns true if no slot is waiting to be read.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    ///Returns true if no slot is free to write.
+    pub fn is_full(&self) -> bool {
+        self.len() == self.capacity()
+    }
+
+    ///Fills the next free slot in place and publishes it. Returns false without calling f if the queue is full.
+    pub fn push_with(&mut self, f: impl FnOnce(&mut X)) -> bool {
+        if self.is_full() {
+            return false;
+        }
+        let tail = self.shared.tail.load(Ordering::Relaxed);
+        //the slot is free and not visible to consumer until tail is published.
+        f(unsafe { &mut *self.shared.slot(tail) });
+        self.shared
+            .tail
+            .store(tail.wrapping_add(1), Ordering::Release);
+        true
+    }
+
+    ///Swaps x into the next free slot and publishes it, returning the slot's previous value for reuse. Returns x back if the queue is full.
+    pub fn push(&mut self, mut x: X) -> Result<X, X> {
+        if self.is_full() {
+            return Err(x);
+        }
+        self.push_with(|o| std::mem::swap(o, &mut x));
+        Ok(x)
+    }
+}
+
+///The reading side of a queue.
+pub struct Consumer<X> {
+    shared: Arc<Shared<X>>,
+}
+
+impl<X> std::fmt::Debug for Consumer<X> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("Consumer");
+        f.field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
+
+impl<X> Consumer<X> {
+    ///Returns number of slots.
+    pub fn capacity(&self) -> usize {
+        self.shared.slots.len()
+    }
+
+    ///Returns number of slots waiting to be read.
+    pub fn len(&self) -> usize {
+        self.shared.len()
+    }
+
+    ///Returns true if no slot is waiting to be read.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    ///Reads the oldest published slot in place and frees it. Returns false without calling f if the queue is empty.
+    pub fn pop_with(&mut self, f: impl FnOnce(&mut X)) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+        let head = self.shared.head.load(Ordering::Relaxed);
+        //the slot is published and not touched by producer until head is advanced.
+        f(unsafe { &mut *self.shared.slot(head) });
+        self.shared
+            .head
+            .store(head.wrapping_add(1), Ordering::Release);
+        true
+    }
+
+    ///Swaps x into the oldest published slot and frees it, returning the slot's value. Returns x back if the queue is empty.
+    pub fn pop(&mut self, mut x: X) -> Result<X, X> {
+        if self.is_empty() {
+            return Err(x);
+        }
+        self.pop_with(|o| std::mem::swap(o, &mut x));
+        Ok(x)
+    }
+}
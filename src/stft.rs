@@ -0,0 +1,170 @@
+//!Short-time Fourier transform.
+
+use crate::db::*;
+use crate::fft::*;
+use crate::format::*;
+use crate::sample::*;
+use crate::units::*;
+use crate::window::*;
+
+///Short-time Fourier transform of a channel. Frames are centered at multiples of hop.
+#[derive(Clone, Debug)]
+pub struct Stft {
+    sample_rate: SampleRate,
+    fft_size: usize,
+    hop: usize,
+    window: Window,
+    len: usize,
+    ///Frames of `fft_size / 2 + 1` bins.
+    pub frames: Vec<Vec<Complex>>,
+}
+
+impl Stft {
+    ///Transforms mono whole. FFT size is rounded up to a power of two.
+    pub fn new(o: &Whole<f32>, fft_size: usize, hop: usize, window: Window) -> Self {
+        Self::from_channel(o, 0, fft_size, hop, window)
+    }
+
+    ///Transforms channel n of whole. FFT size is rounded up to a power of two.
+    pub fn from_channel<T: Frame>(
+        o: &Whole<T>,
+        n: usize,
+        fft_size: usize,
+        hop: usize,
+        window: Window,
+    ) -> Self {
+        let x: Vec<f32> = o.iter().map(|i| i.channel(n)).collect();
+        Self::from_slice(o.sample_rate(), &x, fft_size, hop, window)
+    }
+
+    ///Transforms samples. FFT size is rounded up to a power of two.
+    pub fn from_slice(
+        sample_rate: SampleRate,
+        x: &[f32],
+        fft_size: usize,
+        hop: usize,
+        window: Window,
+    ) -> Self {
+        let fft_size = fft_size.max(2).next_power_of_two();
+        let hop = hop.max(1);
+        let half = fft_size / 2;
+        let w = window.coefficients(fft_size);
+        let count = x.len().div_ceil(hop) + 1;
+        let mut frames = Vec::with_capacity(count);
+        let mut buf = vec![Complex::default(); fft_size];
+        for t in 0..count {
+            for (k, b) in buf.iter_mut().enumerate() {
+                let v = (t * hop + k)
+                    .checked_sub(half)
+                    .and_then(|i| x.get(i))
+                    .map_or(0.0, |v| *v);
+                *b = Complex::new((v * w[k]) as f64, 0.0);
+            }
+            fft(&mut buf, false);
+            frames.push(buf[..=half].to_vec());
+        }
+        Self {
+            sample_rate,
+            fft_size,
+            hop,
+            window,
+            len: x.len(),
+            frames,
+        }
+    }
+
+    ///Returns sample rate.
+    pub fn sample_rate(&self) -> SampleRate {
+        self.sample_rate
+    }
+
+    ///Returns FFT size.
+    pub fn fft_size(&self) -> usize {
+        self.fft_size
+    }
+
+    ///Returns hop size in frames.
+    pub fn hop(&self) -> usize {
+        self.hop
+    }
+
+    ///Returns frame count of the transformed signal.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    ///Returns true if the transformed signal is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    ///Returns center frequency of bin k in Hz.
+    pub fn frequency(&self, k: usize) -> f32 {
+        k as f32 * self.sample_rate.get() as f32 / self.fft_size as f32
+    }
+
+    ///Returns center time of frame t in seconds.
+    pub fn time(&self, t: usize) -> f32 {
+        (t * self.hop) as f32 / self.sample_rate.get() as f32
+    }
+
+    ///Returns time by frequency matrix of magnitude.
+    pub fn magnitude(&self) -> Vec<Vec<f32>> {
+        self.frames
+            .iter()
+            .map(|f| f.iter().map(|c| c.norm() as f32).collect())
+            .collect()
+    }
+
+    ///Returns time by frequency matrix of magnitude in dB, relative to a full scale sine.
+    pub fn magnitude_db(&self) -> Vec<Vec<Db>> {
+        let gain: f32 = self.window.coefficients(self.fft_size).iter().sum::<f32>() / 2.0;
+        self.frames
+            .iter()
+            .map(|f| {
+                f.iter()
+                    .map(|c| Db::from_gain(c.norm() as f32 / gain))
+                    .collect()
+            })
+            .collect()
+    }
+
+    ///Inverse transform of weighted overlap-add, returns samples of the original length. Empty if there is no frame.
+    pub fn inverse_slice(&self) -> Vec<f32> {
+        if self.frames.is_empty() {
+            return Vec::new();
+        }
+        let n = self.fft_size;
+        let half = n / 2;
+        let w = self.window.coefficients(n);
+        let total = (self.frames.len() - 1) * self.hop + n;
+        let mut y = vec![0.0f64; total];
+        let mut norm = vec![0.0f64; total];
+        let mut buf = vec![Complex::default(); n];
+        for (t, f) in self.frames.iter().enumerate() {
+            for (k, b) in buf.iter_mut().enumerate() {
+                *b = if k <= half { f[k] } else { f[n - k].conj() };
+            }
+            fft(&mut buf, true);
+            for (k, b) in buf.iter().enumerate() {
+                let i = t * self.hop + k;
+                y[i] += b.re / n as f64 * w[k] as f64;
+                norm[i] += (w[k] * w[k]) as f64;
+            }
+        }
+        (half..half + self.len)
+            .map(|i| {
+                if norm[i] > 1e-9 {
+                    (y[i] / norm[i]) as f32
+                } else {
+                    0.0
+                }
+            })
+            .collect()
+    }
+
+    ///Inverse transform to a mono whole.
+    pub fn inverse(&self) -> Whole<f32> {
+        Whole::from_block(self.sample_rate, Block::from(self.inverse_slice()))
+    }
+}
@@ -0,0 +1,173 @@
+//!Framed streaming of byte blocks over Read and Write.
+//!
+//!Each frame is a 20-byte header followed by the data bytes. The header holds magic `BSSF`, sample rate (u32), channel size (u16), byte size (u16), flags (u8, bit 0 big-endian data, bit 1 checksum), 3 reserved bytes and data length (u32), in big-endian.
+//!
+//!With the checksum flag, the data is followed by CRC-32 (IEEE, u32 big-endian) of header and data, so corruption is an error at decode time.
+
+use crate::format::*;
+use crate::hash::Crc32;
+use crate::sample::*;
+use crate::units::SampleRate;
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+const MAGIC: [u8; 4] = *b"BSSF";
+
+///Size of frame header in bytes.
+pub const HEADER_SIZE: usize = 20;
+
+///Default maximum data length of a frame accepted by Reader.
+pub const MAX_FRAME: usize = 16 << 20;
+
+const BIG_ENDIAN: u8 = 1;
+
+const CHECKSUM: u8 = 2;
+
+///A writer of framed byte blocks.
+#[derive(Debug)]
+pub struct Writer<W> {
+    inner: W,
+    sample_rate: SampleRate,
+    checksum: bool,
+}
+
+impl<W: Write> Writer<W> {
+    ///Constructs a new Writer tagging frames with sample rate.
+    pub fn new(inner: W, sample_rate: SampleRate) -> Self {
+        Self {
+            inner,
+            sample_rate,
+            checksum: false,
+        }
+    }
+
+    ///Sets whether frames carry a CRC-32 of header and data, false by default.
+    pub fn set_checksum(&mut self, checksum: bool) {
+        self.checksum = checksum;
+    }
+
+    ///Writes a frame of byte block.
+    pub fn write(&mut self, o: &ByteBlock) -> Result<()> {
+        let len = u32::try_from(o.len())
+            .map_err(|_| Error::new(ErrorKind::InvalidInput, "frame too long"))?;
+        let byte_size = u16::try_from(o.byte_size())
+            .map_err(|_| Error::new(ErrorKind::InvalidInput, "byte size too large"))?;
+        let mut h = [0u8; HEADER_SIZE];
+        h[..4].copy_from_slice(&MAGIC);
+        h[4..8].copy_from_slice(&self.sample_rate.get().to_be_bytes());
+        h[8..10].copy_from_slice(&o.channel_size().to_be_bytes());
+        h[10..12].copy_from_slice(&byte_size.to_be_bytes());
+        h[12] = if o.is_big_endian() { BIG_ENDIAN } else { 0 };
+        if self.checksum {
+            h[12] |= CHECKSUM;
+        }
+        h[16..20].copy_from_slice(&len.to_be_bytes());
+        self.inner.write_all(&h)?;
+        self.inner.write_all(o)?;
+        if self.checksum {
+            let mut c = Crc32::new();
+            c.update(&h);
+            c.update(o);
+            self.inner.write_all(&c.finish().to_be_bytes())?;
+        }
+        Ok(())
+    }
+
+    ///Writes a frame of block in native byte order.
+    pub fn write_block<T: Sample>(&mut self, o: &Block<T>) -> Result<()> {
+        self.write(&ByteBlock::from_block(cfg!(target_endian = "big"), o))
+    }
+
+    ///Flushes the inner writer.
+    pub fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+
+    ///Returns the inner writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+///A reader of framed byte blocks.
+#[derive(Debug)]
+pub struct Reader<R> {
+    inner: R,
+    sample_rate: u32,
+    max_frame: usize,
+}
+
+impl<R: Read> Reader<R> {
+    ///Constructs a new Reader.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            sample_rate: 0,
+            max_frame: MAX_FRAME,
+        }
+    }
+
+    ///Sets maximum data length of a frame, longer frames are an error. MAX_FRAME by default.
+    pub fn set_max_frame(&mut self, max_frame: usize) {
+        self.max_frame = max_frame;
+    }
+
+    ///Returns sample rate of the last frame read.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    ///Reads a frame. Returns None at the end of stream before a header. A frame with a checksum that does not match, or a layout that does not divide its data, is an error of kind InvalidData.
+    pub fn read(&mut self) -> Result<Option<ByteBlock>> {
+        let mut h = [0u8; HEADER_SIZE];
+        let mut n = 0;
+        while n < HEADER_SIZE {
+            match self.inner.read(&mut h[n..]) {
+                Ok(0) if n == 0 => return Ok(None),
+                Ok(0) => return Err(ErrorKind::UnexpectedEof.into()),
+                Ok(k) => n += k,
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        if h[..4] != MAGIC {
+            return Err(Error::new(ErrorKind::InvalidData, "bad frame magic"));
+        }
+        let be = |i: usize| [h[i], h[i + 1]];
+        let channel_size = u16::from_be_bytes(be(8));
+        let byte_size = u16::from_be_bytes(be(10)) as usize;
+        let len = u32::from_be_bytes([h[16], h[17], h[18], h[19]]) as usize;
+        if len > self.max_frame {
+            return Err(Error::new(ErrorKind::InvalidData, "frame too long"));
+        }
+        let mut data = vec![0; len];
+        self.inner.read_exact(&mut data)?;
+        if h[12] & CHECKSUM != 0 {
+            let mut b = [0u8; 4];
+            self.inner.read_exact(&mut b)?;
+            let mut c = Crc32::new();
+            c.update(&h);
+            c.update(&data);
+            let found = c.finish();
+            let expected = u32::from_be_bytes(b);
+            if found != expected {
+                return Err(crate::error::Error::ChecksumMismatch { expected, found }.into());
+            }
+        }
+        let o = ByteBlock::try_new(channel_size, byte_size, h[12] & BIG_ENDIAN != 0, data)?;
+        self.sample_rate = u32::from_be_bytes([h[4], h[5], h[6], h[7]]);
+        Ok(Some(o))
+    }
+
+    ///Returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Iterator for Reader<R> {
+    type Item = Result<ByteBlock>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read().transpose()
+    }
+}
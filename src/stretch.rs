@@ -0,0 +1,156 @@
+use crate::cancel::*;
+use crate::error::*;
+use crate::format::*;
+use crate::resample::{planar, resample_planar};
+use crate::sample::*;
+use crate::units::*;
+use crate::util::*;
+use crate::window::*;
+
+///A WSOLA time stretcher changing duration while preserving pitch. Channels are processed independently.
+#[derive(Clone, Debug)]
+pub struct TimeStretch {
+    sample_rate: SampleRate,
+    window: usize,
+    search: usize,
+}
+
+impl TimeStretch {
+    ///Constructs a new TimeStretch of a 40 ms window and a 10 ms search.
+    pub fn new(sample_rate: SampleRate) -> Self {
+        let mut o = Self {
+            sample_rate,
+            window: 0,
+            search: 0,
+        };
+        o.set_window_ms(40.0);
+        o.set_search_ms(10.0);
+        o
+    }
+
+    ///Sets analysis window in ms, 40 by default. Longer windows suit music and shorter ones speech.
+    pub fn set_window_ms(&mut self, ms: f32) {
+        self.window = (ms_to_frames(ms, self.sample_rate.get()) & !1).max(4);
+    }
+
+    ///Sets tolerance of the similarity search in ms, 10 by default. A wider search aligns lower frequencies at more cost.
+    pub fn set_search_ms(&mut self, ms: f32) {
+        self.search = ms_to_frames(ms, self.sample_rate.get());
+    }
+
+    ///Returns analysis window in frames.
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    ///Returns tolerance of the similarity search in frames.
+    pub fn search(&self) -> usize {
+        self.search
+    }
+
+    //stretches a channel to out frames.
+    fn channel(&self, x: &[f32], factor: f64, out: usize) -> Vec<f32> {
+        let n = self.window;
+        let hs = n / 2;
+        let ha = hs as f64 / factor;
+        let w = Window::Hann.coefficients(n);
+        //half a window of silence ahead, so the first output frames are fully overlapped.
+        let frames = out / hs + 2;
+        let len = (frames as f64 * ha).ceil() as usize + n + self.search + hs;
+        let mut xp = vec![0.0; hs + len.max(x.len())];
+        xp[hs..hs + x.len()].copy_from_slice(x);
+        let mut y = vec![0.0; frames * hs + n];
+        let mut prev: Option<usize> = None;
+        for k in 0..frames {
+            let nominal = (k as f64 * ha).round() as usize;
+            let q = match prev {
+                None => nominal,
+                Some(p) => {
+                    //the natural continuation of the previous segment is the template.
+                    let t = &xp[p + hs..p + n];
+                    let lo = nominal.saturating_sub(self.search);
+                    let hi = (nominal + self.search).min(xp.len() - n);
+                    let mut best = (f32::NEG_INFINITY, nominal.min(hi));
+                    for q in lo..=hi {
+                        let c = &xp[q..q + hs];
+                        let (mut s, mut e) = (0.0, 0.0);
+                        for (a, b) in t.iter().zip(c) {
+                            s += a * b;
+                            e += b * b;
+                        }
+                        let score = s / (e + 1e-9f32).sqrt();
+                        if score > best.0 {
+                            best = (score, q);
+                        }
+                    }
+                    best.1
+                }
+            };
+            for (i, (o, g)) in y[k * hs..k * hs + n].iter_mut().zip(&w).enumerate() {
+                *o += g * xp[q + i];
+            }
+            prev = Some(q);
+        }
+        y[hs..hs + out].to_vec()
+    }
+
+    ///Returns whole stretched to factor times its duration, preserving pitch. Fails if factor is not positive and finite.
+    pub fn process<T: Frame>(&self, o: &Whole<T>, factor: f32) -> Result<Whole<T>, Error> {
+        if !(factor > 0.0 && factor.is_finite()) {
+            return Err(Error::InvalidArgument("factor not positive"));
+        }
+        let out = (o.len() as f64 * factor as f64).round() as usize;
+        let mut v = Block::from(vec![T::default(); out]);
+        if o.is_empty() {
+            return Ok(Whole::from_block(o.sample_rate(), v));
+        }
+        for (c, x) in planar(o).iter().enumerate() {
+            let y = self.channel(x, factor as f64, out);
+            for (f, s) in v.iter_mut().zip(y) {
+                f.set_channel(c, s);
+            }
+        }
+        Ok(Whole::from_block(o.sample_rate(), v))
+    }
+
+    ///Returns whole shifted in pitch by semitones plus cents, of the same duration, by stretching and resampling back. Fails with Error::InvalidSampleRate if the scaled sample rate is out of range.
+    pub fn pitch_shift<T: Frame>(
+        &self,
+        o: &Whole<T>,
+        semitones: f32,
+        cents: f32,
+    ) -> Result<Whole<T>, Error> {
+        let sr = o.sample_rate();
+        let ratio = 2f64.powf((semitones as f64 * 100.0 + cents as f64) / 1200.0);
+        let from = (sr.get() as f64 * ratio).round() as u32;
+        if from == sr.get() || from == 0 || o.is_empty() {
+            return Ok(Whole::from_block(sr, Block::from(o.to_vec())));
+        }
+        let from = SampleRate::new(from)?;
+        //the stretched frames played at the scaled rate keep the duration and scale the pitch.
+        let ratio = from.get() as f64 / sr.get() as f64;
+        let out = (o.len() as f64 * ratio).round() as usize;
+        let x: Vec<Vec<f32>> = planar(o)
+            .iter()
+            .map(|x| self.channel(x, ratio, out))
+            .collect();
+        let y = resample_planar(&x, from, sr, &mut RunOptions::new())?;
+        let mut v = Block::from(vec![T::default(); o.len()]);
+        for (c, y) in y.iter().enumerate() {
+            for (f, s) in v.iter_mut().zip(y) {
+                f.set_channel(c, *s);
+            }
+        }
+        Ok(Whole::from_block(sr, v))
+    }
+}
+
+///Returns whole shifted in pitch by semitones plus cents, of the same duration, by a TimeStretch of default settings.
+pub fn pitch_shift<T: Frame>(o: &Whole<T>, semitones: f32, cents: f32) -> Result<Whole<T>, Error> {
+    TimeStretch::new(o.sample_rate()).pitch_shift(o, semitones, cents)
+}
+
+///Returns whole stretched to factor times its duration, preserving pitch, by a TimeStretch of default settings. Fails if factor is not positive and finite.
+pub fn time_stretch<T: Frame>(o: &Whole<T>, factor: f32) -> Result<Whole<T>, Error> {
+    TimeStretch::new(o.sample_rate()).process(o, factor)
+}
@@ -0,0 +1,83 @@
+//reverses bytes of each sample of width in place, returns bytes processed from the start.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3")]
+unsafe fn swap_ssse3(data: &mut [u8], width: usize) -> usize {
+    use std::arch::x86_64::*;
+    let (mask, step): ([u8; 16], usize) = match width {
+        2 => ([1, 0, 3, 2, 5, 4, 7, 6, 9, 8, 11, 10, 13, 12, 15, 14], 16),
+        //5 samples of 24 bits, the last byte is kept.
+        3 => ([2, 1, 0, 5, 4, 3, 8, 7, 6, 11, 10, 9, 14, 13, 12, 15], 15),
+        4 => ([3, 2, 1, 0, 7, 6, 5, 4, 11, 10, 9, 8, 15, 14, 13, 12], 16),
+        8 => ([7, 6, 5, 4, 3, 2, 1, 0, 15, 14, 13, 12, 11, 10, 9, 8], 16),
+        _ => return 0,
+    };
+    let m = _mm_loadu_si128(mask.as_ptr() as *const __m128i);
+    let mut i = 0;
+    while i + 16 <= data.len() {
+        let p = data.as_mut_ptr().add(i) as *mut __m128i;
+        _mm_storeu_si128(p, _mm_shuffle_epi8(_mm_loadu_si128(p), m));
+        i += step;
+    }
+    i
+}
+
+///Reverses bytes of each sample of width in place, converting between big-endian and little-endian. A trailing partial sample is left as it is.
+pub(crate) fn swap_bytes(data: &mut [u8], width: usize) {
+    if width < 2 {
+        return;
+    }
+    #[cfg(feature = "rayon")]
+    if data.len() >= crate::par::PAR_LEN * width {
+        use rayon::prelude::*;
+        data.par_chunks_mut(crate::par::PAR_LEN * width)
+            .for_each(|o| swap_serial(o, width));
+        return;
+    }
+    swap_serial(data, width);
+}
+
+fn swap_serial(data: &mut [u8], width: usize) {
+    #[allow(unused_mut)]
+    let mut i = 0;
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("ssse3") {
+        i = unsafe { swap_ssse3(data, width) };
+    }
+    for o in data[i..].chunks_exact_mut(width) {
+        o.reverse();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn swap_matches_scalar() {
+        let src: Vec<u8> = (0..83u8).map(|x| x.wrapping_mul(37) ^ 0x5A).collect();
+        for width in 1..=8 {
+            for start in 0..4 {
+                for end in start..=src.len() {
+                    //swaps in place so the start is unaligned.
+                    let mut a = src.clone();
+                    swap_serial(&mut a[start..end], width);
+                    let mut b = src.clone();
+                    for o in b[start..end].chunks_exact_mut(width) {
+                        o.reverse();
+                    }
+                    assert_eq!(a, b, "width {} of {}..{}", width, start, end);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn swap_twice() {
+        let src: Vec<u8> = (0..=255).collect();
+        let mut a = src.clone();
+        swap_bytes(&mut a, 3);
+        assert_ne!(a, src);
+        swap_bytes(&mut a, 3);
+        assert_eq!(a, src);
+    }
+}
@@ -0,0 +1,187 @@
+use crate::edit::*;
+use crate::format::*;
+use crate::sample::*;
+use crate::units::*;
+
+///A clip of an edit placed at a frame of a lane.
+pub struct Clip<T> {
+    pub position: usize,
+    pub edit: Edit<T>,
+}
+
+impl<T> Clone for Clip<T> {
+    fn clone(&self) -> Self {
+        Self {
+            position: self.position,
+            edit: self.edit.clone(),
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for Clip<T>
+where
+    T: Sample,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("Clip");
+        f.field("position", &self.position)
+            .field("edit", &self.edit)
+            .finish()
+    }
+}
+
+impl<T> Clip<T>
+where
+    T: Sample,
+{
+    ///Constructs a new Clip.
+    pub fn new(position: usize, edit: Edit<T>) -> Self {
+        Self { position, edit }
+    }
+
+    ///Returns the frame after the clip.
+    pub fn end(&self) -> usize {
+        self.position + self.edit.len()
+    }
+}
+
+///Resolution of overlapping clips in a lane.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Overlap {
+    ///Sums overlapping clips.
+    Mix,
+    ///Clips added later cover earlier ones.
+    Latest,
+}
+
+///An arrangement of clips at absolute frames across lanes, rendered by summing lanes.
+pub struct Timeline<T> {
+    sample_rate: SampleRate,
+    overlap: Overlap,
+    lanes: Vec<Vec<Clip<T>>>,
+}
+
+impl<T> Clone for Timeline<T> {
+    fn clone(&self) -> Self {
+        Self {
+            sample_rate: self.sample_rate,
+            overlap: self.overlap,
+            lanes: self.lanes.clone(),
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for Timeline<T>
+where
+    T: Sample,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("Timeline");
+        f.field("sample_rate", &self.sample_rate)
+            .field("overlap", &self.overlap)
+            .field("lanes", &self.lanes)
+            .finish()
+    }
+}
+
+impl<T> Timeline<T>
+where
+    T: Sample,
+{
+    ///Constructs a new, empty Timeline rendering at sample rate, resolving overlaps in a lane by overlap.
+    pub fn new(sample_rate: SampleRate, overlap: Overlap) -> Self {
+        Self {
+            sample_rate,
+            overlap,
+            lanes: Vec::new(),
+        }
+    }
+
+    ///Returns sample rate.
+    pub fn sample_rate(&self) -> SampleRate {
+        self.sample_rate
+    }
+
+    ///Returns resolution of overlapping clips in a lane.
+    pub fn overlap(&self) -> Overlap {
+        self.overlap
+    }
+
+    ///Sets resolution of overlapping clips in a lane.
+    pub fn set_overlap(&mut self, overlap: Overlap) {
+        self.overlap = overlap;
+    }
+
+    ///Adds a clip to lane, adding lanes up to it.
+    pub fn add(&mut self, lane: usize, o: Clip<T>) {
+        if self.lanes.len() <= lane {
+            self.lanes.resize_with(lane + 1, Vec::new);
+        }
+        self.lanes[lane].push(o);
+    }
+
+    ///Returns lanes of clips in order of addition.
+    pub fn lanes(&self) -> &[Vec<Clip<T>>] {
+        &self.lanes
+    }
+
+    ///Returns mutable lanes of clips.
+    pub fn lanes_mut(&mut self) -> &mut Vec<Vec<Clip<T>>> {
+        &mut self.lanes
+    }
+
+    ///Returns the frame after the last clip.
+    pub fn len(&self) -> usize {
+        self.lanes
+            .iter()
+            .flatten()
+            .map(|o| o.end())
+            .max()
+            .unwrap_or(0)
+    }
+
+    ///Returns true if there is no frame.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+//adds channels of b to a.
+fn mix<T: Frame>(a: &mut T, b: &T) {
+    for n in 0..T::CHANNEL_SIZE as usize {
+        a.set_channel(n, a.channel(n) + b.channel(n));
+    }
+}
+
+impl<T> Timeline<T>
+where
+    T: Frame,
+{
+    ///Renders all lanes into a new Whole from frame 0.
+    pub fn render(&self) -> Whole<T> {
+        let n = self.len();
+        let mut v = Block::from(vec![T::default(); n]);
+        let mut lane = vec![T::default(); n];
+        for clips in &self.lanes {
+            lane.fill(T::default());
+            for o in clips {
+                let e = &o.edit;
+                let k = e.len();
+                let start = e.range.start.min(e.source.len());
+                let dst = &mut lane[o.position..o.position + k];
+                for (i, (d, s)) in dst.iter_mut().zip(&e.source[start..start + k]).enumerate() {
+                    let mut s = *s;
+                    s.scale(e.gain_at(i, k));
+                    match self.overlap {
+                        Overlap::Mix => mix(d, &s),
+                        Overlap::Latest => *d = s,
+                    }
+                }
+            }
+            for (a, b) in v.iter_mut().zip(&lane) {
+                mix(a, b);
+            }
+        }
+        Whole::from_block(self.sample_rate, v)
+    }
+}
@@ -0,0 +1,316 @@
+use crate::cancel::*;
+use crate::convert::*;
+use crate::dither::*;
+use crate::error::*;
+use crate::format::*;
+use crate::resample::*;
+use crate::sample::*;
+use crate::units::*;
+use crate::util::*;
+use std::ops::Deref;
+
+///Sample format of converted bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SampleFormat {
+    U8,
+    I16,
+    ///24 bit integer packed in 3 bytes.
+    I24,
+    I32,
+    F32,
+    F64,
+}
+
+impl SampleFormat {
+    ///Returns bytes of a sample.
+    pub fn bytes(self) -> usize {
+        match self {
+            Self::U8 => 1,
+            Self::I16 => 2,
+            Self::I24 => 3,
+            Self::I32 | Self::F32 => 4,
+            Self::F64 => 8,
+        }
+    }
+
+    ///Returns bits of an integer format, None for float.
+    pub fn bits(self) -> Option<u32> {
+        match self {
+            Self::U8 => Some(8),
+            Self::I16 => Some(16),
+            Self::I24 => Some(24),
+            Self::I32 => Some(32),
+            Self::F32 | Self::F64 => None,
+        }
+    }
+
+    //appends a sample normalized to full scale 1.0 in byte order.
+    fn put(self, v: f64, big_endian: bool, o: &mut Vec<u8>) {
+        let n = o.len();
+        match self {
+            Self::U8 => o.push(u8::from_unit(v)),
+            Self::I16 => o.extend_from_slice(&i16::from_unit(v).to_le_bytes()),
+            Self::I24 => {
+                let s = (v * 8388608.0).round().clamp(-8388608.0, 8388607.0) as i32;
+                o.extend_from_slice(&s.to_le_bytes()[..3]);
+            }
+            Self::I32 => o.extend_from_slice(&i32::from_unit(v).to_le_bytes()),
+            Self::F32 => o.extend_from_slice(&(v as f32).to_le_bytes()),
+            Self::F64 => o.extend_from_slice(&v.to_le_bytes()),
+        }
+        if big_endian {
+            o[n..].reverse();
+        }
+    }
+}
+
+//writes samples in a format, applying scaling and dither.
+struct Encoder {
+    format: SampleFormat,
+    big_endian: bool,
+    scaling: ScalingConvention,
+    dither: Option<Ditherer>,
+}
+
+impl Encoder {
+    fn new(
+        format: SampleFormat,
+        big_endian: bool,
+        dither: Dither,
+        scaling: ScalingConvention,
+        channel_size: u16,
+    ) -> Self {
+        Self {
+            format,
+            big_endian,
+            scaling,
+            dither: format
+                .bits()
+                .filter(|_| dither != Dither::None)
+                .map(|bits| Ditherer::new(dither, bits, channel_size)),
+        }
+    }
+
+    //appends v of channel.
+    fn put(&mut self, channel: usize, v: f32, o: &mut Vec<u8>) {
+        let mut s = v as f64;
+        //integer formats are written in the Asymmetric convention, on whose steps dither lands.
+        if let Some(bits) = self.format.bits() {
+            s = self.scaling.to_asymmetric(s, bits);
+            if let Some(d) = &mut self.dither {
+                s = d
+                    .quantize(channel, s)
+                    .max(self.scaling.min_asymmetric(bits));
+            }
+        }
+        self.format.put(s, self.big_endian, o);
+    }
+}
+
+///Target of conversion.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TargetSpec {
+    pub sample_rate: SampleRate,
+    pub format: SampleFormat,
+    pub big_endian: bool,
+    ///Dither of integer formats, ignored for float.
+    pub dither: Dither,
+    ///Scaling of integer formats, ignored for float.
+    pub scaling: ScalingConvention,
+}
+
+impl TargetSpec {
+    ///Constructs a new TargetSpec without dither, in the Asymmetric scaling convention.
+    pub fn new(sample_rate: SampleRate, format: SampleFormat, big_endian: bool) -> Self {
+        Self {
+            sample_rate,
+            format,
+            big_endian,
+            dither: Dither::None,
+            scaling: ScalingConvention::Asymmetric,
+        }
+    }
+}
+
+impl<T> Whole<T>
+where
+    T: Frame,
+{
+    ///Converts self to the sample rate and format of spec, interleaved. With the gpu feature, resampling of long wholes runs on a compute shader when an adapter is available, while format encoding always runs on the CPU.
+    pub fn convert_to(&self, spec: TargetSpec) -> Result<ByteBlock, Error> {
+        self.convert_to_with(spec, &mut RunOptions::new())
+    }
+
+    ///Converts like `convert_to`, reporting output frames done and total to options after each block and failing with Error::Cancelled once its cancellation is observed between blocks.
+    pub fn convert_to_with(
+        &self,
+        spec: TargetSpec,
+        options: &mut RunOptions,
+    ) -> Result<ByteBlock, Error> {
+        options.check()?;
+        let x = planar(self);
+        let resampled = spec.sample_rate != self.sample_rate();
+        let y = if resampled {
+            resample_planar(&x, self.sample_rate(), spec.sample_rate, options)?
+        } else {
+            x
+        };
+        let n = y.first().map_or(0, |c| c.len());
+        let width = spec.format.bytes();
+        let mut v = Vec::with_capacity(n * y.len() * width);
+        let mut e = Encoder::new(
+            spec.format,
+            spec.big_endian,
+            spec.dither,
+            spec.scaling,
+            T::CHANNEL_SIZE,
+        );
+        for i in 0..n {
+            for (k, c) in y.iter().enumerate() {
+                e.put(k, c[i], &mut v);
+            }
+            //resampling has reported already.
+            if !resampled && ((i + 1) % PROGRESS_FRAMES == 0 || i + 1 == n) {
+                options.report(i + 1, n);
+                options.check()?;
+            }
+        }
+        Ok(ByteBlock::new(
+            T::CHANNEL_SIZE,
+            T::CHANNEL_SIZE as usize * width,
+            spec.big_endian,
+            v,
+        ))
+    }
+}
+
+///Target of export_bytes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExportSpec {
+    pub format: SampleFormat,
+    pub big_endian: bool,
+    ///Dither of integer formats, ignored for float.
+    pub dither: Dither,
+    ///Scaling of integer formats, ignored for float.
+    pub scaling: ScalingConvention,
+    ///Source channel of each output channel, which may drop or repeat channels, empty for the source order.
+    pub channel_order: Vec<usize>,
+}
+
+impl ExportSpec {
+    ///Constructs a new ExportSpec of the source channel order, without dither, in the Asymmetric scaling convention.
+    pub fn new(format: SampleFormat, big_endian: bool) -> Self {
+        Self {
+            format,
+            big_endian,
+            dither: Dither::None,
+            scaling: ScalingConvention::Asymmetric,
+            channel_order: Vec::new(),
+        }
+    }
+}
+
+///Interleaved bytes exported from a whole.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WholeBytes {
+    sample_rate: SampleRate,
+    channel_size: u16,
+    format: SampleFormat,
+    big_endian: bool,
+    data: Vec<u8>,
+}
+
+impl WholeBytes {
+    ///Returns sample rate.
+    pub fn sample_rate(&self) -> SampleRate {
+        self.sample_rate
+    }
+
+    ///Returns channel size.
+    pub fn channel_size(&self) -> u16 {
+        self.channel_size
+    }
+
+    ///Returns sample format.
+    pub fn format(&self) -> SampleFormat {
+        self.format
+    }
+
+    ///Returns true if bytes are big-endian.
+    pub fn is_big_endian(&self) -> bool {
+        self.big_endian
+    }
+
+    ///Returns number of frames.
+    pub fn frames(&self) -> Frames {
+        Frames(
+            self.data
+                .len()
+                .checked_div(self.channel_size as usize * self.format.bytes())
+                .unwrap_or(0),
+        )
+    }
+
+    ///Converts Self into ByteBlock.
+    pub fn into_byte_block(self) -> ByteBlock {
+        ByteBlock::new(
+            self.channel_size,
+            self.channel_size as usize * self.format.bytes(),
+            self.big_endian,
+            self.data,
+        )
+    }
+}
+
+impl Deref for WholeBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+impl From<WholeBytes> for Vec<u8> {
+    fn from(o: WholeBytes) -> Self {
+        o.data
+    }
+}
+
+impl<T> Whole<T>
+where
+    T: Frame,
+{
+    ///Exports frames as interleaved bytes of spec in one pass of scaling, dither, channel order and byte order, at the same sample rate. Fails with Error::InvalidArgument if an entry of the channel order is not a channel, or with Error::Unsupported if there are more than u16::MAX output channels.
+    pub fn export_bytes(&self, spec: &ExportSpec) -> Result<WholeBytes, Error> {
+        let order: Vec<usize> = if spec.channel_order.is_empty() {
+            (0..T::CHANNEL_SIZE as usize).collect()
+        } else {
+            spec.channel_order.clone()
+        };
+        if order.iter().any(|o| *o >= T::CHANNEL_SIZE as usize) {
+            return Err(Error::InvalidArgument("channel order out of range"));
+        }
+        let channel_size =
+            u16::try_from(order.len()).map_err(|_| Error::Unsupported("too many channels"))?;
+        let mut e = Encoder::new(
+            spec.format,
+            spec.big_endian,
+            spec.dither,
+            spec.scaling,
+            channel_size,
+        );
+        let mut data = Vec::with_capacity(self.len() * order.len() * spec.format.bytes());
+        for f in self.iter() {
+            for (k, c) in order.iter().enumerate() {
+                e.put(k, f.channel(*c), &mut data);
+            }
+        }
+        Ok(WholeBytes {
+            sample_rate: self.sample_rate(),
+            channel_size,
+            format: spec.format,
+            big_endian: spec.big_endian,
+            data,
+        })
+    }
+}
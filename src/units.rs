@@ -0,0 +1,276 @@
+use crate::error::*;
+use crate::format::*;
+use crate::sample::*;
+use crate::util::*;
+use std::ops::{Add, AddAssign, Range, Sub, SubAssign};
+use std::time::Duration;
+
+///A number of frames, each holding one sample of every channel.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Frames(pub usize);
+
+///A number of samples, counting every channel of a frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SampleCount(pub usize);
+
+///A span of time in seconds.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct Seconds(pub f64);
+
+///A sample rate in Hz, validated to lie between MIN and MAX.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SampleRate(u32);
+
+impl SampleRate {
+    ///Lowest valid rate.
+    pub const MIN: u32 = 1;
+    ///Highest valid rate, enough for DSD512.
+    pub const MAX: u32 = 24_576_000;
+
+    pub const HZ_8000: SampleRate = SampleRate(8000);
+    pub const HZ_16000: SampleRate = SampleRate(16000);
+    pub const HZ_44100: SampleRate = SampleRate(44100);
+    pub const HZ_48000: SampleRate = SampleRate(48000);
+    pub const HZ_96000: SampleRate = SampleRate(96000);
+    pub const HZ_192000: SampleRate = SampleRate(192000);
+
+    ///Constructs a new SampleRate, failing if hz is outside MIN and MAX.
+    pub fn new(hz: u32) -> Result<Self, Error> {
+        if (Self::MIN..=Self::MAX).contains(&hz) {
+            Ok(Self(hz))
+        } else {
+            Err(Error::InvalidSampleRate(hz))
+        }
+    }
+
+    ///Returns rate in Hz.
+    pub fn get(self) -> u32 {
+        self.0
+    }
+
+    ///Returns the ratio to other in lowest terms, as self and other divided by their greatest common divisor.
+    pub fn ratio_to(self, other: SampleRate) -> (u32, u32) {
+        let g = gcd(self.0 as u64, other.0 as u64) as u32;
+        (self.0 / g, other.0 / g)
+    }
+
+    ///Returns frames at other of frames at self, rounded up as the resampler does.
+    pub fn convert_frames(self, frames: Frames, other: SampleRate) -> Frames {
+        let (l, m) = self.ratio_to(other);
+        Frames((frames.0 as u64 * m as u64).div_ceil(l as u64) as usize)
+    }
+}
+
+impl TryFrom<u32> for SampleRate {
+    type Error = Error;
+
+    fn try_from(hz: u32) -> Result<Self, Error> {
+        Self::new(hz)
+    }
+}
+
+impl From<SampleRate> for u32 {
+    fn from(o: SampleRate) -> Self {
+        o.0
+    }
+}
+
+impl std::fmt::Display for SampleRate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} Hz", self.0)
+    }
+}
+
+macro_rules! arithmetic {
+    ($t:ty) => {
+        impl Add for $t {
+            type Output = Self;
+
+            fn add(self, o: Self) -> Self {
+                Self(self.0 + o.0)
+            }
+        }
+
+        impl Sub for $t {
+            type Output = Self;
+
+            fn sub(self, o: Self) -> Self {
+                Self(self.0 - o.0)
+            }
+        }
+
+        impl AddAssign for $t {
+            fn add_assign(&mut self, o: Self) {
+                self.0 += o.0;
+            }
+        }
+
+        impl SubAssign for $t {
+            fn sub_assign(&mut self, o: Self) {
+                self.0 -= o.0;
+            }
+        }
+    };
+}
+
+arithmetic!(Frames);
+arithmetic!(SampleCount);
+arithmetic!(Seconds);
+
+impl Frames {
+    ///Returns samples of frames of channel size.
+    pub fn to_samples(self, channel_size: u16) -> SampleCount {
+        SampleCount(self.0 * channel_size as usize)
+    }
+
+    ///Returns bytes of frames of byte size.
+    pub fn to_bytes(self, byte_size: usize) -> usize {
+        self.0 * byte_size
+    }
+
+    ///Returns duration of frames at sample rate, 0 if sample rate is 0.
+    pub fn to_seconds(self, sample_rate: u32) -> Seconds {
+        match sample_rate {
+            0 => Seconds(0.0),
+            sr => Seconds(self.0 as f64 / sr as f64),
+        }
+    }
+}
+
+impl SampleCount {
+    ///Returns whole frames of samples of channel size, 0 if channel size is 0.
+    pub fn to_frames(self, channel_size: u16) -> Frames {
+        Frames(self.0.checked_div(channel_size as usize).unwrap_or(0))
+    }
+}
+
+impl Seconds {
+    ///Returns the nearest frames at sample rate. Negative seconds give 0.
+    pub fn to_frames(self, sample_rate: u32) -> Frames {
+        Frames((self.0.max(0.0) * sample_rate as f64).round() as usize)
+    }
+}
+
+impl From<Frames> for usize {
+    fn from(o: Frames) -> Self {
+        o.0
+    }
+}
+
+impl From<SampleCount> for usize {
+    fn from(o: SampleCount) -> Self {
+        o.0
+    }
+}
+
+impl From<Duration> for Seconds {
+    fn from(o: Duration) -> Self {
+        Self(o.as_secs_f64())
+    }
+}
+
+impl From<Seconds> for Duration {
+    ///Negative or non-finite seconds give zero.
+    fn from(o: Seconds) -> Self {
+        Duration::try_from_secs_f64(o.0.max(0.0)).unwrap_or_default()
+    }
+}
+
+block_impl! {
+    impl<T> Block<T>
+    where
+        T: Sample,
+    {
+        ///Returns number of frames.
+        pub fn frames(&self) -> Frames {
+            Frames(self.len())
+        }
+
+        ///Returns number of samples of all channels.
+        pub fn sample_count(&self) -> SampleCount {
+            self.frames().to_samples(T::CHANNEL_SIZE)
+        }
+
+        ///Returns frames of range, clamped to the end.
+        pub fn slice_frames(&self, range: Range<Frames>) -> &[T] {
+            let end = range.end.0.min(self.len());
+            &self[range.start.0.min(end)..end]
+        }
+    }
+}
+
+impl<T> Whole<T>
+where
+    T: Sample,
+{
+    ///Returns duration.
+    pub fn duration(&self) -> Seconds {
+        Frames(self.len()).to_seconds(self.sample_rate().get())
+    }
+
+    ///Returns frames of range in seconds, each end rounded to the nearest frame and clamped.
+    pub fn slice_seconds(&self, range: Range<Seconds>) -> &[T] {
+        let sr = self.sample_rate().get();
+        let end = range.end.to_frames(sr).0.min(self.len());
+        &self[range.start.to_frames(sr).0.min(end)..end]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_rate_range() {
+        assert!(matches!(
+            SampleRate::new(0),
+            Err(Error::InvalidSampleRate(0))
+        ));
+        let over = SampleRate::MAX + 1;
+        assert!(matches!(SampleRate::new(over), Err(Error::InvalidSampleRate(o)) if o == over));
+        assert_eq!(
+            SampleRate::new(SampleRate::MIN).unwrap().get(),
+            SampleRate::MIN
+        );
+        assert_eq!(
+            SampleRate::new(SampleRate::MAX).unwrap().get(),
+            SampleRate::MAX
+        );
+        for hz in [1, 8000, 44100, 48000, 2_822_400] {
+            let o = SampleRate::try_from(hz).unwrap();
+            assert_eq!(u32::from(o), hz);
+        }
+        assert_eq!(SampleRate::new(44100).unwrap(), SampleRate::HZ_44100);
+        assert_eq!(SampleRate::HZ_48000.to_string(), "48000 Hz");
+    }
+
+    #[test]
+    fn ratio_and_frames() {
+        let (a, b) = (SampleRate::HZ_44100, SampleRate::HZ_48000);
+        assert_eq!(a.ratio_to(b), (147, 160));
+        assert_eq!(b.ratio_to(a), (160, 147));
+        assert_eq!(a.ratio_to(a), (1, 1));
+        assert_eq!(a.convert_frames(Frames(44100), b), Frames(48000));
+        assert_eq!(a.convert_frames(Frames(1), b), Frames(2));
+        assert_eq!(a.convert_frames(Frames(0), b), Frames(0));
+        assert_eq!(b.convert_frames(Frames(160), a), Frames(147));
+        assert_eq!(b.convert_frames(Frames(161), a), Frames(148));
+    }
+
+    #[test]
+    fn seconds_round_trip() {
+        for n in [0, 1, 441, 44100, 1 << 30] {
+            let s = Frames(n).to_seconds(44100);
+            assert_eq!(s.to_frames(44100), Frames(n));
+        }
+        assert_eq!(Frames(24000).to_seconds(48000), Seconds(0.5));
+        assert_eq!(Frames(5).to_seconds(0), Seconds(0.0));
+        assert_eq!(Seconds(-1.0).to_frames(48000), Frames(0));
+        assert_eq!(Frames(3).to_samples(2), SampleCount(6));
+        assert_eq!(SampleCount(7).to_frames(2), Frames(3));
+        assert_eq!(SampleCount(7).to_frames(0), Frames(0));
+        let d = Duration::from(Seconds(1.5));
+        assert_eq!(Seconds::from(d), Seconds(1.5));
+        assert_eq!(Duration::from(Seconds(-1.0)), Duration::ZERO);
+    }
+}
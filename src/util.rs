@@ -0,0 +1,62 @@
+///Returns greatest common divisor.
+pub(crate) fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+///Converts milliseconds to frames.
+pub(crate) fn ms_to_frames(ms: f32, sample_rate: u32) -> usize {
+    (ms.max(0.0) * 0.001 * sample_rate as f32).round() as usize
+}
+
+///Returns one-pole smoothing coefficient of time constant in milliseconds.
+pub(crate) fn time_coef(ms: f32, sample_rate: u32) -> f32 {
+    let n = ms * 0.001 * sample_rate as f32;
+    if n > 0.0 {
+        (-1.0 / n).exp()
+    } else {
+        0.0
+    }
+}
+
+///Quantizes value of full scale 1.0 to bits, rounding to the nearest step.
+pub(crate) fn quantize(v: f32, bits: u32) -> f32 {
+    let s = (1u64 << (bits.clamp(1, 32) - 1)) as f64;
+    ((v as f64 * s).round().clamp(-s, s - 1.0) / s) as f32
+}
+
+///A seedable SplitMix64 pseudo random number generator.
+#[derive(Clone, Debug)]
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    ///Returns uniform value in [0, 1).
+    pub fn uniform(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    ///Returns standard normal value.
+    pub fn gaussian(&mut self) -> f64 {
+        let u = 1.0 - self.uniform();
+        let v = self.uniform();
+        (-2.0 * u.ln()).sqrt() * (std::f64::consts::TAU * v).cos()
+    }
+}
+
+///Frames between progress reports of long-running operations.
+pub(crate) const PROGRESS_FRAMES: usize = 1 << 14;
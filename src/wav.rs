@@ -0,0 +1,214 @@
+//!Synchronous WAV reading, with decoding of the data chunk split across threads.
+
+use crate::cancel::*;
+use crate::error::*;
+use crate::format::*;
+use crate::sample::*;
+use crate::units::SampleRate;
+use std::fs::File;
+use std::io::{ErrorKind, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::Mutex;
+
+//frames decoded per read of a worker.
+const READ_FRAMES: usize = 1 << 14;
+
+//below this many frames a range is not worth a thread.
+const MIN_FRAMES: usize = 1 << 16;
+
+//largest fmt chunk accepted, far above the 40 bytes of WAVE_FORMAT_EXTENSIBLE.
+pub(crate) const MAX_FMT: u64 = 1 << 16;
+
+///Format of WAV data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WavSpec {
+    pub sample_rate: u32,
+    pub channel_size: u16,
+    ///Bits of a sample.
+    pub bits: u16,
+    ///True for IEEE float, false for integer PCM.
+    pub float: bool,
+}
+
+impl WavSpec {
+    ///Returns bytes of a frame.
+    pub fn byte_size(&self) -> usize {
+        self.channel_size as usize * self.bits.div_ceil(8) as usize
+    }
+}
+
+///Parses the body of a fmt chunk.
+pub(crate) fn parse_fmt(f: &[u8]) -> Result<WavSpec, Error> {
+    if f.len() < 16 {
+        return Err(Error::InvalidData("short fmt chunk"));
+    }
+    let u16_at = |i: usize| u16::from_le_bytes([f[i], f[i + 1]]);
+    let mut tag = u16_at(0);
+    if tag == 0xFFFE && f.len() >= 26 {
+        tag = u16_at(24);
+    }
+    Ok(WavSpec {
+        sample_rate: u32::from_le_bytes([f[4], f[5], f[6], f[7]]),
+        channel_size: u16_at(2),
+        bits: u16_at(14),
+        float: match tag {
+            1 => false,
+            3 => true,
+            _ => return Err(Error::Unsupported("WAV format")),
+        },
+    })
+}
+
+///Reads headers up to the data chunk. Returns format and data length, None if unknown.
+pub fn read_header<R: Read>(r: &mut R) -> Result<(WavSpec, Option<u64>), Error> {
+    let mut h = [0u8; 12];
+    r.read_exact(&mut h)?;
+    if &h[..4] != b"RIFF" || &h[8..] != b"WAVE" {
+        return Err(Error::InvalidData("not a RIFF WAVE"));
+    }
+    let mut spec = None;
+    loop {
+        let mut c = [0u8; 8];
+        r.read_exact(&mut c)?;
+        let size = u32::from_le_bytes([c[4], c[5], c[6], c[7]]);
+        let n = size as u64 + (size & 1) as u64;
+        match &c[..4] {
+            b"fmt " => {
+                //the size is untrusted, so the buffer grows only as bytes arrive.
+                if n > MAX_FMT {
+                    return Err(Error::InvalidData("oversized fmt chunk"));
+                }
+                let mut f = Vec::new();
+                r.take(n).read_to_end(&mut f)?;
+                spec = Some(parse_fmt(&f)?);
+            }
+            b"data" => {
+                let spec = spec.ok_or(Error::InvalidData("data before fmt chunk"))?;
+                //0 and u32::MAX are written by streaming writers.
+                return Ok((spec, (size != 0 && size != u32::MAX).then_some(size as u64)));
+            }
+            _ => {
+                if std::io::copy(&mut r.take(n), &mut std::io::sink())? < n {
+                    return Err(Error::Io(ErrorKind::UnexpectedEof.into()));
+                }
+            }
+        }
+    }
+}
+
+//returns the decoder of a little-endian sample of spec to full scale 1.0.
+fn sample_fn(spec: WavSpec) -> Option<fn(&[u8]) -> f32> {
+    let f: fn(&[u8]) -> f32 = match (spec.float, spec.bits.div_ceil(8)) {
+        (false, 1) => |b| b[0].to_unit() as f32,
+        (false, 2) => |b| i16::from_le_bytes([b[0], b[1]]).to_unit() as f32,
+        (false, 3) => |b| i32::from_le_bytes([0, b[0], b[1], b[2]]).to_unit() as f32,
+        (false, 4) => |b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]).to_unit() as f32,
+        (true, 4) => |b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+        (true, 8) => {
+            |b| f64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]) as f32
+        }
+        _ => return None,
+    };
+    Some(f)
+}
+
+//reads and decodes frames of o from offset of a file of its own.
+fn decode_range<T: Frame>(
+    path: &Path,
+    offset: u64,
+    spec: WavSpec,
+    get: fn(&[u8]) -> f32,
+    o: &mut [T],
+    cancel: &CancelToken,
+    report: &(dyn Fn(usize) + Sync),
+) -> Result<(), Error> {
+    let mut f = File::open(path)?;
+    f.seek(SeekFrom::Start(offset))?;
+    let byte_size = spec.byte_size();
+    let width = byte_size / spec.channel_size as usize;
+    let mut buf = vec![0u8; byte_size * READ_FRAMES.min(o.len())];
+    for o in o.chunks_mut(READ_FRAMES) {
+        cancel.check()?;
+        let b = &mut buf[..o.len() * byte_size];
+        f.read_exact(b)?;
+        for (i, b) in o.iter_mut().zip(b.chunks_exact(byte_size)) {
+            for (c, s) in b.chunks_exact(width).enumerate() {
+                i.set_channel(c, get(s));
+            }
+        }
+        report(o.len());
+    }
+    Ok(())
+}
+
+///Reads a WAV file into a whole, converting samples to full scale 1.0. The channel size of T must match the file, and its sample rate must be valid.
+pub fn read<T: Frame + Send>(path: impl AsRef<Path>) -> Result<Whole<T>, Error> {
+    read_parallel(path, 1)
+}
+
+///Reads a WAV file into a whole like `read`, splitting the data chunk into ranges of frames decoded on threads and stitched in place. Threads of 0 means the available parallelism.
+pub fn read_parallel<T: Frame + Send>(
+    path: impl AsRef<Path>,
+    threads: usize,
+) -> Result<Whole<T>, Error> {
+    read_parallel_with(path, threads, &mut RunOptions::new())
+}
+
+///Reads like `read_parallel`, reporting frames decoded and total to options after each read of any thread. Once its cancellation is observed between reads, all threads stop and Err(Error::Cancelled) is returned.
+pub fn read_parallel_with<T: Frame + Send>(
+    path: impl AsRef<Path>,
+    threads: usize,
+    options: &mut RunOptions,
+) -> Result<Whole<T>, Error> {
+    let path = path.as_ref();
+    let mut f = File::open(path)?;
+    let (spec, size) = read_header(&mut f)?;
+    if spec.channel_size != T::CHANNEL_SIZE {
+        return Err(Error::LayoutMismatch {
+            expected: (T::CHANNEL_SIZE, T::BYTE_SIZE),
+            found: (spec.channel_size, spec.byte_size()),
+        });
+    }
+    let sample_rate = SampleRate::new(spec.sample_rate)?;
+    let get = sample_fn(spec).ok_or(Error::Unsupported("WAV sample format"))?;
+    let offset = f.stream_position()?;
+    let available = f.metadata()?.len().saturating_sub(offset);
+    drop(f);
+    let byte_size = spec.byte_size() as u64;
+    let n = (size.map_or(available, |o| o.min(available)) / byte_size) as usize;
+    let threads = match threads {
+        0 => std::thread::available_parallelism().map_or(1, |o| o.get()),
+        o => o,
+    };
+    let threads = threads.min(n.div_ceil(MIN_FRAMES)).max(1);
+    let cancel = options.cancel().clone();
+    let cancel = &cancel;
+    let progress = Mutex::new((0, options));
+    let report = |k: usize| {
+        let mut g = progress.lock().unwrap_or_else(|o| o.into_inner());
+        g.0 += k;
+        let done = g.0;
+        g.1.report(done, n);
+    };
+    let mut v = vec![T::default(); n];
+    if threads == 1 {
+        decode_range(path, offset, spec, get, &mut v, cancel, &report)?;
+    } else {
+        let per = n.div_ceil(threads);
+        std::thread::scope(|s| {
+            let handles: Vec<_> = v
+                .chunks_mut(per)
+                .enumerate()
+                .map(|(i, o)| {
+                    let offset = offset + (i * per) as u64 * byte_size;
+                    let report = &report;
+                    s.spawn(move || decode_range(path, offset, spec, get, o, cancel, report))
+                })
+                .collect();
+            handles
+                .into_iter()
+                .try_for_each(|h| h.join().expect("decoding thread panicked"))
+        })?;
+    }
+    Ok(Whole::from_block(sample_rate, Block::from(v)))
+}
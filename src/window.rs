@@ -0,0 +1,27 @@
+use std::f64::consts::PI;
+
+///Window function of spectral analysis.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Window {
+    Rectangular,
+    Hann,
+    Hamming,
+    Blackman,
+}
+
+impl Window {
+    ///Returns n periodic window coefficients.
+    pub fn coefficients(self, n: usize) -> Vec<f32> {
+        (0..n)
+            .map(|i| {
+                let a = 2.0 * PI * i as f64 / n as f64;
+                (match self {
+                    Self::Rectangular => 1.0,
+                    Self::Hann => 0.5 - 0.5 * a.cos(),
+                    Self::Hamming => 0.54 - 0.46 * a.cos(),
+                    Self::Blackman => 0.42 - 0.5 * a.cos() + 0.08 * (2.0 * a).cos(),
+                }) as f32
+            })
+            .collect()
+    }
+}